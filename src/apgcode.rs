@@ -0,0 +1,182 @@
+use std::fmt;
+
+use crate::{rle::{MAX_CELLS, MAX_SIDE}, Cell, Coord, Pattern, PlaneCoord, Rule, SparseUniverse};
+
+/// Digits of the extended Wechsler format, each one a column of 5 cells with the top one as the lowest bit.
+/// After a `y` they count runs of 4 and more empty columns instead, up to `z`.
+const DIGITS: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+/// Longest period `to_apgcode` looks for
+const MAX_PERIOD: usize = 1024;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseApgcodeError {
+    /// Only still lifes (`xs`), oscillators (`xp`) and spaceships (`xq`) are encoded as patterns
+    UnsupportedPrefix(String),
+    /// A character that isn't part of the extended Wechsler format
+    InvalidCharacter(char),
+    /// The live cells span more than `MAX_SIDE` to a side, or `MAX_CELLS` in all
+    TooLarge,
+}
+
+impl Pattern {
+    /// The apgcode the pattern goes by under `rule`: `xs` and its population for still lifes, `xp` and the period
+    /// for oscillators and `xq` and the period for spaceships, followed by the smallest encoding of any of its
+    /// phases and orientations. `None` for anything else, such as patterns that die out, grow or take longer
+    /// than 1024 generations to come back. Runs on an unbounded plane, so only life-like rules count.
+    pub fn to_apgcode(&self, rule: Rule) -> Option<String> {
+        let mut plane = SparseUniverse::new();
+        plane.set_rule(rule);
+        for (c, cell) in self.iter_cells() { plane.set_pixel(c.into(), cell); }
+        let (start, first) = plane_pattern(&plane)?;
+
+        let mut phases = vec![first.clone()];
+        for period in 1..=MAX_PERIOD {
+            plane.tick();
+            let (top_left, phase) = plane_pattern(&plane)?;
+            if phase == first {
+                let prefix = match (top_left == start, period) {
+                    (true, 1)  => format!("xs{}", first.population()),
+                    (true, _)  => format!("xp{period}"),
+                    (false, _) => format!("xq{period}"),
+                };
+                let body = phases.iter().flat_map(orientations).map(|p| p.to_wechsler())
+                    .min_by(|a, b| a.len().cmp(&b.len()).then_with(|| a.cmp(b)))
+                    .unwrap_or_default();
+                return Some(format!("{prefix}_{body}"));
+            }
+            phases.push(phase);
+        }
+        None
+    }
+
+    /// Reads the pattern out of an `xs`, `xp` or `xq` apgcode (such as `xq4_153`, a glider), trimmed
+    pub fn from_apgcode(code: &str) -> Result<Pattern, ParseApgcodeError> {
+        let unsupported = || ParseApgcodeError::UnsupportedPrefix(code.split('_').next().unwrap_or_default().to_owned());
+        let (prefix, body) = code.trim().split_once('_').ok_or_else(unsupported)?;
+        let kind = prefix.strip_prefix("xs").or(prefix.strip_prefix("xp")).or(prefix.strip_prefix("xq"));
+        if !kind.is_some_and(|n| !n.is_empty() && n.bytes().all(|b| b.is_ascii_digit())) { return Err(unsupported()); }
+        Pattern::from_wechsler(body)
+    }
+
+    /// The pattern, trimmed, in the extended Wechsler format that apgcodes use after their prefix: strips of 5
+    /// rows split by `z`, each one a digit per column, with `w` and `x` for 2 and 3 empty columns and `y`
+    /// and a digit for 4 and more. Empty columns ending a strip are left out.
+    pub fn to_wechsler(&self) -> String {
+        let pattern = self.trim();
+        let strips = (0..pattern.get_height()).step_by(5).map(|top| {
+            let mut columns = (0..pattern.get_width()).map(|col| {
+                (top..(top + 5).min(pattern.get_height()))
+                    .filter(|&row| pattern.is_alive(Coord::new(row, col)))
+                    .fold(0, |digit, row| digit | 1 << (row - top))
+            }).collect::<Vec<_>>();
+            while columns.last() == Some(&0) { columns.pop(); }
+
+            let mut strip = String::new();
+            let mut empty = 0;
+            for digit in columns {
+                if digit == 0 { empty += 1; continue; }
+                while empty > 0 {
+                    let run = empty.min(39);
+                    match run {
+                        1 => strip.push('0'),
+                        2 => strip.push('w'),
+                        3 => strip.push('x'),
+                        _ => { strip.push('y'); strip.push(DIGITS[run - 4] as char); }
+                    }
+                    empty -= run;
+                }
+                strip.push(DIGITS[digit] as char);
+            }
+            strip
+        });
+        strips.collect::<Vec<_>>().join("z")
+    }
+
+    /// Reads a pattern in the extended Wechsler format (see `to_wechsler`), trimmed
+    pub fn from_wechsler(s: &str) -> Result<Pattern, ParseApgcodeError> {
+        // Live cells as (row, column)
+        let mut cells = Vec::new();
+        let (mut top, mut col) = (0, 0);
+        let mut chars = s.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                'w' => col += 2,
+                'x' => col += 3,
+                'y' => {
+                    let run = chars.next().and_then(|c| c.to_digit(36)).ok_or(ParseApgcodeError::InvalidCharacter('y'))?;
+                    col += 4 + run as usize;
+                }
+                'z' => (top, col) = (top + 5, 0),
+                _   => {
+                    let digit = c.to_digit(32).filter(|_| c.is_ascii_digit() || c.is_ascii_lowercase())
+                        .ok_or(ParseApgcodeError::InvalidCharacter(c))?;
+                    cells.extend((0..5).filter(|row| digit & 1 << row != 0).map(|row| (top + row, col)));
+                    col += 1;
+                }
+            }
+        }
+
+        if cells.is_empty() { return Ok(Pattern::new(0, 0)); }
+        // Only as big as the live cells, however far empty strips and columns push them
+        let extent = |along: fn(&(usize, usize)) -> usize| {
+            let (min, max) = (cells.iter().map(along).min().unwrap_or(0), cells.iter().map(along).max().unwrap_or(0));
+            (min, max - min + 1)
+        };
+        let ((top, height), (left, width)) = (extent(|&(row, _)| row), extent(|&(_, col)| col));
+        if height.max(width) > MAX_SIDE || height * width > MAX_CELLS { return Err(ParseApgcodeError::TooLarge); }
+        let mut pattern = Pattern::new(height, width);
+        for (row, col) in cells { pattern.set(Coord::new(row - top, col - left), Cell::Alive); }
+        Ok(pattern)
+    }
+}
+
+/// Top-left corner of the live cells on the plane and the pattern they make, if there are any
+fn plane_pattern(plane: &SparseUniverse) -> Option<(PlaneCoord, Pattern)> {
+    let (top_left, bottom_right) = plane.bounding_box()?;
+    let (height, width) = ((bottom_right.row - top_left.row + 1) as usize, (bottom_right.col - top_left.col + 1) as usize);
+    Some((top_left, Pattern::from(&plane.to_universe(top_left, height, width))))
+}
+
+/// The 8 ways to turn and mirror a pattern
+fn orientations(pattern: &Pattern) -> Vec<Pattern> {
+    [pattern.clone(), pattern.rotate_cw(), pattern.rotate_cw().rotate_cw(), pattern.rotate_ccw()].into_iter()
+        .flat_map(|turned| { let mirrored = turned.flip_h(); [turned, mirrored] })
+        .collect()
+}
+
+impl fmt::Display for ParseApgcodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseApgcodeError::UnsupportedPrefix(prefix) => write!(f, "the {prefix} prefix isn't supported"),
+            ParseApgcodeError::InvalidCharacter(c)       => write!(f, "unexpected character {c:?}"),
+            ParseApgcodeError::TooLarge                  => write!(f, "the pattern is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseApgcodeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_a_glider() {
+        let glider = Pattern::from_apgcode("xq4_153").unwrap();
+        assert_eq!((glider.get_height(), glider.get_width(), glider.population()), (3, 3, 5));
+        assert_eq!(glider.to_wechsler(), "153");
+    }
+
+    #[test]
+    fn only_allocates_what_the_live_cells_span() {
+        // Far from the corner, but one cell all the same
+        let far = format!("xs1_{}1", "yz".repeat(1000));
+        let cell = Pattern::from_apgcode(&far).unwrap();
+        assert_eq!((cell.get_height(), cell.get_width()), (1, 1));
+
+        let wide = format!("xs2_1{}1", "yz".repeat(2000));
+        assert_eq!(Pattern::from_apgcode(&wide), Err(ParseApgcodeError::TooLarge));
+        let tall = format!("xs2_1{}1", "z".repeat(MAX_SIDE / 5 + 1));
+        assert_eq!(Pattern::from_apgcode(&tall), Err(ParseApgcodeError::TooLarge));
+    }
+}
@@ -0,0 +1,99 @@
+use crate::{rng::SplitMix64, Coord, Universe};
+
+/// Colored variants, whose live cells carry one of a few colors. Colors get passed on to newborn cells, without
+/// ever changing what lives or dies.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Coloring {
+    /// Two colors, newborns taking the one most of their live neighbors have
+    Immigration,
+    /// Four colors, newborns taking the one most of their live neighbors have or, when they're all different,
+    /// the one none of them has
+    QuadLife,
+}
+
+impl Coloring {
+    pub fn colors(&self) -> u8 {
+        match self {
+            Coloring::Immigration => 2,
+            Coloring::QuadLife    => 4,
+        }
+    }
+
+    /// Color of a newborn, given how many of its live neighbors have each color. Ties go to the lowest color.
+    fn inherit(&self, counts: [u16; 4]) -> u8 {
+        let counts = &counts[..self.colors() as usize];
+        let most = counts.iter().copied().max().unwrap_or(0);
+        let first = |n: u16| counts.iter().position(|&count| count == n).unwrap_or(0) as u8;
+        let tied = counts.iter().filter(|&&count| count == most).count();
+        let absent = counts.iter().filter(|&&count| count == 0).count();
+        match (self, tied, absent) {
+            (_, 1, _)                  => first(most),
+            (Coloring::QuadLife, _, 1) => first(0),
+            _                          => first(most),
+        }
+    }
+}
+
+impl Universe {
+    pub fn get_coloring(&self) -> Option<Coloring> { self.coloring }
+    pub fn get_paint(&self) -> u8                  { self.paint }
+
+    /// Turns a colored variant on (or off with `None`). Cells keep whatever color they had, as far as it fits.
+    pub fn set_coloring(&mut self, coloring: Option<Coloring>) {
+        self.coloring = coloring;
+        if let Some(coloring) = coloring {
+            for color in self.colors.iter_mut() { *color %= coloring.colors(); }
+            self.paint %= coloring.colors();
+        }
+        // Colors aren't kept track of by tile, and these may all have changed
+        self.mark_all_dirty();
+    }
+
+    /// Color given to the live cells placed with `set_pixel`
+    pub fn set_paint(&mut self, color: u8) {
+        self.paint = self.coloring.map_or(0, |coloring| color % coloring.colors());
+    }
+
+    /// Color of the cell, 0 unless a colored variant is on
+    pub fn color_at(&self, c: Coord) -> u8 {
+        if self.coloring.is_some() { self.colors[self.coord_to_idx(c)] } else { 0 }
+    }
+
+    pub fn set_color(&mut self, c: Coord, color: u8) {
+        let Some(coloring) = self.coloring else { return };
+        let i = self.coord_to_idx(c);
+        self.colors[i] = color % coloring.colors();
+    }
+
+    /// Gives every live cell a random color
+    pub(crate) fn scatter_colors(&mut self, seed: u64) {
+        let Some(coloring) = self.coloring else { return };
+        let mut rng = SplitMix64::new(!seed);
+        for (color, cell) in self.colors.iter_mut().zip(&self.cells) {
+            if cell.is_alive() { *color = (rng.next_u64() % coloring.colors() as u64) as u8; }
+        }
+    }
+
+    /// After a tick, with the previous generation in the back buffer, colors the cells that were just born.
+    /// Their live neighbors were alive last generation too, so their colors are still there to read.
+    pub(crate) fn update_colors(&mut self) {
+        let Some(coloring) = self.coloring else { return };
+        let neighborhood = self.rule.neighborhood();
+        // Hex neighborhoods differ between even and odd rows
+        let offsets = [neighborhood.all_offsets(0), neighborhood.all_offsets(1)];
+
+        for i in 0..self.cells.len() {
+            if !self.cells[i].is_alive() || self.back_buffer[i].is_alive() { continue; }
+            let c = self.idx_to_coords(i);
+            let mut counts = [0; 4];
+            for &(dy, dx) in &offsets[c.row % 2] {
+                let y = self.boundary.resolve(c.row as isize + dy, self.height);
+                let x = self.col_boundary.resolve(c.col as isize + dx, self.width);
+                let (Some(y), Some(x)) = (y, x) else { continue };
+                let j = y * self.width + x;
+                if self.back_buffer[j].is_alive() { counts[self.colors[j] as usize % 4] += 1; }
+            }
+            self.colors[i] = coloring.inherit(counts);
+        }
+    }
+}
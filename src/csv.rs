@@ -0,0 +1,16 @@
+use crate::Universe;
+
+impl Universe {
+    /// The grid as comma separated values, a line per row and each cell as its state (see `Cell::state` and
+    /// `Universe::conductor_state`), so plain 0 and 1 under two-state rules
+    pub fn to_csv(&self) -> String {
+        let mut out = String::with_capacity(self.height * (self.width * 2 + 1));
+        let conductor = self.conductor_state();
+        for row in self.cells.chunks(self.width.max(1)) {
+            let line = row.iter().map(|cell| cell.state(conductor).to_string()).collect::<Vec<_>>().join(",");
+            out.push_str(&line);
+            out.push('\n');
+        }
+        out
+    }
+}
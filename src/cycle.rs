@@ -0,0 +1,104 @@
+use std::{collections::{HashMap, VecDeque}, hash::{DefaultHasher, Hash, Hasher}};
+
+use crate::{Cell, Universe};
+
+/// A state that came back: the universe has been repeating with this period since generation `since`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    pub period: u64,
+    pub since: u64,
+}
+
+impl Cycle {
+    /// Period 1 means nothing changes anymore
+    pub fn is_still(&self) -> bool { self.period == 1 }
+}
+
+/// Remembers the hashes of the last `window` states it was shown, to notice when one repeats
+#[derive(Clone, Debug)]
+pub struct LoopDetector {
+    /// State hash => generation it was seen at
+    seen: HashMap<u64, u64>,
+    order: VecDeque<u64>,
+    window: usize,
+}
+
+impl LoopDetector {
+    pub fn new(window: usize) -> Self {
+        let capacity = window.min(1 << 12);
+        Self { seen: HashMap::with_capacity(capacity), order: VecDeque::with_capacity(capacity), window }
+    }
+
+    pub fn clear(&mut self) {
+        self.seen.clear();
+        self.order.clear();
+    }
+
+    /// Records the current state, returning the cycle it closes if it was seen before
+    pub fn observe(&mut self, universe: &Universe) -> Option<Cycle> {
+        let mut hasher = DefaultHasher::new();
+        universe.hash(&mut hasher);
+        let hash = hasher.finish();
+        let generation = universe.get_generation();
+
+        if let Some(&since) = self.seen.get(&hash) {
+            if since < generation { return Some(Cycle { period: generation - since, since }); }
+        }
+
+        if self.window == 0 { return None; }
+        if self.order.len() == self.window {
+            if let Some(oldest) = self.order.pop_front() { self.seen.remove(&oldest); }
+        }
+        self.order.push_back(hash);
+        self.seen.insert(hash, generation);
+        None
+    }
+}
+
+/// How `Universe::run_until_stable` ended, along with the generation it stopped at
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// Every cell is dead
+    Extinct { generation: u64 },
+    /// The universe settled into a still life or oscillator
+    Stable { cycle: Cycle, generation: u64 },
+    /// Still going when the generation cap was hit
+    Unsettled { generation: u64 },
+}
+
+impl Outcome {
+    pub fn generation(&self) -> u64 {
+        match *self {
+            Outcome::Extinct { generation } | Outcome::Stable { generation, .. } | Outcome::Unsettled { generation } => generation,
+        }
+    }
+
+    /// Period of the cycle it settled into, if it did
+    pub fn period(&self) -> Option<u64> {
+        match self {
+            Outcome::Stable { cycle, .. } => Some(cycle.period),
+            _                             => None,
+        }
+    }
+}
+
+impl Universe {
+    /// Ticks until the universe dies out or starts repeating itself, for at most `max_gens` generations
+    pub fn run_until_stable(&mut self, max_gens: u64) -> Outcome {
+        let mut detector = LoopDetector::new(max_gens.saturating_add(1).try_into().unwrap_or(usize::MAX));
+        detector.observe(self);
+
+        for _ in 0..max_gens {
+            if self.cells.iter().all(|&c| c == Cell::Dead) { return Outcome::Extinct { generation: self.get_generation() }; }
+            self.tick();
+            if let Some(cycle) = detector.observe(self) {
+                return Outcome::Stable { cycle, generation: self.get_generation() };
+            }
+        }
+
+        match self.cells.iter().all(|&c| c == Cell::Dead) {
+            true  => Outcome::Extinct { generation: self.get_generation() },
+            false => Outcome::Unsettled { generation: self.get_generation() },
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use crate::{Cell, Universe};
+
+/// Whether a cell is alive next generation in the elementary automaton numbered `rule` (Wolfram's code), which
+/// is bit `4 * left + 2 * center + right` of the number
+fn lives(rule: u8, left: bool, center: bool, right: bool) -> bool {
+    rule >> (4 * left as u8 + 2 * center as u8 + right as u8) & 1 == 1
+}
+
+impl Universe {
+    pub fn get_elementary_rule(&self) -> Option<u8> { self.elementary }
+
+    /// Runs the universe as a space-time diagram of the elementary (one-dimensional, two-state) automaton
+    /// numbered `rule`, such as 30 or 110, instead of its `Rule`. The last row is the current generation: every
+    /// tick the rows move up by one, the first one falling off, and the last one gets the next generation of
+    /// the one before it. Lasts until the next `set_rule`, or `None` to go back to it.
+    pub fn set_elementary_rule(&mut self, rule: Option<u8>) {
+        self.elementary = rule;
+        if rule.is_some() {
+            self.table = None;
+            self.block_rule = None;
+            self.custom_rule = None;
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Writes into `next` the diagram a generation later by `rule`, see `set_elementary_rule`
+    pub(crate) fn tick_elementary(&self, rule: u8, next: &mut [Cell]) {
+        let w = self.width;
+        if self.height == 0 || w == 0 { return; }
+        let last = (self.height - 1) * w;
+        next[..last].copy_from_slice(&self.cells[w..]);
+
+        let line = &self.cells[last..];
+        let alive_at = |x: isize| self.col_boundary.resolve(x, w).is_some_and(|x| line[x].is_alive());
+        for (x, cell) in next[last..].iter_mut().enumerate() {
+            let x = x as isize;
+            *cell = match lives(rule, alive_at(x - 1), alive_at(x), alive_at(x + 1)) {
+                true  => Cell::Alive,
+                false => Cell::Dead,
+            };
+        }
+    }
+}
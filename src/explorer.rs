@@ -0,0 +1,80 @@
+use crate::{rng::SplitMix64, LoopDetector, Rule, Universe};
+
+/// Side of the soups rules are tried on
+const SOUP_SIZE: usize = 48;
+/// Generations each soup gets to run for
+const SOUP_GENERATIONS: u64 = 120;
+
+/// A rule that was tried, along with how interesting it turned out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Candidate {
+    pub rule: Rule,
+    /// From 0 (died out, exploded or froze) up to 1
+    pub score: f32,
+}
+
+/// Tries out random life-like rules one at a time, holding on to the best ones found so far
+#[derive(Clone, Debug)]
+pub struct RuleExplorer {
+    rng: SplitMix64,
+    /// Best first
+    candidates: Vec<Candidate>,
+    keep: usize,
+    tried: usize,
+}
+
+impl RuleExplorer {
+    /// Explores starting from `seed`, keeping the `keep` best candidates
+    pub fn new(seed: u64, keep: usize) -> Self {
+        Self { rng: SplitMix64::new(seed), candidates: Vec::new(), keep, tried: 0 }
+    }
+
+    pub fn candidates(&self) -> &[Candidate] { &self.candidates }
+    pub fn get_tried(&self) -> usize         { self.tried }
+
+    /// Makes up a rule (never with B0, which would fill the soup right away), tries it and returns how it did
+    pub fn try_one(&mut self) -> Candidate {
+        let rule = loop {
+            let mut counts = |chance: f32, first: u8| (first..=8).filter(|_| self.rng.next_f32() < chance).collect::<Vec<_>>();
+            let (birth, survival) = (counts(0.25, 1), counts(0.4, 0));
+            let rule = Rule::new(&birth, &survival);
+            if !birth.is_empty() && !self.candidates.iter().any(|c| c.rule == rule) { break rule; }
+        };
+
+        let candidate = Candidate { rule, score: interestingness(rule, self.rng.next_u64()) };
+        self.tried += 1;
+        let at = self.candidates.partition_point(|c| c.score >= candidate.score);
+        self.candidates.insert(at, candidate);
+        self.candidates.truncate(self.keep);
+        candidate
+    }
+}
+
+/// Runs a soup (`seed` deciding which) by `rule` and scores how interesting it is, from 0 to 1. Soups that die
+/// out, fill up, or settle into still lifes and short oscillators score 0, and the highest scores go to ones
+/// that stay sparse while a few percent of their cells keep changing.
+pub fn interestingness(rule: Rule, seed: u64) -> f32 {
+    let mut universe = Universe::new(SOUP_SIZE, SOUP_SIZE);
+    universe.set_rule(rule);
+    universe.randomize(0.5, seed);
+    let mut detector = LoopDetector::new(16);
+    let mut changes = 0;
+
+    for generation in 0..SOUP_GENERATIONS {
+        let before = universe.cells.clone();
+        universe.tick();
+        if detector.observe(&universe).is_some() { return 0.0; }
+        // Only the last quarter counts, once the soup has had time to settle
+        if generation >= SOUP_GENERATIONS * 3 / 4 {
+            changes += before.iter().zip(&universe.cells).filter(|(a, b)| a != b).count();
+        }
+    }
+
+    let cells = (SOUP_SIZE * SOUP_SIZE) as f32;
+    let density = universe.population() as f32 / cells;
+    let activity = changes as f32 / (cells * (SOUP_GENERATIONS / 4) as f32);
+    if density == 0.0 || activity == 0.0 { return 0.0; }
+    // Bell curves over the logarithms, peaking at 15% of the cells alive and 3% changing every generation
+    let bell = |x: f32, peak: f32| (-(x / peak).ln().powi(2) / 2.0).exp();
+    bell(density, 0.15) * bell(activity, 0.03)
+}
@@ -0,0 +1,168 @@
+use std::{collections::HashMap, io, path::Path};
+
+use crate::{Cell, Coord, Palette, Universe};
+
+/// Codes never get longer than this many bits, as the format has it
+const MAX_CODE_SIZE: u32 = 12;
+
+/// Records generations of a universe as they go by, to be written out as a looping animated GIF
+#[derive(Clone, Debug)]
+pub struct GifRecorder {
+    cell_size: usize,
+    palette: Palette,
+    /// Hundredths of a second each frame stays on
+    delay: u16,
+    /// Generations between frames
+    every: u64,
+    transposed: bool,
+    /// (height, width) in cells of every frame, set by the first one
+    size: Option<Coord>,
+    /// Each frame's cells, row by row, as indices into the color table
+    frames: Vec<Vec<u8>>,
+    last_generation: Option<u64>,
+}
+
+impl GifRecorder {
+    /// Draws every cell as a `cell_size` pixels square in its `palette` color (decaying ones all alike, and
+    /// blended over dead cells by their alpha, since GIFs have no translucency), each frame staying on for
+    /// `delay` hundredths of a second
+    pub fn new(cell_size: usize, palette: Palette, delay: u16) -> Self {
+        Self { cell_size: cell_size.max(1), palette, delay, every: 1, transposed: false, size: None, frames: Vec::new(),
+               last_generation: None }
+    }
+
+    pub fn get_every(&self) -> u64              { self.every }
+    /// Only captures the generations that are a multiple of `every`
+    pub fn set_every(&mut self, every: u64)     { self.every = every.max(1) }
+    pub fn is_transposed(&self) -> bool         { self.transposed }
+    /// Swaps rows and columns, for frontends drawing rows across instead of down
+    pub fn set_transposed(&mut self, t: bool)   { self.transposed = t }
+    pub fn frame_count(&self) -> usize          { self.frames.len() }
+
+    /// Adds a frame of the universe, unless its generation isn't one to capture or was just captured. The first
+    /// frame sets the size of the rest, and universes that have since been resized are left out.
+    pub fn capture(&mut self, universe: &Universe) {
+        let generation = universe.get_generation();
+        if !generation.is_multiple_of(self.every) || self.last_generation == Some(generation) { return; }
+        let size = Coord::new(universe.get_height(), universe.get_width());
+        if *self.size.get_or_insert(size) != size { return; }
+
+        self.last_generation = Some(generation);
+        self.frames.push(universe.iter_cells().map(|(_, cell)| match cell {
+            Cell::Dead      => 0,
+            Cell::Alive     => 1,
+            Cell::Dying(_)  => 2,
+            Cell::Conductor => 3,
+        }).collect());
+    }
+
+    /// The recording as a GIF that loops forever, unless its frames would be wider or taller than the 65535
+    /// pixels GIFs go up to
+    pub fn encode(&self) -> io::Result<Vec<u8>> {
+        let size = self.size.unwrap_or(Coord::new(0, 0));
+        let (rows, cols) = if self.transposed { (size.col, size.row) } else { (size.row, size.col) };
+        let pixels = |cells: usize| cells.checked_mul(self.cell_size).and_then(|n| u16::try_from(n).ok())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "the frames are too big for a GIF"));
+        let (height, width) = (pixels(rows)?, pixels(cols)?);
+
+        let mut out = b"GIF89a".to_vec();
+        out.extend(width.to_le_bytes());
+        out.extend(height.to_le_bytes());
+        // A global color table of 4 entries, with the first one as the background
+        out.extend([0x91, 0, 0]);
+        let Palette { dead, alive, dying, conductor } = self.palette;
+        for color in [dead, alive, dying, conductor] {
+            out.extend((0..3).map(|c| (dead[c] as u32 * (255 - color[3] as u32) / 255 + color[c] as u32 * color[3] as u32 / 255) as u8));
+        }
+        // Netscape's extension, looping forever
+        out.extend([0x21, 0xFF, 0x0B]);
+        out.extend(b"NETSCAPE2.0");
+        out.extend([0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        for frame in &self.frames {
+            out.extend([0x21, 0xF9, 0x04, 0x00]);
+            out.extend(self.delay.to_le_bytes());
+            out.extend([0x00, 0x00]);
+            out.push(0x2C);
+            out.extend([0, 0, 0, 0]);
+            out.extend(width.to_le_bytes());
+            out.extend(height.to_le_bytes());
+            out.push(0x00);
+
+            let pixels = (0..height as usize).flat_map(|y| (0..width as usize).map(move |x| (y / self.cell_size, x / self.cell_size)))
+                .map(|(row, col)| if self.transposed { frame[col * size.col + row] } else { frame[row * size.col + col] })
+                .collect::<Vec<_>>();
+            out.push(2);
+            for block in lzw(&pixels, 2).chunks(255) {
+                out.push(block.len() as u8);
+                out.extend(block);
+            }
+            out.push(0);
+        }
+        out.push(0x3B);
+        Ok(out)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> { std::fs::write(path, self.encode()?) }
+}
+
+/// GIF's flavor of LZW over `pixels`, each below `2^min_code_size`, with codes packed starting from the lowest bit
+fn lzw(pixels: &[u8], min_code_size: u32) -> Vec<u8> {
+    let (clear, end) = (1 << min_code_size, (1 << min_code_size) + 1);
+    let (mut out, mut bits, mut pending) = (Vec::new(), 0u32, 0u32);
+    let mut code_size = min_code_size + 1;
+    let mut next = end + 1;
+    let mut codes = HashMap::<(u16, u8), u16>::new();
+
+    // Writes a code, then widens the codes to come once the next one to be assigned wouldn't fit
+    let mut emit = |code: u16, code_size: &mut u32, next: u16| {
+        pending |= (code as u32) << bits;
+        bits += *code_size;
+        while bits >= 8 {
+            out.push(pending as u8);
+            (pending, bits) = (pending >> 8, bits - 8);
+        }
+        if *code_size < MAX_CODE_SIZE && next > (1 << *code_size) - 1 { *code_size += 1; }
+    };
+
+    emit(clear, &mut code_size, next);
+    let mut current = None;
+    for &pixel in pixels {
+        let Some(prefix) = current else { current = Some(pixel as u16); continue };
+        if let Some(&code) = codes.get(&(prefix, pixel)) {
+            current = Some(code);
+            continue;
+        }
+        emit(prefix, &mut code_size, next);
+        if next < 1 << MAX_CODE_SIZE {
+            codes.insert((prefix, pixel), next);
+            next += 1;
+        } else {
+            emit(clear, &mut code_size, next);
+            codes.clear();
+            (code_size, next) = (min_code_size + 1, end + 1);
+        }
+        current = Some(pixel as u16);
+    }
+    if let Some(prefix) = current { emit(prefix, &mut code_size, next); }
+    emit(end, &mut code_size, next);
+    if bits > 0 { out.push(pending as u8); }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refuses_frames_too_big_for_a_gif() {
+        let mut recorder = GifRecorder::new(4, Palette::default(), 10);
+        recorder.capture(&Universe::new(3, 16384));
+        assert_eq!(recorder.encode().map_err(|e| e.kind()), Err(io::ErrorKind::InvalidInput));
+
+        let mut recorder = GifRecorder::new(4, Palette::default(), 10);
+        recorder.capture(&Universe::new(3, 16383));
+        let gif = recorder.encode().unwrap();
+        assert_eq!(&gif[6..10], [(16383u16 * 4).to_le_bytes(), 12u16.to_le_bytes()].concat());
+    }
+}
@@ -0,0 +1,205 @@
+use macroquad::prelude::*;
+
+use crate::{Boundary, Cell, Coord, Rule, Universe};
+
+/// Universe living in a GPU texture, advanced by a fragment shader ping-ponging between two render targets.
+/// Cell data is only read back when the CPU asks for it. Needs macroquad's window to be up. If the shader doesn't
+/// compile it runs on the CPU instead, only uploading the cells to be drawn.
+pub struct GpuUniverse {
+    targets: [RenderTarget; 2],
+    /// Index of the target holding the current generation
+    current: usize,
+    /// Or `None` if the tick shader couldn't be compiled
+    material: Option<Material>,
+    /// CPU copy of the cells, out of date while `cpu_stale`
+    cpu: Universe,
+    cpu_stale: bool,
+    /// Edits made on the CPU copy that haven't been uploaded yet
+    gpu_stale: bool,
+}
+
+impl GpuUniverse {
+    pub fn new(height: usize, width: usize) -> Self { Self::from(&Universe::new(height, width)) }
+
+    pub fn get_width(&self) -> usize       { self.cpu.get_width() }
+    pub fn get_height(&self) -> usize      { self.cpu.get_height() }
+    pub fn get_rule(&self) -> Rule         { self.cpu.get_rule() }
+    pub fn get_boundary(&self) -> Boundary { self.cpu.get_boundary() }
+    pub fn get_boundaries(&self) -> (Boundary, Boundary) { self.cpu.get_boundaries() }
+    /// Whether generations go by on the GPU, rather than on the CPU for want of a working shader
+    pub fn is_accelerated(&self) -> bool   { self.material.is_some() }
+
+    pub fn set_rule(&mut self, rule: Rule)      { self.cpu.set_rule(rule) }
+    pub fn set_boundary(&mut self, b: Boundary) { self.cpu.set_boundary(b) }
+    /// See `Universe::set_boundaries`
+    pub fn set_boundaries(&mut self, vertical: Boundary, horizontal: Boundary) { self.cpu.set_boundaries(vertical, horizontal) }
+
+    /// Current generation, to be drawn directly (one texel per cell, white for alive)
+    pub fn texture(&mut self) -> &Texture2D {
+        self.upload();
+        &self.targets[self.current].texture
+    }
+
+    /// Reads the cells back from the GPU if they changed since last time
+    pub fn universe(&mut self) -> &Universe {
+        if self.cpu_stale {
+            // SAFETY: only used to submit the batched draw calls, so that the ticks land before reading back
+            unsafe { get_internal_gl().flush(); }
+            let image = self.targets[self.current].texture.get_texture_data();
+            for (i, texel) in image.bytes.chunks(4).enumerate() {
+                self.cpu[i] = if texel[0] > 127 { Cell::Alive } else { Cell::Dead };
+            }
+            self.cpu_stale = false;
+        }
+        &self.cpu
+    }
+
+    pub fn is_alive(&mut self, c: Coord) -> bool { self.universe().is_alive(c) }
+
+    pub fn set_pixel(&mut self, c: Coord, val: Cell) {
+        self.universe();
+        self.cpu.set_pixel(c, val);
+        self.gpu_stale = true;
+    }
+
+    pub fn toggle_pixel(&mut self, c: Coord) {
+        self.universe();
+        self.cpu.toggle_pixel(c);
+        self.gpu_stale = true;
+    }
+
+    /// Queues a generation on the GPU. Leaves the default camera set.
+    pub fn tick(&mut self) {
+        self.upload();
+        let Some(material) = &self.material else {
+            self.cpu.tick();
+            self.gpu_stale = true;
+            return;
+        };
+        let (w, h) = (self.get_width() as f32, self.get_height() as f32);
+        let rule = self.get_rule();
+        let mask = |on: &dyn Fn(u16) -> bool| (0..=8).filter(|&n| on(n)).map(|n| (1 << n) as f32).sum::<f32>();
+        let boundary = |b: Boundary| match b {
+            Boundary::Toroidal => 0.0f32,
+            Boundary::Dead     => 1.0,
+            Boundary::Mirror   => 2.0,
+        };
+        // The texture's x runs along the universe's rows and its y down its columns
+        let (vertical, horizontal) = self.get_boundaries();
+
+        material.set_uniform("Size", vec2(w, h));
+        material.set_uniform("Birth", mask(&|n| rule.births_on(n)));
+        material.set_uniform("Survival", mask(&|n| rule.survives_on(n)));
+        material.set_uniform("Boundary", vec2(boundary(horizontal), boundary(vertical)));
+
+        let next = 1 - self.current;
+        set_camera(&Camera2D {
+            zoom: vec2(2.0 / w, 2.0 / h),
+            target: vec2(w / 2.0, h / 2.0),
+            render_target: Some(self.targets[next].clone()),
+            ..Default::default()
+        });
+        gl_use_material(material);
+        draw_texture_ex(&self.targets[self.current].texture, 0.0, 0.0, WHITE,
+                        DrawTextureParams { dest_size: Some(vec2(w, h)), ..Default::default() });
+        gl_use_default_material();
+        set_default_camera();
+
+        self.current = next;
+        self.cpu_stale = true;
+    }
+
+    pub fn step(&mut self, n: u64) {
+        for _ in 0..n { self.tick(); }
+    }
+
+    fn upload(&mut self) {
+        if !self.gpu_stale { return; }
+        // SAFETY: see `universe`, pending ticks must not land on top of the upload
+        unsafe { get_internal_gl().flush(); }
+        let bytes = self.cpu.cells.iter()
+            .flat_map(|c| if c.is_alive() { [255; 4] } else { [0, 0, 0, 255] })
+            .collect::<Vec<u8>>();
+        self.targets[self.current].texture.update_from_bytes(self.get_width() as u32, self.get_height() as u32, &bytes);
+        self.gpu_stale = false;
+    }
+}
+
+impl From<&Universe> for GpuUniverse {
+    fn from(universe: &Universe) -> Self {
+        let (w, h) = (universe.get_width() as u32, universe.get_height() as u32);
+        let targets = [render_target(w, h), render_target(w, h)];
+        for target in &targets { target.texture.set_filter(FilterMode::Nearest); }
+
+        let material = load_material(
+            ShaderSource::Glsl { vertex: VERTEX_SHADER, fragment: TICK_SHADER },
+            MaterialParams {
+                uniforms: vec![
+                    ("Size".to_owned(), UniformType::Float2),
+                    ("Birth".to_owned(), UniformType::Float1),
+                    ("Survival".to_owned(), UniformType::Float1),
+                    ("Boundary".to_owned(), UniformType::Float2),
+                ],
+                ..Default::default()
+            },
+        ).ok();
+
+        Self { targets, current: 0, material, cpu: universe.clone(), cpu_stale: false, gpu_stale: true }
+    }
+}
+
+const VERTEX_SHADER: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+
+varying highp vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}
+";
+
+/// Birth and Survival are bitmasks over neighbor counts, Boundary is 0 (toroidal), 1 (dead) or 2 (mirror) past the
+/// sides on x and past the top and bottom on y
+const TICK_SHADER: &str = "#version 100
+precision highp float;
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+uniform vec2 Size;
+uniform float Birth;
+uniform float Survival;
+uniform vec2 Boundary;
+
+float alive_at(vec2 cell) {
+    bool dead_x = Boundary.x > 0.5 && Boundary.x < 1.5 && (cell.x < 0.0 || cell.x >= Size.x);
+    bool dead_y = Boundary.y > 0.5 && Boundary.y < 1.5 && (cell.y < 0.0 || cell.y >= Size.y);
+    if (dead_x || dead_y) { return 0.0; }
+    vec2 wrapped = mod(cell, Size);
+    vec2 clamped = clamp(cell, vec2(0.0), Size - 1.0);
+    cell = vec2(Boundary.x < 0.5 ? wrapped.x : clamped.x, Boundary.y < 0.5 ? wrapped.y : clamped.y);
+    return step(0.5, texture2D(Texture, (cell + 0.5) / Size).r);
+}
+
+float bit(float mask, float n) {
+    return mod(floor((mask + 0.5) / pow(2.0, n)), 2.0);
+}
+
+void main() {
+    vec2 cell = floor(uv * Size);
+    float neighbors = 0.0;
+    for (int dy = -1; dy <= 1; dy++) {
+        for (int dx = -1; dx <= 1; dx++) {
+            if (dx != 0 || dy != 0) { neighbors += alive_at(cell + vec2(float(dx), float(dy))); }
+        }
+    }
+    float mask = alive_at(cell) > 0.5 ? Survival : Birth;
+    float next = bit(mask, neighbors);
+    gl_FragColor = vec4(next, next, next, 1.0);
+}
+";
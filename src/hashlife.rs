@@ -0,0 +1,512 @@
+use std::{collections::HashMap, fmt};
+
+use crate::{Cell, Coord, PlaneCoord, Rule, Universe};
+
+type NodeId = u32;
+
+const DEAD: NodeId = 0;
+const ALIVE: NodeId = 1;
+
+/// Past this many nodes, unreachable ones (and the step cache) get dropped
+const GC_THRESHOLD: usize = 1 << 22;
+/// Level of the biggest root, whose corners still fit in an `i64` wherever it's moved to. Cells leaving it
+/// are lost.
+const MAX_LEVEL: u8 = 62;
+
+/// Quadtree node covering a `2^level` × `2^level` square. Level 0 nodes are single cells.
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    children: [NodeId; 4], // nw, ne, sw, se
+    level: u8,
+    population: u64,
+}
+
+/// Gosper's HashLife: a hash-consed quadtree with memoized steps. Unbounded, so
+/// coordinates are [`PlaneCoord`]s. Rules with B0 or decay states are not supported.
+#[derive(Clone, Debug)]
+pub struct HashLife {
+    nodes: Vec<Node>,
+    canonical: HashMap<[NodeId; 4], NodeId>,
+    /// (node, log2 of the generations advanced) => result
+    steps: HashMap<(NodeId, u8), NodeId>,
+    empty: Vec<NodeId>,
+    root: NodeId,
+    /// Top-left corner of the root node
+    origin: PlaneCoord,
+    rule: Rule,
+    generation: u64,
+}
+
+impl HashLife {
+    pub fn new() -> Self {
+        let leaf = |population| Node { children: [DEAD; 4], level: 0, population };
+        let mut hl = Self {
+            nodes: vec![leaf(0), leaf(1)],
+            canonical: HashMap::new(),
+            steps: HashMap::new(),
+            empty: vec![DEAD],
+            root: DEAD,
+            origin: PlaneCoord::new(-4, -4),
+            rule: Rule::default(),
+            generation: 0,
+        };
+        hl.root = hl.empty(3);
+        hl
+    }
+
+    pub fn get_rule(&self) -> Rule       { self.rule }
+    pub fn get_generation(&self) -> u64  { self.generation }
+    pub fn population(&self) -> u64      { self.nodes[self.root as usize].population }
+
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.steps.clear();
+    }
+
+    pub fn is_alive(&self, c: PlaneCoord) -> bool {
+        if !self.contains(c) { return false; }
+        self.alive_in(self.root, c.row - self.origin.row, c.col - self.origin.col)
+    }
+
+    /// Cells too far out for the biggest root are left as they are
+    pub fn set_pixel(&mut self, c: PlaneCoord, val: Cell) {
+        while !self.contains(c) {
+            if self.level(self.root) == MAX_LEVEL { return; }
+            self.expand();
+        }
+        let (row, col) = (c.row - self.origin.row, c.col - self.origin.col);
+        self.root = self.set_in(self.root, row, col, val.is_alive());
+    }
+
+    pub fn toggle_pixel(&mut self, c: PlaneCoord) {
+        let val = if self.is_alive(c) { Cell::Dead } else { Cell::Alive };
+        self.set_pixel(c, val);
+    }
+
+    pub fn tick(&mut self) { self.advance_pow2(0); }
+
+    /// Advances `n` generations, taking power-of-two leaps through the memoized steps. The root can't grow past
+    /// `MAX_LEVEL`, so the longest leaps are `2^(MAX_LEVEL - 3)` generations, taken as many times as it takes.
+    pub fn step(&mut self, n: u64) {
+        const LONGEST: u8 = MAX_LEVEL - 3;
+        for bit in 0..64 {
+            if n >> bit & 1 == 0 { continue; }
+            let (log2, leaps) = if bit <= LONGEST { (bit, 1) } else { (LONGEST, 1u64 << (bit - LONGEST)) };
+            for _ in 0..leaps { self.advance_pow2(log2); }
+        }
+    }
+
+    /// Renders the given window of the plane in the same format as [`Universe`]'s `Display`
+    pub fn render_region(&self, top_left: PlaneCoord, height: usize, width: usize) -> String {
+        self.to_universe(top_left, height, width).render()
+    }
+
+    /// Copies a window of the plane into a dense universe
+    pub fn to_universe(&self, top_left: PlaneCoord, height: usize, width: usize) -> Universe {
+        let mut universe = Universe::new(height, width);
+        universe.set_rule(self.rule);
+        for row in 0..height {
+            for col in 0..width {
+                let c = PlaneCoord::new(top_left.row + row as i64, top_left.col + col as i64);
+                if self.is_alive(c) { universe.set_pixel(Coord::new(row, col), Cell::Alive); }
+            }
+        }
+        universe
+    }
+
+    /// Reads a pattern in Golly's Macrocell format, whose every line is a quadtree node: `k nw ne sw se` for a
+    /// node of side `2^k` out of the nodes on earlier lines (numbered from 1, and 0 being an empty node), or
+    /// an 8×8 leaf in rows of `.` and `*` ending in `$`. Nodes keep on being shared as they are in the file,
+    /// and the last one is the root, centered on the origin. `#R` gives the rule and `#G` the generation.
+    pub fn from_macrocell(s: &str) -> Result<Self, ParseMacrocellError> {
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        if !lines.next().is_some_and(|(_, line)| line.starts_with("[M2]")) { return Err(ParseMacrocellError::MissingHeader); }
+
+        let mut hl = Self::new();
+        // Node on each line so far, the first one standing in for 0
+        let mut ids = vec![DEAD];
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let invalid = ParseMacrocellError::InvalidLine(number);
+            if let Some(rule) = line.strip_prefix("#R") {
+                let rule = rule.trim();
+                hl.set_rule(rule.parse().map_err(|_| ParseMacrocellError::UnsupportedRule(rule.to_owned()))?);
+                continue;
+            }
+            if let Some(generation) = line.strip_prefix("#G") {
+                hl.generation = generation.trim().parse().map_err(|_| invalid)?;
+                continue;
+            }
+            if line.starts_with('#') { continue; }
+
+            let id = if line.starts_with(['.', '*', '$']) {
+                let mut leaf = [[false; 8]; 8];
+                let (mut row, mut col) = (0, 0);
+                for c in line.chars() {
+                    match c {
+                        '.'                       => col += 1,
+                        '*' if row < 8 && col < 8 => { leaf[row][col] = true; col += 1; }
+                        '$'                       => (row, col) = (row + 1, 0),
+                        _                         => return Err(invalid),
+                    }
+                }
+                hl.build(&leaf, 3, 0, 0)
+            } else {
+                let fields = line.split_whitespace().map(str::parse::<usize>).collect::<Result<Vec<_>, _>>();
+                let Ok(&[level, nw, ne, sw, se]) = fields.as_deref() else { return Err(invalid) };
+                if !(1..=MAX_LEVEL as usize).contains(&level) { return Err(invalid); }
+                let level = level as u8;
+                let mut children = [DEAD; 4];
+                for (child, k) in children.iter_mut().zip([nw, ne, sw, se]) {
+                    *child = match (level, k) {
+                        // Level 1 nodes are made of cell states instead
+                        (1, 0 | 1)                                                   => k as NodeId,
+                        (1, _)                                                       => return Err(invalid),
+                        (_, 0)                                                       => hl.empty(level - 1),
+                        _ if ids.get(k).is_some_and(|&id| hl.level(id) == level - 1) => ids[k],
+                        _                                                            => return Err(invalid),
+                    };
+                }
+                hl.node(children)
+            };
+            ids.push(id);
+        }
+
+        if ids.len() > 1 {
+            hl.root = ids[ids.len() - 1];
+            let half = 1 << (hl.level(hl.root) - 1);
+            hl.origin = PlaneCoord::new(-half, -half);
+            while hl.level(hl.root) < 3 { hl.expand(); }
+        }
+        Ok(hl)
+    }
+
+    /// Writes the plane in Golly's Macrocell format (see `from_macrocell`), with every node shared as many times
+    /// as it is in the quadtree written just once. Where the plane is doesn't carry over: reading it back
+    /// centers it on the origin.
+    pub fn to_macrocell(&self) -> String {
+        let mut out = format!("[M2] (gameoflife)\n#R {}\n", self.rule);
+        if self.generation > 0 { out.push_str(&format!("#G {}\n", self.generation)); }
+        let mut hl = self.clone();
+        while hl.level(hl.root) < 3 { hl.expand(); }
+        hl.write_macrocell(hl.root, &mut HashMap::new(), &mut out);
+        out
+    }
+
+    fn size(&self) -> i64 { 1 << self.level(self.root) }
+    fn level(&self, n: NodeId) -> u8 { self.nodes[n as usize].level }
+    fn children(&self, n: NodeId) -> [NodeId; 4] { self.nodes[n as usize].children }
+    fn population_of(&self, n: NodeId) -> u64 { self.nodes[n as usize].population }
+
+    /// Whether the cell `row` down and `col` across from the top-left corner of `node` is alive
+    fn alive_in(&self, node: NodeId, row: i64, col: i64) -> bool {
+        let (mut node, mut row, mut col) = (node, row, col);
+        while self.level(node) > 0 {
+            let half = 1 << (self.level(node) - 1);
+            let quadrant = (row >= half) as usize * 2 + (col >= half) as usize;
+            node = self.nodes[node as usize].children[quadrant];
+            row %= half;
+            col %= half;
+        }
+        node == ALIVE
+    }
+
+    /// Node for the `2^level` square at (`row`, `col`) in `leaf`
+    fn build(&mut self, leaf: &[[bool; 8]; 8], level: u8, row: usize, col: usize) -> NodeId {
+        if level == 0 { return if leaf[row][col] { ALIVE } else { DEAD }; }
+        let half = 1 << (level - 1);
+        let children = [(row, col), (row, col + half), (row + half, col), (row + half, col + half)]
+            .map(|(row, col)| self.build(leaf, level - 1, row, col));
+        self.node(children)
+    }
+
+    /// Writes `n` out after its children, unless it's empty or already written, returning its line number
+    fn write_macrocell(&self, n: NodeId, numbers: &mut HashMap<NodeId, usize>, out: &mut String) -> usize {
+        if self.population_of(n) == 0 { return 0; }
+        if let Some(&number) = numbers.get(&n) { return number; }
+
+        let level = self.level(n);
+        if level == 3 {
+            let mut rows = (0..8).map(|row| {
+                let line = (0..8).map(|col| if self.alive_in(n, row, col) { '*' } else { '.' }).collect::<String>();
+                line.trim_end_matches('.').to_owned()
+            }).collect::<Vec<_>>();
+            while rows.last().is_some_and(String::is_empty) { rows.pop(); }
+            for row in rows { out.push_str(&row); out.push('$'); }
+        } else {
+            let [nw, ne, sw, se] = self.children(n).map(|child| self.write_macrocell(child, numbers, out));
+            out.push_str(&format!("{level} {nw} {ne} {sw} {se}"));
+        }
+        out.push('\n');
+        numbers.insert(n, numbers.len() + 1);
+        numbers.len()
+    }
+
+    fn contains(&self, c: PlaneCoord) -> bool {
+        let size = self.size();
+        (self.origin.row..self.origin.row + size).contains(&c.row)
+            && (self.origin.col..self.origin.col + size).contains(&c.col)
+    }
+
+    fn node(&mut self, children: [NodeId; 4]) -> NodeId {
+        if let Some(&id) = self.canonical.get(&children) { return id; }
+        let level = self.level(children[0]) + 1;
+        let population = children.iter().map(|&c| self.population_of(c)).sum();
+        let id = self.nodes.len() as NodeId;
+        self.nodes.push(Node { children, level, population });
+        self.canonical.insert(children, id);
+        id
+    }
+
+    fn empty(&mut self, level: u8) -> NodeId {
+        while self.empty.len() <= level as usize {
+            let e = *self.empty.last().unwrap();
+            let next = self.node([e; 4]);
+            self.empty.push(next);
+        }
+        self.empty[level as usize]
+    }
+
+    fn set_in(&mut self, node: NodeId, row: i64, col: i64, alive: bool) -> NodeId {
+        let level = self.level(node);
+        if level == 0 { return if alive { ALIVE } else { DEAD }; }
+
+        let half = 1 << (level - 1);
+        let quadrant = (row >= half) as usize * 2 + (col >= half) as usize;
+        let mut children = self.children(node);
+        children[quadrant] = self.set_in(children[quadrant], row % half, col % half, alive);
+        self.node(children)
+    }
+
+    /// Doubles the root's side, keeping its contents in the middle
+    fn expand(&mut self) {
+        let level = self.level(self.root);
+        let e = self.empty(level - 1);
+        let [nw, ne, sw, se] = self.children(self.root);
+        let children = [
+            self.node([e, e, e, nw]),
+            self.node([e, e, ne, e]),
+            self.node([e, sw, e, e]),
+            self.node([se, e, e, e]),
+        ];
+        self.root = self.node(children);
+        let shift = 1 << (level - 1);
+        self.origin = PlaneCoord::new(self.origin.row - shift, self.origin.col - shift);
+    }
+
+    /// The centered node one level down
+    fn center(&mut self, n: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.children(n);
+        self.node([self.children(nw)[3], self.children(ne)[2], self.children(sw)[1], self.children(se)[0]])
+    }
+
+    fn horizontal_center(&mut self, w: NodeId, e: NodeId) -> NodeId {
+        let (w, e) = (self.children(w), self.children(e));
+        self.node([w[1], e[0], w[3], e[2]])
+    }
+
+    fn vertical_center(&mut self, n: NodeId, s: NodeId) -> NodeId {
+        let (n, s) = (self.children(n), self.children(s));
+        self.node([n[2], n[3], s[0], s[1]])
+    }
+
+    /// Takes a leap of `2^log2` generations, `log2` being at most `MAX_LEVEL - 3`
+    fn advance_pow2(&mut self, log2: u8) {
+        let center_population = |hl: &mut Self| {
+            let c = hl.center(hl.root);
+            let cc = hl.center(c);
+            hl.population_of(cc)
+        };
+        while self.level(self.root) < log2 + 3
+            || (self.level(self.root) < MAX_LEVEL && center_population(self) != self.population()) {
+            self.expand();
+        }
+
+        let level = self.level(self.root);
+        self.root = self.result(self.root, log2);
+        let shift = 1 << (level - 2);
+        self.origin = PlaneCoord::new(self.origin.row + shift, self.origin.col + shift);
+        self.generation = self.generation.wrapping_add(1 << log2);
+
+        if self.nodes.len() > GC_THRESHOLD { self.collect_garbage(); }
+    }
+
+    /// Center of `n`, advanced `2^min(log2, level - 2)` generations
+    fn result(&mut self, n: NodeId, log2: u8) -> NodeId {
+        let level = self.level(n);
+        let log2 = log2.min(level - 2);
+        if self.population_of(n) == 0 { return self.empty(level - 1); }
+        if let Some(&r) = self.steps.get(&(n, log2)) { return r; }
+
+        let r = if level == 2 {
+            self.base_step(n)
+        } else {
+            let [nw, ne, sw, se] = self.children(n);
+            let n00 = nw;
+            let n01 = self.horizontal_center(nw, ne);
+            let n02 = ne;
+            let n10 = self.vertical_center(nw, sw);
+            let n11 = self.center(n);
+            let n12 = self.vertical_center(ne, se);
+            let n20 = sw;
+            let n21 = self.horizontal_center(sw, se);
+            let n22 = se;
+            let nine = [n00, n01, n02, n10, n11, n12, n20, n21, n22];
+
+            let full_speed = log2 == level - 2;
+            let mut r = [DEAD; 9];
+            for (i, &sub) in nine.iter().enumerate() {
+                r[i] = if full_speed { self.result(sub, log2 - 1) } else { self.center(sub) };
+            }
+
+            let quads = [
+                self.node([r[0], r[1], r[3], r[4]]),
+                self.node([r[1], r[2], r[4], r[5]]),
+                self.node([r[3], r[4], r[6], r[7]]),
+                self.node([r[4], r[5], r[7], r[8]]),
+            ];
+            let next_log2 = if full_speed { log2 - 1 } else { log2 };
+            let mut children = [DEAD; 4];
+            for (i, &q) in quads.iter().enumerate() { children[i] = self.result(q, next_log2); }
+            self.node(children)
+        };
+
+        self.steps.insert((n, log2), r);
+        r
+    }
+
+    /// One generation of a 4×4 node, yielding its inner 2×2
+    fn base_step(&mut self, n: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (q, &child) in self.children(n).iter().enumerate() {
+            for (k, &leaf) in self.children(child).iter().enumerate() {
+                grid[q / 2 * 2 + k / 2][q % 2 * 2 + k % 2] = leaf == ALIVE;
+            }
+        }
+
+        let mut next = [DEAD; 4];
+        for (k, cell) in next.iter_mut().enumerate() {
+            let (row, col) = (1 + k / 2, 1 + k % 2);
+            let neighbors = grid[row - 1..=row + 1].iter()
+                .flat_map(|line| &line[col - 1..=col + 1])
+                .filter(|&&alive| alive)
+                .count() as u8 - grid[row][col] as u8;
+            let current = if grid[row][col] { Cell::Alive } else { Cell::Dead };
+            *cell = if self.rule.next(current, neighbors.into()).is_alive() { ALIVE } else { DEAD };
+        }
+        self.node(next)
+    }
+
+    /// Rebuilds the arena keeping only the nodes reachable from the root
+    fn collect_garbage(&mut self) {
+        let old = std::mem::take(&mut self.nodes);
+        self.nodes = old[..2].to_vec();
+        self.canonical.clear();
+        self.steps.clear();
+        self.empty = vec![DEAD];
+
+        let mut remap = HashMap::new();
+        self.root = self.copy_from(&old, self.root, &mut remap);
+    }
+
+    fn copy_from(&mut self, old: &[Node], n: NodeId, remap: &mut HashMap<NodeId, NodeId>) -> NodeId {
+        if n <= ALIVE { return n; }
+        if let Some(&id) = remap.get(&n) { return id; }
+        let mut children = old[n as usize].children;
+        for c in children.iter_mut() { *c = self.copy_from(old, *c, remap); }
+        let id = self.node(children);
+        remap.insert(n, id);
+        id
+    }
+}
+
+impl Default for HashLife {
+    fn default() -> Self { Self::new() }
+}
+
+impl From<&Universe> for HashLife {
+    /// Places the universe with its top-left corner at the origin of the plane
+    fn from(universe: &Universe) -> Self {
+        let mut hl = Self::new();
+        hl.set_rule(universe.get_rule());
+        for c in universe.live_cells() { hl.set_pixel(c.into(), Cell::Alive); }
+        hl
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMacrocellError {
+    /// The first line must start with `[M2]`
+    MissingHeader,
+    /// The `#R` line's rule isn't one `Rule` parses
+    UnsupportedRule(String),
+    /// A line that couldn't be made sense of, counting from 1. Nodes can only be made of earlier ones, one
+    /// level down, and cell states other than 0 and 1 aren't supported.
+    InvalidLine(usize),
+}
+
+impl fmt::Display for ParseMacrocellError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseMacrocellError::MissingHeader         => write!(f, "the file doesn't start with [M2]"),
+            ParseMacrocellError::UnsupportedRule(rule) => write!(f, "the rule {rule} isn't supported"),
+            ParseMacrocellError::InvalidLine(line)     => write!(f, "couldn't make sense of line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseMacrocellError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{BlitMode, Boundary, LibraryPattern};
+
+    /// `pattern` near the top-left corner of a universe big enough that nothing it sends out reaches the dead
+    /// edges in `generations`
+    fn boxed(pattern: LibraryPattern, generations: usize) -> Universe {
+        let side = 32 + generations;
+        let mut universe = Universe::new(side, side);
+        universe.set_boundaries(Boundary::Dead, Boundary::Dead);
+        universe.blit(&pattern.pattern(), Coord::new(8, 8), BlitMode::Copy);
+        universe
+    }
+
+    #[test]
+    fn steps_like_ticking_a_universe() {
+        for (pattern, generations) in [(LibraryPattern::Glider, 77), (LibraryPattern::GosperGliderGun, 150)] {
+            let mut universe = boxed(pattern, generations);
+            let mut hl = HashLife::from(&universe);
+            for _ in 0..generations { universe.tick(); }
+            hl.step(generations as u64);
+
+            let (height, width) = (universe.get_height(), universe.get_width());
+            assert_eq!(hl.get_generation(), generations as u64);
+            assert_eq!(hl.population(), universe.live_cells().count() as u64);
+            assert_eq!(hl.to_universe(PlaneCoord::new(0, 0), height, width).live_cells().collect::<Vec<_>>(),
+                       universe.live_cells().collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn macrocell_round_trip() {
+        let mut hl = HashLife::from(&boxed(LibraryPattern::GosperGliderGun, 0));
+        hl.step(45);
+        let macrocell = hl.to_macrocell();
+        let read = HashLife::from_macrocell(&macrocell).unwrap();
+        assert_eq!((read.get_rule(), read.get_generation(), read.population()), (hl.get_rule(), 45, hl.population()));
+        assert_eq!(read.to_macrocell(), macrocell);
+    }
+
+    #[test]
+    fn takes_huge_steps() {
+        let mut hl = HashLife::from(&boxed(LibraryPattern::Glider, 0));
+        hl.step(1 << 61);
+        assert_eq!((hl.get_generation(), hl.population()), (1 << 61, 5));
+
+        let mut hl = HashLife::new();
+        for c in [(0, 0), (0, 1), (1, 0), (1, 1)] { hl.set_pixel(PlaneCoord::new(c.0, c.1), Cell::Alive); }
+        hl.step(u64::MAX);
+        assert_eq!((hl.get_generation(), hl.population()), (u64::MAX, 4));
+        assert!(hl.is_alive(PlaneCoord::new(1, 1)) && !hl.is_alive(PlaneCoord::new(i64::MAX, i64::MIN)));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{Cell, Coord, Universe};
+
+/// One undoable change: every cell it touched, with its state before and after
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Edit {
+    changes: Vec<(Coord, Cell, Cell)>,
+}
+
+impl Edit {
+    pub fn is_empty(&self) -> bool { self.changes.is_empty() }
+
+    fn push(&mut self, c: Coord, before: Cell, after: Cell) {
+        if before != after { self.changes.push((c, before, after)); }
+    }
+}
+
+/// Undo/redo stacks of the edits made to a universe. Edits go through the history so it can record them;
+/// anything between `begin_group` and `end_group` (e.g. a whole mouse stroke) is undone as one.
+#[derive(Clone, Debug, Default)]
+pub struct EditHistory {
+    undo: Vec<Edit>,
+    redo: Vec<Edit>,
+    group: Option<Edit>,
+}
+
+impl EditHistory {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn can_undo(&self) -> bool { !self.undo.is_empty() }
+    pub fn can_redo(&self) -> bool { !self.redo.is_empty() }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+        self.group = None;
+    }
+
+    pub fn begin_group(&mut self) {
+        self.end_group();
+        self.group = Some(Edit::default());
+    }
+
+    pub fn end_group(&mut self) {
+        if let Some(edit) = self.group.take() { self.commit(edit); }
+    }
+
+    pub fn set_pixel(&mut self, universe: &mut Universe, c: Coord, val: Cell) {
+        let mut edit = Edit::default();
+        for image in universe.symmetric_images(c) { edit.push(image, universe[image], val); }
+        universe.set_pixel(c, val);
+        self.record(edit);
+    }
+
+    pub fn toggle_pixel(&mut self, universe: &mut Universe, c: Coord) {
+        let val = !universe[c];
+        self.set_pixel(universe, c, val);
+    }
+
+    /// Records an arbitrary bulk edit (paste, clear...) by diffing the universe around it. If `f` resizes the
+    /// universe there is no sensible way back, so the history is dropped instead.
+    pub fn apply(&mut self, universe: &mut Universe, f: impl FnOnce(&mut Universe)) {
+        let before = universe.clone();
+        f(universe);
+
+        if (before.get_height(), before.get_width()) != (universe.get_height(), universe.get_width()) {
+            return self.clear();
+        }
+
+        let mut edit = Edit::default();
+        for ((c, old), (_, new)) in before.iter_cells().zip(universe.iter_cells()) { edit.push(c, old, new); }
+        self.record(edit);
+    }
+
+    /// Reverts the last edit, returning whether there was one
+    pub fn undo(&mut self, universe: &mut Universe) -> bool {
+        self.end_group();
+        let Some(edit) = self.undo.pop() else { return false };
+        for &(c, before, _) in edit.changes.iter().rev() { universe[c] = before; }
+        self.redo.push(edit);
+        true
+    }
+
+    /// Reapplies the last undone edit, returning whether there was one
+    pub fn redo(&mut self, universe: &mut Universe) -> bool {
+        self.end_group();
+        let Some(edit) = self.redo.pop() else { return false };
+        for &(c, _, after) in &edit.changes { universe[c] = after; }
+        self.undo.push(edit);
+        true
+    }
+
+    fn record(&mut self, edit: Edit) {
+        match &mut self.group {
+            Some(group) => group.changes.extend(edit.changes),
+            None        => self.commit(edit),
+        }
+    }
+
+    fn commit(&mut self, edit: Edit) {
+        if edit.is_empty() { return; }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+}
@@ -0,0 +1,179 @@
+use crate::{rng::SplitMix64, Coord};
+
+/// Weights over the cells up to `radius` away from one, adding up to 1
+#[derive(Clone, Debug, PartialEq)]
+pub struct Kernel {
+    radius: usize,
+    /// (row offset, column offset, weight), leaving out the cells that weigh nothing
+    weights: Vec<(isize, isize, f32)>,
+}
+
+impl Kernel {
+    /// Lenia's: as many concentric rings as there are `peaks`, out to `radius` cells, each one a smooth bump as
+    /// high as its peak
+    pub fn rings(radius: usize, peaks: &[f32]) -> Self {
+        let bump = |x: f32| if 0.0 < x && x < 1.0 { (4.0 - 1.0 / (x * (1.0 - x))).exp() } else { 0.0 };
+        Self::from_distance(radius, |d| {
+            let r = d / radius.max(1) as f32 * peaks.len() as f32;
+            peaks.get(r as usize).map_or(0.0, |peak| peak * bump(r.fract()))
+        })
+    }
+
+    /// Every cell within `radius`, antialiased along the edge
+    pub fn disk(radius: f32) -> Self {
+        Self::from_distance(radius.ceil() as usize + 1, |d| inside(d, radius))
+    }
+
+    /// Every cell between `inner` and `outer` cells away, antialiased along both edges
+    pub fn annulus(inner: f32, outer: f32) -> Self {
+        Self::from_distance(outer.ceil() as usize + 1, |d| inside(d, outer) - inside(d, inner))
+    }
+
+    pub fn get_radius(&self) -> usize { self.radius }
+
+    /// Normalized kernel weighing each cell by its distance from the center
+    fn from_distance(radius: usize, weight: impl Fn(f32) -> f32) -> Self {
+        let r = radius as isize;
+        let mut weights = (-r..=r).flat_map(|dy| (-r..=r).map(move |dx| (dy, dx)))
+            .map(|(dy, dx)| (dy, dx, weight(((dy * dy + dx * dx) as f32).sqrt())))
+            .filter(|&(_, _, w)| w > 0.0)
+            .collect::<Vec<_>>();
+        let total = weights.iter().map(|&(_, _, w)| w).sum::<f32>();
+        if total > 0.0 {
+            for (_, _, w) in weights.iter_mut() { *w /= total; }
+        }
+        Self { radius, weights }
+    }
+}
+
+/// How much of a cell `d` away from the center is within `radius`
+fn inside(d: f32, radius: f32) -> f32 { (radius + 0.5 - d).clamp(0.0, 1.0) }
+
+/// Rule for a continuous universe, telling how fast each cell grows (up to 1) or shrinks (down to -1)
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContinuousRule {
+    /// Lenia: cells grow where the potential `kernel` gathers around them is near `mu`, within a bell of width
+    /// `sigma`, and shrink everywhere else
+    Lenia { kernel: Kernel, mu: f32, sigma: f32 },
+    /// SmoothLife: with `m` the filling of the `inner` disk and `n` that of the `outer` ring, dead cells come to
+    /// life while `n` is within `birth` and live ones stay so within `survival`. `alpha_n` smooths out the edges
+    /// of those intervals and `alpha_m` the line between dead and alive.
+    SmoothLife { inner: Kernel, outer: Kernel, birth: (f32, f32), survival: (f32, f32), alpha_n: f32, alpha_m: f32 },
+}
+
+impl ContinuousRule {
+    /// Lenia's Orbium, whose gliders are about 20 cells across
+    pub fn orbium() -> Self {
+        ContinuousRule::Lenia { kernel: Kernel::rings(13, &[1.0]), mu: 0.15, sigma: 0.015 }
+    }
+
+    /// Rafler's SmoothLife, over an inner disk of `radius` cells and a ring out to three times that
+    pub fn smooth_life(radius: f32) -> Self {
+        ContinuousRule::SmoothLife {
+            inner: Kernel::disk(radius),
+            outer: Kernel::annulus(radius, 3.0 * radius),
+            birth: (0.278, 0.365),
+            survival: (0.267, 0.445),
+            alpha_n: 0.028,
+            alpha_m: 0.147,
+        }
+    }
+}
+
+impl Default for ContinuousRule {
+    fn default() -> Self { ContinuousRule::orbium() }
+}
+
+/// Grid of cells that are each anywhere from 0 (empty) to 1 (full), changing a bit every tick by how much their
+/// rule makes them grow, scaled down by `dt`. Wraps around on both axes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContinuousUniverse {
+    cells: Vec<f32>,
+    height: usize,
+    width: usize,
+    rule: ContinuousRule,
+    dt: f32,
+    generation: u64,
+}
+
+impl ContinuousUniverse {
+    pub fn new(height: usize, width: usize) -> Self {
+        Self { cells: vec![0.0; height * width], height, width, rule: ContinuousRule::default(), dt: 0.1, generation: 0 }
+    }
+
+    pub fn get_height(&self) -> usize                { self.height }
+    pub fn get_width(&self) -> usize                 { self.width }
+    pub fn get_rule(&self) -> &ContinuousRule        { &self.rule }
+    pub fn set_rule(&mut self, rule: ContinuousRule) { self.rule = rule }
+    pub fn get_dt(&self) -> f32                      { self.dt }
+    pub fn set_dt(&mut self, dt: f32)                { self.dt = dt.clamp(0.0, 1.0) }
+    pub fn get_generation(&self) -> u64              { self.generation }
+    /// Sum of every cell
+    pub fn mass(&self) -> f32                        { self.cells.iter().sum() }
+
+    pub fn get(&self, c: Coord) -> f32               { self.cells[c.row * self.width + c.col] }
+    pub fn set(&mut self, c: Coord, value: f32)      { self.cells[c.row * self.width + c.col] = value.clamp(0.0, 1.0) }
+
+    /// Each cell gets a random value with probability `density` (0 to 1), and is empty otherwise
+    pub fn randomize(&mut self, density: f32, seed: u64) {
+        self.randomize_patch(Coord::new(0, 0), Coord::new(self.height, self.width), density, seed);
+    }
+
+    /// Like `randomize`, but only within the `size` (height, width) box at `top_left`, clearing everything else.
+    /// Patterns rarely come out of a soup filling the whole grid, but often do out of a smaller one.
+    pub fn randomize_patch(&mut self, top_left: Coord, size: Coord, density: f32, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        self.cells.fill(0.0);
+        for row in top_left.row..(top_left.row + size.row).min(self.height) {
+            for col in top_left.col..(top_left.col + size.col).min(self.width) {
+                self.cells[row * self.width + col] = if rng.next_f32() < density { rng.next_f32() } else { 0.0 };
+            }
+        }
+    }
+
+    pub fn tick(&mut self) {
+        let rates = match &self.rule {
+            ContinuousRule::Lenia { kernel, mu, sigma } => self.convolve(kernel).into_iter()
+                .map(|u| 2.0 * (-(u - mu).powi(2) / (2.0 * sigma * sigma)).exp() - 1.0)
+                .collect::<Vec<_>>(),
+            ContinuousRule::SmoothLife { inner, outer, birth, survival, alpha_n, alpha_m } => {
+                let sigmoid = |x: f32, a: f32, alpha: f32| 1.0 / (1.0 + (-(x - a) * 4.0 / alpha).exp());
+                self.convolve(inner).into_iter().zip(self.convolve(outer)).map(|(m, n)| {
+                    let aliveness = sigmoid(m, 0.5, *alpha_m);
+                    let low = birth.0 * (1.0 - aliveness) + survival.0 * aliveness;
+                    let high = birth.1 * (1.0 - aliveness) + survival.1 * aliveness;
+                    2.0 * sigmoid(n, low, *alpha_n) * (1.0 - sigmoid(n, high, *alpha_n)) - 1.0
+                }).collect()
+            }
+        };
+
+        for (cell, rate) in self.cells.iter_mut().zip(rates) { *cell = (*cell + self.dt * rate).clamp(0.0, 1.0); }
+        self.generation += 1;
+    }
+
+    /// Weighted sum of the cells around each one. Goes over a copy padded with the opposite edges, so the
+    /// wrapping around costs nothing within the loop.
+    fn convolve(&self, kernel: &Kernel) -> Vec<f32> {
+        let (h, w, r) = (self.height, self.width, kernel.radius);
+        if h == 0 || w == 0 { return Vec::new(); }
+        let padded_width = w + 2 * r;
+        let mut padded = vec![0.0; (h + 2 * r) * padded_width];
+        for y in 0..h + 2 * r {
+            let source = (y + h * r - r) % h;
+            for x in 0..padded_width {
+                padded[y * padded_width + x] = self.cells[source * w + (x + w * r - r) % w];
+            }
+        }
+
+        let mut sums = vec![0.0; h * w];
+        for y in 0..h {
+            for x in 0..w {
+                let center = (y + r) * padded_width + x + r;
+                sums[y * w + x] = kernel.weights.iter()
+                    .map(|&(dy, dx, weight)| weight * padded[(center as isize + dy * padded_width as isize + dx) as usize])
+                    .sum();
+            }
+        }
+        sums
+    }
+}
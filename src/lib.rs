@@ -1,16 +1,186 @@
-use std::{mem::swap, ops::{Index, IndexMut, Not}};
+use std::{collections::VecDeque, hash::{Hash, Hasher}, mem::swap, ops::{Index, IndexMut, Not}, str::FromStr, sync::Arc};
+
+#[cfg(feature = "gpu")]
+mod gpu;
+mod apgcode;
+mod coloring;
+mod csv;
+mod cycle;
+mod elementary;
+mod explorer;
+mod gif;
+mod hashlife;
+mod history;
+mod lenia;
+mod library;
+mod lif;
+mod margolus;
+mod neighborhood;
+#[cfg(feature = "net")]
+mod net;
+mod noise;
+mod npy;
+mod packed;
+mod palette;
+mod pattern;
+mod plaintext;
+#[cfg(feature = "parallel")]
+mod pool;
+mod population;
+#[cfg(feature = "image")]
+mod raster;
+mod revision;
+mod rle;
+mod rng;
+mod rule;
+mod ruletable;
+mod session;
+mod shapes;
+mod simd;
+mod sparse;
+mod svg;
+mod symmetry;
+mod turmite;
+mod zone;
+mod universe3d;
+#[cfg(feature = "video")]
+mod video;
+
+#[cfg(feature = "gpu")]
+pub use gpu::GpuUniverse;
+pub use apgcode::ParseApgcodeError;
+pub use coloring::Coloring;
+pub use cycle::{Cycle, LoopDetector, Outcome};
+pub use explorer::{interestingness, Candidate, RuleExplorer};
+pub use gif::GifRecorder;
+pub use hashlife::{HashLife, ParseMacrocellError};
+pub use history::{Edit, EditHistory};
+pub use lenia::{ContinuousRule, ContinuousUniverse, Kernel};
+pub use library::{LibraryPattern, PatternKind};
+pub use lif::ParseLifeError;
+pub use margolus::BlockRule;
+pub use neighborhood::Neighborhood;
+#[cfg(feature = "net")]
+pub use net::FetchError;
+pub use packed::PackedUniverse;
+pub use palette::Palette;
+pub use pattern::{BlitMode, Pattern};
+pub use plaintext::ParsePlaintextError;
+pub use revision::Revision;
+pub use rle::ParseRleError;
+pub use rule::{ParseRuleError, Rule, RulePreset};
+pub use ruletable::{ParseRuleTableError, RuleTable};
+pub use session::{ParseSessionError, Session};
+pub use shapes::{circle_cells, line_cells, rectangle_cells};
+pub use sparse::SparseUniverse;
+pub use symmetry::Symmetry;
+pub use turmite::{Heading, Move, Turmite, Turn};
+pub use zone::{Zone, ZoneBorder};
+pub use universe3d::{Rule3D, Universe3D};
+#[cfg(feature = "video")]
+pub use video::VideoExporter;
+
+use population::TilePopulations;
+use revision::Revisions;
+use rule::CustomRule;
+
+/// Side of the square tiles used to skip re-evaluating quiescent regions
+const TILE: usize = 16;
+/// Most generations the population log keeps, dropping the oldest past that
+const POPULATION_LOG: usize = 1 << 16;
+
+/// Custom transition function, from a cell and its live neighbors
+type NextCell<'a> = dyn Fn(Cell, u8) -> Cell + Sync + 'a;
 
 #[derive(Clone, Debug)]
 pub struct Universe {
-    /// Flattened grid of Cells
+    /// Flattened grid of Cells. Writing to it directly bypasses change tracking, so `tick` may
+    /// skip the edits: prefer indexing or `set_pixel`.
     pub cells: Vec<Cell>,
     back_buffer: Vec<Cell>,
     height: usize,
     width: usize,
+    /// Past the top and bottom edges
+    boundary: Boundary,
+    /// Past the left and right edges
+    col_boundary: Boundary,
+    rule: Rule,
+    /// Overrides `rule` while set
+    table: Option<Arc<RuleTable>>,
+    /// Overrides `rule` while set, see `set_block_rule`
+    block_rule: Option<BlockRule>,
+    /// Overrides `rule` while set, see `set_custom_rule`
+    custom_rule: Option<CustomRule>,
+    /// Overrides `rule` while set, see `set_elementary_rule`
+    elementary: Option<u8>,
+    /// Rectangles going by rules of their own, see `add_zone`
+    zones: Vec<Zone>,
+    zone_border: ZoneBorder,
+    generation: u64,
+    /// Tiles that changed last generation (or were edited since)
+    changed_tiles: Vec<bool>,
+    /// When each tile last changed, see `changed_since`
+    revisions: Revisions,
+    /// Live cells in each tile, see `population`
+    populations: TilePopulations,
+    /// Previous generations, most recent last, at most `past_depth` of them
+    past: VecDeque<Vec<Cell>>,
+    past_depth: usize,
+    /// Generations each cell has been alive for, see `age_at`
+    ages: Vec<u32>,
+    symmetry: Symmetry,
+    /// Population after each of the last `POPULATION_LOG` generations since recording started, if it's on
+    population_log: Option<VecDeque<u64>>,
+    /// Generations since each cell last died, if keeping track, see `since_death`
+    deaths: Option<Vec<u32>>,
+    /// How much each cell has been changing lately and how fast that's forgotten, if keeping track, see
+    /// `activity_at`
+    activity: Option<(Vec<f32>, f32)>,
+    auto_expand: Option<usize>,
+    turmites: Vec<Turmite>,
+    /// Decides the chances of stochastic rules, along with the generation and the cell
+    seed: u64,
+    coloring: Option<Coloring>,
+    /// Color of each cell, only kept up to date while `coloring` is on
+    colors: Vec<u8>,
+    paint: u8,
 }
 
-/// Coordinates, stored as a (row, column) tuple
+/// Saved copy of a universe's contents, see [`Universe::snapshot`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    cells: Vec<Cell>,
+    ages: Vec<u32>,
+    colors: Vec<u8>,
+    height: usize,
+    width: usize,
+    generation: u64,
+}
+
+impl Snapshot {
+    pub fn get_height(&self) -> usize     { self.height }
+    pub fn get_width(&self) -> usize      { self.width }
+    pub fn get_generation(&self) -> u64   { self.generation }
+}
+
+/// Two universes that were expected to be the same size aren't, as (height, width) coordinates
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DimensionMismatch {
+    pub expected: Coord,
+    pub found: Coord,
+}
+
+impl std::fmt::Display for DimensionMismatch {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected a {}x{} universe, found a {}x{} one",
+               self.expected.row, self.expected.col, self.found.row, self.found.col)
+    }
+}
+
+impl std::error::Error for DimensionMismatch {}
+
+/// Coordinates, stored as a (row, column) tuple
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct Coord {
     pub row: usize, 
     pub col: usize,
@@ -20,92 +190,916 @@ impl Coord {
     pub fn new(y: usize, x: usize) -> Coord { Self { row: y, col: x } }
 }
 
+/// Signed coordinates on an unbounded plane, stored as a (row, column) tuple
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PlaneCoord {
+    pub row: i64,
+    pub col: i64,
+}
+
+impl PlaneCoord {
+    pub fn new(y: i64, x: i64) -> PlaneCoord { Self { row: y, col: x } }
+}
+
+impl From<Coord> for PlaneCoord {
+    fn from(c: Coord) -> Self { Self::new(c.row as i64, c.col as i64) }
+}
+
 #[repr(u8)]
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub enum Cell {
     Dead,
     Alive,
+    /// Decaying under a Generations rule, this many steps out of being alive. In Wireworld, an electron tail.
+    Dying(u8),
+    /// Wireworld's wire, which electrons (live cells) travel along
+    Conductor,
 }
 
 impl Cell {
     fn is_alive(&self) -> bool { *self == Cell::Alive }
+
+    /// Numbered as in multi-state rules: dead 0, alive 1 and then the decaying states, with the conductor as
+    /// `conductor`, the state the rule gives it (see `Rule::conductor_state`)
+    pub fn state(&self, conductor: usize) -> usize {
+        match *self {
+            Cell::Dead      => 0,
+            Cell::Alive     => 1,
+            Cell::Dying(k)  => k as usize + 1,
+            Cell::Conductor => conductor,
+        }
+    }
+
+    /// The cell numbered `state` (see `Cell::state`), decaying states past 255 staying at the last one
+    pub fn from_state(state: usize, conductor: usize) -> Cell {
+        match state {
+            0                   => Cell::Dead,
+            1                   => Cell::Alive,
+            s if s == conductor => Cell::Conductor,
+            s                   => Cell::Dying(u8::try_from(s - 1).unwrap_or(u8::MAX)),
+        }
+    }
+}
+
+/// Where the existing cells end up when resizing a universe
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Center,
+    BottomRight,
+    /// The old (0, 0) cell lands on this (row, column) offset, which may be negative
+    Offset(isize, isize),
+}
+
+impl Anchor {
+    /// (row, column) offset of the old cells when going from `old` to `new` dimensions
+    pub fn offset(&self, old: Coord, new: Coord) -> (isize, isize) {
+        let grow = (new.row as isize - old.row as isize, new.col as isize - old.col as isize);
+        match *self {
+            Anchor::TopLeft      => (0, 0),
+            Anchor::Center       => (grow.0 / 2, grow.1 / 2),
+            Anchor::BottomRight  => grow,
+            Anchor::Offset(y, x) => (y, x),
+        }
+    }
+}
+
+/// How neighbors that fall past the edges of the universe are resolved
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Edges wrap around, so the universe is a torus
+    #[default]
+    Toroidal,
+    /// Everything outside the universe is a dead cell
+    Dead,
+    /// Edges act as mirrors, reflecting the cells next to them
+    Mirror,
+}
+
+impl Boundary {
+    /// Maps `pos` back into `0..len`, or `None` if it falls off the edge
+    fn resolve(&self, pos: isize, len: usize) -> Option<usize> {
+        let len = len as isize;
+        match self {
+            _ if (0..len).contains(&pos) => Some(pos as usize),
+            Boundary::Toroidal           => Some(pos.rem_euclid(len) as usize),
+            Boundary::Dead               => None,
+            Boundary::Mirror             => {
+                let period = 2 * len;
+                let folded = pos.rem_euclid(period);
+                Some(if folded < len { folded } else { period - 1 - folded } as usize)
+            }
+        }
+    }
 }
 
 impl Universe {
     pub fn new(height: usize, width: usize) -> Self {
         let cells = vec![Cell::Dead; width*height];
-        Self { cells: cells.clone(), back_buffer: cells, height, width }
+        let changed_tiles = vec![true; height.div_ceil(TILE) * width.div_ceil(TILE)];
+        Self { cells: cells.clone(), back_buffer: cells, height, width,
+               boundary: Boundary::default(), col_boundary: Boundary::default(), rule: Rule::default(), table: None,
+               block_rule: None, custom_rule: None, elementary: None,
+               zones: Vec::new(), zone_border: ZoneBorder::default(), generation: 0,
+               revisions: Revisions::new(changed_tiles.len()), populations: TilePopulations::new(changed_tiles.len()),
+               changed_tiles,
+               past: VecDeque::new(), past_depth: 0, ages: vec![0; width*height], symmetry: Symmetry::default(),
+               population_log: None, deaths: None, activity: None, auto_expand: None, turmites: Vec::new(), seed: 0, coloring: None,
+               colors: vec![0; width*height], paint: 0 }
     }
 
     pub fn is_alive(&self, c: Coord) -> bool { self[c].is_alive() }
 
-    pub fn set_dimensions(&mut self, new_dims: Coord) {
-        let old = self.clone();
-        let mut new = Self {
-            cells:       vec![Cell::Dead; new_dims.row*new_dims.col],
-            back_buffer: vec![Cell::Dead; new_dims.row*new_dims.col],
-            height: new_dims.row, width: new_dims.col
-        };
+    /// How many generations the cell has been alive for, counting the current one: 1 for a cell that was just
+    /// born (or placed), 0 for a dead one
+    pub fn age_at(&self, c: Coord) -> u32 {
+        if self.is_alive(c) { self.ages[self.coord_to_idx(c)].max(1) } else { 0 }
+    }
 
-        for old_y in 0..(old.height.min(new.height)) {
-            for old_x in 0..(old.width.min(new.width)) {
-                let coords = Coord::new(old_y, old_x);
-                new[coords] = old[coords];
-            }
+    /// How far along its changes the universe is now, see `changed_since`
+    pub fn revision(&self) -> Revision { self.revisions.current() }
+
+    /// Tiles within the `height`×`width` region starting at `top_left` whose cells may have changed after `since`,
+    /// or all of them if it's `None` or a revision of another universe, as their top-left corners and sizes clipped
+    /// to the region. Whatever keeps a copy of some of the cells only has to bring those up to date, and hold on to
+    /// the `revision` it's then at. Tiles also count as changed the generation after they did, as `previous_at`
+    /// changes with them, but ages, deaths and activity change everywhere every generation and aren't kept track of.
+    pub fn changed_since(&self, since: Option<Revision>, top_left: Coord, height: usize, width: usize)
+                         -> impl Iterator<Item = (Coord, Coord)> + '_ {
+        let tile_cols = self.width.div_ceil(TILE);
+        let (top, left) = (top_left.row.min(self.height), top_left.col.min(self.width));
+        let (bottom, right) = ((top_left.row + height).min(self.height), (top_left.col + width).min(self.width));
+        (top / TILE..bottom.div_ceil(TILE))
+            .flat_map(move |ty| (left / TILE..right.div_ceil(TILE)).map(move |tx| (ty, tx)))
+            .filter(move |&(ty, tx)| self.revisions.changed(ty * tile_cols + tx, since))
+            .map(move |(ty, tx)| {
+                let from = Coord::new((ty * TILE).max(top), (tx * TILE).max(left));
+                let to = Coord::new(((ty + 1) * TILE).min(bottom), ((tx + 1) * TILE).min(right));
+                (from, Coord::new(to.row - from.row, to.col - from.col))
+            })
+            .filter(|(_, size)| size.row > 0 && size.col > 0)
+    }
+
+    /// What the cell was a generation ago, out of the back buffer, so only right after a tick and before any edits
+    pub fn previous_at(&self, c: Coord) -> Cell { self.back_buffer.get(self.coord_to_idx(c)).copied().unwrap_or(Cell::Dead) }
+
+    /// Every cell along with its coordinates, row by row
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Coord, Cell)> + '_ {
+        self.cells.iter().enumerate().map(|(i, &cell)| (self.idx_to_coords(i), cell))
+    }
+
+    pub fn live_cells(&self) -> impl Iterator<Item = Coord> + '_ {
+        self.iter_cells().filter(|(_, cell)| cell.is_alive()).map(|(c, _)| c)
+    }
+
+    /// Live cells, only counted again in the tiles that changed since the last time
+    pub fn population(&self) -> usize {
+        let tile_cols = self.width.div_ceil(TILE);
+        self.populations.total(|t| {
+            let (rows, cols) = (t / tile_cols * TILE, t % tile_cols * TILE);
+            (rows..(rows + TILE).min(self.height))
+                .map(|y| self.cells[y * self.width + cols..y * self.width + (cols + TILE).min(self.width)].iter().filter(|c| c.is_alive()).count() as u32)
+                .sum()
+        }) as usize
+    }
+
+    /// Starts (or stops and forgets) logging the population every generation. The log begins with the current
+    /// population, and gets one more entry per tick, forgetting the oldest past the last 65536.
+    pub fn record_population(&mut self, on: bool) {
+        self.population_log = on.then(|| VecDeque::from([self.population() as u64]));
+    }
+
+    /// Logged populations, oldest first, see `record_population`. Empty while not recording.
+    pub fn population_history(&self) -> &VecDeque<u64> {
+        const EMPTY: &VecDeque<u64> = &VecDeque::new();
+        self.population_log.as_ref().unwrap_or(EMPTY)
+    }
+
+    /// Starts (or stops) keeping track of when cells die, for `since_death`
+    pub fn record_deaths(&mut self, on: bool) {
+        self.deaths = on.then(|| vec![0; self.cells.len()]);
+    }
+
+    /// Starts (or stops, with `None`) keeping track of how much each cell changes, for `activity_at`. Every
+    /// generation a cell's activity is multiplied by `decay`, and goes up by 1 if the cell changed.
+    pub fn record_activity(&mut self, decay: Option<f32>) {
+        self.activity = decay.map(|decay| (vec![0.0; self.cells.len()], decay.clamp(0.0, 1.0)));
+    }
+
+    /// How much the cell has been changing, higher the more often and the more recently it did: 1 for one that only
+    /// just changed, and up to `1 / (1 - decay)` for one that changes every generation. 0 while activity isn't
+    /// being recorded, see `record_activity`.
+    pub fn activity_at(&self, c: Coord) -> f32 {
+        self.activity.as_ref().and_then(|(activity, _)| activity.get(self.coord_to_idx(c))).copied().unwrap_or(0.0)
+    }
+
+    /// How many generations ago the cell died, counting from 1 for one that died this generation: 0 for live cells,
+    /// ones that haven't died since deaths started being recorded and whenever they aren't. Edits that move or
+    /// replace everything, such as shifting or resizing, start the count over.
+    pub fn since_death(&self, c: Coord) -> u32 {
+        self.deaths.as_ref().and_then(|deaths| deaths.get(self.coord_to_idx(c))).copied().unwrap_or(0)
+    }
+
+    /// Resizes the universe, placing the existing cells according to `anchor`. Whatever doesn't fit is lost.
+    pub fn set_dimensions(&mut self, new_dims: Coord, anchor: Anchor) {
+        let (dy, dx) = anchor.offset(Coord::new(self.height, self.width), new_dims);
+        if new_dims == Coord::new(self.height, self.width) && (dy, dx) == (0, 0) { return; }
+
+        let old_width      = self.width;
+        let old_cells      = std::mem::replace(&mut self.cells, vec![Cell::Dead; new_dims.row*new_dims.col]);
+        let old_ages       = std::mem::replace(&mut self.ages, vec![0; new_dims.row*new_dims.col]);
+        let old_colors     = std::mem::replace(&mut self.colors, vec![0; new_dims.row*new_dims.col]);
+        self.back_buffer   = vec![Cell::Dead; new_dims.row*new_dims.col];
+        self.height        = new_dims.row;
+        self.width         = new_dims.col;
+        self.changed_tiles = vec![true; self.height.div_ceil(TILE) * self.width.div_ceil(TILE)];
+        self.revisions.reset(self.changed_tiles.len());
+        self.populations.forget_all(self.changed_tiles.len());
+        self.past.clear();
+        self.forget_deaths();
+
+        // Only the span of each old row that lands inside gets copied over
+        let cols = (-dx).max(0) as usize..(self.width as isize - dx).clamp(0, old_width as isize) as usize;
+        let rows = old_cells.chunks(old_width.max(1)).zip(old_ages.chunks(old_width.max(1))).zip(old_colors.chunks(old_width.max(1)));
+        for (row, ((cells, ages), colors)) in rows.enumerate() {
+            let new_y = row as isize + dy;
+            if !(0..self.height as isize).contains(&new_y) || cols.is_empty() { continue; }
+            let start = self.coord_to_idx(Coord::new(new_y as usize, (cols.start as isize + dx) as usize));
+            self.cells[start..start + cols.len()].copy_from_slice(&cells[cols.clone()]);
+            self.ages[start..start + cols.len()].copy_from_slice(&ages[cols.clone()]);
+            self.colors[start..start + cols.len()].copy_from_slice(&colors[cols.clone()]);
         }
 
-        *self = new;
+        let (height, width) = (self.height as isize, self.width as isize);
+        self.turmites.retain_mut(|t| {
+            let (row, col) = (t.position.row as isize + dy, t.position.col as isize + dx);
+            t.position = Coord::new(row.max(0) as usize, col.max(0) as usize);
+            (0..height).contains(&row) && (0..width).contains(&col)
+        });
+    }
+
+    /// Top-left corner and size (height, width) of the smallest box holding every live cell, if there's any
+    pub fn bounding_box(&self) -> Option<(Coord, Coord)> {
+        pattern::bounding_box(&self.cells, self.width)
+    }
+
+    /// With a margin set, the universe grows before each tick whenever a live cell comes within `margin` cells
+    /// of an edge, so expanding patterns never run into it. `None` (the default) keeps the size fixed.
+    pub fn set_auto_expand(&mut self, margin: Option<usize>) { self.auto_expand = margin }
+    pub fn get_auto_expand(&self) -> Option<usize>          { self.auto_expand }
+
+    /// Grows the universe on every side where live cells come within `margin` of the edge
+    fn expand_to_fit(&mut self, margin: usize) {
+        let Some((top_left, size)) = self.bounding_box() else { return };
+        let grow = margin.max(TILE);
+        let near = |near: bool| if near { grow } else { 0 };
+        let top    = near(top_left.row < margin);
+        let left   = near(top_left.col < margin);
+        let bottom = near(top_left.row + size.row + margin > self.height);
+        let right  = near(top_left.col + size.col + margin > self.width);
+        if top + left + bottom + right == 0 { return; }
+
+        self.set_dimensions(Coord::new(self.height + top + bottom, self.width + left + right),
+                            Anchor::Offset(top as isize, left as isize));
     }
 
     pub fn get_width(&self) -> usize                 { self.width }
     pub fn get_height(&self) -> usize                { self.height }
+    pub fn get_boundary(&self) -> Boundary           { self.boundary }
+    pub fn set_boundary(&mut self, b: Boundary)      { self.set_boundaries(b, b) }
+    pub fn get_boundaries(&self) -> (Boundary, Boundary) { (self.boundary, self.col_boundary) }
+    pub fn get_rule(&self) -> Rule                   { self.rule }
+    pub fn get_rule_table(&self) -> Option<&RuleTable> { self.table.as_deref() }
+    /// States its cells go through: the rule table's own if one is loaded, and the rule's otherwise
+    pub fn states(&self) -> usize                    { self.table.as_ref().map_or(self.rule.states() as usize, |table| table.states()) }
+    /// State conductors are numbered as under the rule or rule table, see `Cell::state`
+    pub fn conductor_state(&self) -> usize {
+        self.table.as_ref().map_or(self.rule.conductor_state(), |table| table.conductor_state())
+    }
+    pub fn get_neighborhood(&self) -> Neighborhood   { self.rule.neighborhood() }
+    pub fn set_neighborhood(&mut self, n: Neighborhood) { self.set_rule(self.rule.with_neighborhood(n)) }
+    pub fn get_generation(&self) -> u64              { self.generation }
+    /// What empty space has turned into by now, see `Rule::background`. Cells are kept relative to it: under
+    /// rules with B0, those alive are the ones that differ from the background, whichever way it is.
+    pub fn background(&self) -> Cell                 { self.rule.background(self.generation) }
+    pub fn reset_generation(&mut self)               { self.generation = 0 }
+    pub fn set_generation(&mut self, g: u64)         { self.generation = g }
+    pub fn get_past_depth(&self) -> usize            { self.past_depth }
+    pub fn get_seed(&self) -> u64                    { self.seed }
+    pub fn set_seed(&mut self, seed: u64)            { self.seed = seed }
+    pub fn get_symmetry(&self) -> Symmetry           { self.symmetry }
+    pub fn set_symmetry(&mut self, s: Symmetry)      { self.symmetry = s }
     pub fn render(&self) -> String                   { self.to_string() }
-    pub fn toggle_pixel(&mut self, c: Coord)         { self[c] = !self[c]; }
-    pub fn set_pixel(&mut self, c: Coord, val: Cell) { self[c] = val }
+    pub fn toggle_pixel(&mut self, c: Coord)         { self.set_pixel(c, !self[c]) }
 
-    pub fn tick(&mut self) {
-        for y in 0..self.height {
-            for x in 0..self.width {
-                let c = Coord::new(y, x);
-                let i = self.coord_to_idx(c);
-                self.back_buffer[i] =
-                    match (self[c], self.alive_neighbor_count(Coord::new(y, x))) {
-                        (Cell::Alive, x) if x < 2           => Cell::Dead,
-                        (Cell::Alive, 2) | (Cell::Alive, 3) => Cell::Alive,
-                        (Cell::Alive, x) if x > 3           => Cell::Dead,
-                        (Cell::Dead, 3)                     => Cell::Alive,
-                        (current, _)                        => current,
-                    }
+    /// Sets the cell, along with its mirror images under the current symmetry. Live cells take the paint color.
+    pub fn set_pixel(&mut self, c: Coord, val: Cell) {
+        for image in self.symmetric_images(c) {
+            self[image] = val;
+            if val.is_alive() { self.set_color(image, self.paint); }
+        }
+    }
+
+    /// Sets the boundary past the top and bottom edges apart from the one past the sides, e.g. `(Dead, Toroidal)`
+    /// for a cylinder wrapping around horizontally. `get_boundary` then only tells the vertical one.
+    pub fn set_boundaries(&mut self, vertical: Boundary, horizontal: Boundary) {
+        self.boundary = vertical;
+        self.col_boundary = horizontal;
+        self.mark_all_dirty();
+    }
+
+    /// Also drops whatever was overriding the rule: a rule table, a block, custom or elementary rule
+    pub fn set_rule(&mut self, rule: Rule) {
+        self.rule = rule;
+        self.table = None;
+        self.block_rule = None;
+        self.custom_rule = None;
+        self.elementary = None;
+        self.mark_all_dirty();
+    }
+
+    /// Runs the universe by a rule loaded from a Golly `.rule` file instead of its `Rule`, until the next
+    /// `set_rule`, or `None` to go back to it
+    pub fn set_rule_table(&mut self, table: Option<RuleTable>) {
+        self.table = table.map(Arc::new);
+        if self.table.is_some() {
+            self.block_rule = None;
+            self.custom_rule = None;
+            self.elementary = None;
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Runs the universe by any function of a cell and how many live neighbors it has (under the rule's
+    /// neighborhood, up to 255) instead of its `Rule`, until the next `set_rule`, or `None` to go back to it
+    pub fn set_custom_rule(&mut self, rule: Option<Box<dyn Fn(Cell, u8) -> Cell + Send + Sync>>) {
+        self.custom_rule = rule.map(|rule| CustomRule(Arc::from(rule)));
+        if self.custom_rule.is_some() {
+            self.table = None;
+            self.block_rule = None;
+            self.elementary = None;
+        }
+        self.mark_all_dirty();
+    }
+
+    pub fn has_custom_rule(&self) -> bool { self.custom_rule.is_some() }
+
+    /// How many previous generations to keep around for `step_back`, 0 (the default) disables it
+    pub fn set_past_depth(&mut self, depth: usize) {
+        self.past_depth = depth;
+        while self.past.len() > depth { self.past.pop_front(); }
+    }
+
+    /// Rewinds to the previous generation, if it's still remembered. Edits made since are lost.
+    pub fn step_back(&mut self) -> bool {
+        let Some(previous) = self.past.pop_back() else { return false };
+        self.cells = previous;
+        self.generation = self.generation.saturating_sub(1);
+        for age in self.ages.iter_mut() { *age = age.saturating_sub(1); }
+        self.forget_deaths();
+        if let Some(log) = &mut self.population_log {
+            if log.len() > 1 { log.pop_back(); }
+        }
+        self.mark_all_dirty();
+        true
+    }
+
+    /// Kills every cell
+    pub fn clear(&mut self) {
+        self.cells.fill(Cell::Dead);
+        self.ages.fill(0);
+        self.forget_deaths();
+        self.mark_all_dirty();
+    }
+
+    /// Kills the live cells and brings everything else to life
+    pub fn invert(&mut self) {
+        for cell in self.cells.iter_mut() { *cell = !*cell; }
+        self.ages.fill(0);
+        self.forget_deaths();
+        self.mark_all_dirty();
+    }
+
+    /// Sets every cell in the rectangle between the two corners, both included. Whatever falls past the edges
+    /// is ignored.
+    pub fn fill_region(&mut self, top_left: Coord, bottom_right: Coord, val: Cell) {
+        for row in top_left.row..bottom_right.row.saturating_add(1).min(self.height) {
+            for col in top_left.col..bottom_right.col.saturating_add(1).min(self.width) {
+                self[Coord::new(row, col)] = val;
+            }
+        }
+    }
+
+    /// Sets the cell at `c`, and every cell in the same state connected to it through their sides, to `val`
+    pub fn flood_fill(&mut self, c: Coord, val: Cell) {
+        if c.row >= self.height || c.col >= self.width || self[c] == val { return; }
+        let target = self[c];
+        let mut stack = vec![c];
+        while let Some(c) = stack.pop() {
+            if self[c] != target { continue; }
+            self[c] = val;
+            if c.row > 0               { stack.push(Coord::new(c.row - 1, c.col)); }
+            if c.row + 1 < self.height { stack.push(Coord::new(c.row + 1, c.col)); }
+            if c.col > 0               { stack.push(Coord::new(c.row, c.col - 1)); }
+            if c.col + 1 < self.width  { stack.push(Coord::new(c.row, c.col + 1)); }
+        }
+    }
+
+    /// Moves everything `dy` rows down and `dx` columns right. With `wrap`, cells leaving one edge come back on
+    /// the opposite one; otherwise they're lost and dead cells come in.
+    pub fn shift(&mut self, dy: isize, dx: isize, wrap: bool) {
+        let (h, w) = (self.height as isize, self.width as isize);
+        let mut cells = vec![Cell::Dead; self.cells.len()];
+        let mut ages = vec![0; self.ages.len()];
+        let mut colors = vec![0; self.colors.len()];
+
+        for i in 0..self.cells.len() {
+            let Coord { row, col } = self.idx_to_coords(i);
+            let (mut y, mut x) = (row as isize + dy, col as isize + dx);
+            if wrap {
+                (y, x) = (y.rem_euclid(h), x.rem_euclid(w));
+            } else if !(0..h).contains(&y) || !(0..w).contains(&x) {
+                continue;
+            }
+            let j = self.coord_to_idx(Coord::new(y as usize, x as usize));
+            cells[j] = self.cells[i];
+            ages[j] = self.ages[i];
+            colors[j] = self.colors[i];
+        }
+
+        self.cells = cells;
+        self.ages = ages;
+        self.colors = colors;
+        self.forget_deaths();
+        self.mark_all_dirty();
+    }
+
+    /// Replaces everything with a random soup where each cell is alive with probability `density` (0 to 1).
+    /// The same seed always gives the same soup. Under cyclic rules every cell gets a random state instead.
+    pub fn randomize(&mut self, density: f32, seed: u64) {
+        let mut rng = rng::SplitMix64::new(seed);
+        let (states, conductor) = (self.rule.states() as u64, self.rule.conductor_state());
+        for cell in self.cells.iter_mut() {
+            *cell = match self.rule.is_cyclic() {
+                true  => Cell::from_state((rng.next_u64() % states) as usize, conductor),
+                false => if rng.next_f32() < density { Cell::Alive } else { Cell::Dead },
+            };
+        }
+        self.ages.fill(0);
+        self.forget_deaths();
+        self.scatter_colors(seed);
+        self.mark_all_dirty();
+    }
+
+    /// Replaces everything with a clumpy soup: cells are alive wherever Perlin noise (roughly -1 to 1) is above
+    /// `threshold`, so 0 gives about half the universe alive. `scale` is the typical size of a clump, in cells.
+    pub fn randomize_noise(&mut self, scale: f32, threshold: f32, seed: u64) {
+        let noise = noise::Perlin::new(seed);
+        let scale = scale.max(f32::EPSILON);
+        for i in 0..self.cells.len() {
+            let Coord { row, col } = self.idx_to_coords(i);
+            let value = noise.at(col as f32 / scale, row as f32 / scale);
+            self.cells[i] = if value > threshold { Cell::Alive } else { Cell::Dead };
+        }
+        self.ages.fill(0);
+        self.forget_deaths();
+        self.scatter_colors(seed);
+        self.mark_all_dirty();
+    }
+
+    /// Copies out the `height`×`width` region starting at `top_left`, with the same rule and boundary.
+    /// Whatever falls past the edges comes out dead.
+    pub fn extract(&self, top_left: Coord, height: usize, width: usize) -> Universe {
+        let mut region = Universe::new(height, width);
+        region.boundary = self.boundary;
+        region.col_boundary = self.col_boundary;
+        region.rule = self.rule;
+        region.past_depth = self.past_depth;
+        region.coloring = self.coloring;
+
+        for row in 0..height.min(self.height.saturating_sub(top_left.row)) {
+            for col in 0..width.min(self.width.saturating_sub(top_left.col)) {
+                let from = Coord::new(top_left.row + row, top_left.col + col);
+                region[Coord::new(row, col)] = self[from];
+                region.colors[row * width + col] = self.colors[self.coord_to_idx(from)];
             }
         }
+        region
+    }
+
+    /// Shrinks the universe in place down to the given region, like `extract` but keeping everything else about
+    /// it. Turmites and zones move along with the cells, and what's left out of the region goes.
+    pub fn crop_to(&mut self, top_left: Coord, height: usize, width: usize) {
+        self.set_dimensions(Coord::new(height, width), Anchor::Offset(-(top_left.row as isize), -(top_left.col as isize)));
+        // Cut down to the part of them inside the region, if any
+        self.zones.retain_mut(|zone| {
+            let clip = |start: usize, size: usize, from: usize, span: usize| {
+                (start.max(from) - from, (start + size).min(from + span).saturating_sub(from))
+            };
+            let (top, bottom) = clip(zone.top_left.row, zone.size.row, top_left.row, height);
+            let (left, right) = clip(zone.top_left.col, zone.size.col, top_left.col, width);
+            (zone.top_left, zone.size) = (Coord::new(top, left), Coord::new(bottom.saturating_sub(top), right.saturating_sub(left)));
+            zone.size.row > 0 && zone.size.col > 0
+        });
+        self.mark_all_dirty();
+    }
+
+    /// Every cell that differs between the two universes, as (coordinates, state here, state in `other`)
+    pub fn diff(&self, other: &Universe) -> Result<Vec<(Coord, Cell, Cell)>, DimensionMismatch> {
+        let (expected, found) = (Coord::new(self.height, self.width), Coord::new(other.height, other.width));
+        if expected != found { return Err(DimensionMismatch { expected, found }); }
+
+        Ok(self.iter_cells()
+            .zip(other.iter_cells())
+            .filter(|((_, mine), (_, theirs))| mine != theirs)
+            .map(|((c, mine), (_, theirs))| (c, mine, theirs))
+            .collect())
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot { cells: self.cells.clone(), ages: self.ages.clone(), colors: self.colors.clone(), height: self.height,
+                   width: self.width, generation: self.generation }
+    }
+
+    /// Brings back the cells, dimensions and generation of a snapshot. Rule and boundary are kept as they are.
+    pub fn restore(&mut self, snapshot: &Snapshot) {
+        self.set_dimensions(Coord::new(snapshot.height, snapshot.width), Anchor::TopLeft);
+        self.cells.clone_from(&snapshot.cells);
+        self.ages.clone_from(&snapshot.ages);
+        self.forget_deaths();
+        self.colors.clone_from(&snapshot.colors);
+        self.generation = snapshot.generation;
+        self.past.clear();
+        self.mark_all_dirty();
+    }
+
+    pub fn tick(&mut self) {
+        let custom = self.custom_rule.clone();
+        self.tick_by(custom.as_ref().map(|custom| &*custom.0 as &NextCell));
+    }
+
+    /// Ticks once by `rule`, a function of each cell and how many live neighbors it has (up to 255), instead of
+    /// whatever the universe runs by
+    pub fn tick_with(&mut self, rule: impl Fn(Cell, u8) -> Cell + Sync) {
+        // Tiles that were still under the usual rule needn't be under this one, nor the other way around
+        self.mark_all_dirty();
+        self.tick_by(Some(&rule));
+        self.mark_all_dirty();
+    }
+
+    fn tick_by(&mut self, custom: Option<&NextCell<'_>>) {
+        if let Some(margin) = self.auto_expand { self.expand_to_fit(margin); }
+        if self.past_depth > 0 {
+            if self.past.len() == self.past_depth { self.past.pop_front(); }
+            self.past.push_back(self.cells.clone());
+        }
+
+        let active = self.active_tiles();
+        let mut next = std::mem::take(&mut self.back_buffer);
+        // Cells are kept relative to the background, which rules with B0 keep changing
+        let rule = self.rule;
+        self.rule = rule.phased(self.generation);
+        match (self.block_rule, self.elementary, custom) {
+            (Some(rule), _, None) => self.tick_blocks(&rule, &mut next),
+            (_, Some(rule), None) => self.tick_elementary(rule, &mut next),
+            _                     => self.tick_into(&mut next, &active, custom),
+        }
+        if !self.zones.is_empty() && self.runs_by_rule() && custom.is_none() { self.tick_zones(&mut next); }
+        self.rule = rule;
+        self.back_buffer = next;
         swap(&mut self.cells, &mut self.back_buffer);
+        self.record_changes(&active);
+        self.update_ages();
+        self.update_colors();
+        self.generation += 1;
+        if !self.turmites.is_empty() { self.tick_turmites(); }
+        if self.population_log.is_some() {
+            let population = self.population() as u64;
+            if let Some(log) = &mut self.population_log {
+                if log.len() == POPULATION_LOG { log.pop_front(); }
+                log.push_back(population);
+            }
+        }
+    }
+
+    /// After a tick, with the previous generation in the back buffer
+    fn update_ages(&mut self) {
+        for ((age, now), before) in self.ages.iter_mut().zip(&self.cells).zip(&self.back_buffer) {
+            *age = match (before.is_alive(), now.is_alive()) {
+                (true, true)  => (*age).max(1) + 1,
+                (false, true) => 1,
+                (_, false)    => 0,
+            };
+        }
+        if let Some((activity, decay)) = &mut self.activity {
+            if activity.len() != self.cells.len() { *activity = vec![0.0; self.cells.len()]; }
+            for ((level, now), before) in activity.iter_mut().zip(&self.cells).zip(&self.back_buffer) {
+                *level = *level * *decay + if now != before { 1.0 } else { 0.0 };
+            }
+        }
+        let Some(deaths) = &mut self.deaths else { return };
+        if deaths.len() != self.cells.len() { *deaths = vec![0; self.cells.len()]; }
+        for ((since, now), before) in deaths.iter_mut().zip(&self.cells).zip(&self.back_buffer) {
+            *since = match (before.is_alive(), now.is_alive()) {
+                (_, true)      => 0,
+                (true, false)  => 1,
+                (false, false) => if *since > 0 { since.saturating_add(1) } else { 0 },
+            };
+        }
+    }
+
+    /// Forgets when cells died, for edits that move or replace them all
+    fn forget_deaths(&mut self) {
+        if let Some(deaths) = &mut self.deaths { *deaths = vec![0; self.cells.len()]; }
+    }
+
+    pub fn step_n(&mut self, n: u64) {
+        for _ in 0..n { self.tick(); }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    fn tick_into(&self, next: &mut [Cell], active: &[bool], custom: Option<&NextCell<'_>>) {
+        self.tick_rows(0, next, active, custom)
+    }
+
+    /// Splits the universe into bands of rows, each ticked on a thread of the pool
+    #[cfg(feature = "parallel")]
+    fn tick_into(&self, next: &mut [Cell], active: &[bool], custom: Option<&NextCell<'_>>) {
+        let pool = pool::Pool::get();
+        let band_height = self.height.div_ceil(pool.threads()).max(1);
+
+        pool.run(next.chunks_mut((band_height * self.width).max(1)).enumerate().map(|(band, rows)| {
+            Box::new(move || self.tick_rows(band * band_height, rows, active, custom)) as Box<dyn FnOnce() + Send + '_>
+        }));
+    }
+
+    /// Computes the next generation of the rows starting at `first_row` into `next`, counting neighbors a
+    /// whole row at a time. Cells outside of the `active` tiles are just carried over. A `custom` rule overrides
+    /// the universe's own.
+    fn tick_rows(&self, first_row: usize, next: &mut [Cell], active: &[bool], custom: Option<&NextCell<'_>>) {
+        let w = self.width;
+        if w == 0 { return; }
+        if let (Some(table), None) = (&self.table, custom) { return self.tick_rows_table(first_row, next, active, table); }
+        if self.rule.is_cyclic() && custom.is_none() { return self.tick_rows_cyclic(first_row, next, active); }
+        if let Neighborhood::Box { radius, include_center } = self.rule.neighborhood() {
+            return self.tick_rows_box(first_row, next, active, radius as usize, include_center, custom);
+        }
+
+        let tiles_per_row = w.div_ceil(TILE);
+        let mut rows = [vec![0; w + 2], vec![0; w + 2], vec![0; w + 2]];
+        let mut vertical = vec![0; w + 2];
+        let mut neighbors = vec![0; w];
+
+        for (r, next_row) in next.chunks_mut(w).enumerate() {
+            let y = first_row + r;
+            let current = &self.cells[y * w..(y + 1) * w];
+            let tile_row = &active[y / TILE * tiles_per_row..(y / TILE + 1) * tiles_per_row];
+            if !tile_row.contains(&true) {
+                next_row.copy_from_slice(current);
+                continue;
+            }
+
+            if !self.rule.is_totalistic() && custom.is_none() {
+                for (row, dy) in rows.iter_mut().zip([-1, 0, 1]) { self.load_padded_row(y as isize + dy, row); }
+                for (x, cell) in next_row.iter_mut().enumerate() {
+                    let roll = self.roll(y * w + x);
+                    *cell = if tile_row[x / TILE] { self.rule.next_configuration(current[x], configuration(&rows, x), roll) } else { current[x] };
+                }
+                continue;
+            }
+
+            if self.rule.neighborhood() == Neighborhood::Moore {
+                for (row, dy) in rows.iter_mut().zip([-1, 0, 1]) { self.load_padded_row(y as isize + dy, row); }
+                simd::add3(&rows[0], &rows[1], &rows[2], &mut vertical);
+                simd::window_sums(&vertical, &rows[1], &mut neighbors);
+            } else {
+                for (x, count) in neighbors.iter_mut().enumerate() { *count = self.alive_neighbor_count(Coord::new(y, x)); }
+            }
+
+            for (x, cell) in next_row.iter_mut().enumerate() {
+                *cell = match tile_row[x / TILE] {
+                    true  => self.next_cell(current[x], neighbors[x].into(), y * w + x, custom),
+                    false => current[x],
+                };
+            }
+        }
+    }
+
+    /// `tick_rows` for Larger than Life neighborhoods. Column sums over the `2 * radius + 1` rows around the
+    /// current one are kept running from row to row, and slid along horizontally, so each cell costs the same
+    /// whatever the radius.
+    fn tick_rows_box(&self, first_row: usize, next: &mut [Cell], active: &[bool], radius: usize, include_center: bool,
+                     custom: Option<&NextCell<'_>>) {
+        let (w, r) = (self.width, radius as isize);
+        let tiles_per_row = w.div_ceil(TILE);
+        let mut row = vec![0; w + 2 * radius];
+        let mut columns = vec![0u16; w + 2 * radius];
+
+        for dy in -r..=r {
+            self.load_padded_row(first_row as isize + dy, &mut row);
+            for (sum, &flag) in columns.iter_mut().zip(&row) { *sum += flag as u16; }
+        }
+
+        for (i, next_row) in next.chunks_mut(w).enumerate() {
+            let y = first_row + i;
+            let current = &self.cells[y * w..(y + 1) * w];
+            let tile_row = &active[y / TILE * tiles_per_row..(y / TILE + 1) * tiles_per_row];
+
+            if tile_row.contains(&true) {
+                let mut window = columns[..2 * radius].iter().sum::<u16>();
+                for (x, cell) in next_row.iter_mut().enumerate() {
+                    window += columns[x + 2 * radius];
+                    let neighbors = window - (!include_center && current[x].is_alive()) as u16;
+                    *cell = match tile_row[x / TILE] {
+                        true  => self.next_cell(current[x], neighbors, y * w + x, custom),
+                        false => current[x],
+                    };
+                    window -= columns[x];
+                }
+            } else {
+                next_row.copy_from_slice(current);
+            }
+
+            // Slides the column sums one row down
+            self.load_padded_row(y as isize - r, &mut row);
+            for (sum, &flag) in columns.iter_mut().zip(&row) { *sum -= flag as u16; }
+            self.load_padded_row(y as isize + r + 1, &mut row);
+            for (sum, &flag) in columns.iter_mut().zip(&row) { *sum += flag as u16; }
+        }
+    }
+
+    /// `tick_rows` going by a rule table, which needs the whole state of every neighbor
+    fn tick_rows_table(&self, first_row: usize, next: &mut [Cell], active: &[bool], table: &RuleTable) {
+        let w = self.width;
+        let tiles_per_row = w.div_ceil(TILE);
+        let mut rows = [vec![Cell::Dead; w + 2], vec![Cell::Dead; w + 2], vec![Cell::Dead; w + 2]];
+
+        for (r, next_row) in next.chunks_mut(w).enumerate() {
+            let y = first_row + r;
+            let current = &self.cells[y * w..(y + 1) * w];
+            let tile_row = &active[y / TILE * tiles_per_row..(y / TILE + 1) * tiles_per_row];
+            if !tile_row.contains(&true) {
+                next_row.copy_from_slice(current);
+                continue;
+            }
+
+            for (row, dy) in rows.iter_mut().zip([-1, 0, 1]) { self.load_padded(y as isize + dy, row, Cell::Dead, |c| c); }
+            for (x, cell) in next_row.iter_mut().enumerate() {
+                let [above, here, below] = [&rows[0][x..x + 3], &rows[1][x..x + 3], &rows[2][x..x + 3]];
+                let neighbors = [above[0], above[1], above[2], here[0], here[2], below[0], below[1], below[2]];
+                *cell = if tile_row[x / TILE] { table.next(current[x], neighbors) } else { current[x] };
+            }
+        }
+    }
+
+    /// `tick_rows` for cyclic rules, which count the neighbors in whichever state comes after each cell's
+    fn tick_rows_cyclic(&self, first_row: usize, next: &mut [Cell], active: &[bool]) {
+        let w = self.width;
+        let tiles_per_row = w.div_ceil(TILE);
+        let neighborhood = self.rule.neighborhood();
+        let offsets = [neighborhood.all_offsets(0), neighborhood.all_offsets(1)];
+
+        for (r, next_row) in next.chunks_mut(w).enumerate() {
+            let y = first_row + r;
+            let current = &self.cells[y * w..(y + 1) * w];
+            let tile_row = &active[y / TILE * tiles_per_row..(y / TILE + 1) * tiles_per_row];
+            for (x, cell) in next_row.iter_mut().enumerate() {
+                if !tile_row[x / TILE] {
+                    *cell = current[x];
+                    continue;
+                }
+                let successor = self.rule.successor(current[x]);
+                let successors = offsets[y % 2].iter().filter(|&&(dy, dx)| {
+                    let row = self.boundary.resolve(y as isize + dy, self.height);
+                    let col = self.col_boundary.resolve(x as isize + dx, w);
+                    matches!((row, col), (Some(row), Some(col)) if self.cells[row * w + col] == successor)
+                }).count();
+                *cell = self.rule.next_cyclic(current[x], successors as u16);
+            }
+        }
+    }
+
+    /// Next state of the cell at index `i`, with `neighbors` of them alive
+    fn next_cell(&self, current: Cell, neighbors: u16, i: usize, custom: Option<&NextCell<'_>>) -> Cell {
+        match custom {
+            Some(rule) => rule(current, neighbors.min(u8::MAX.into()) as u8),
+            None       => self.rule.next_rolled(current, neighbors, self.roll(i)),
+        }
+    }
+
+    /// Live neighbors of the cell under the rule's neighborhood, going through the boundary one by one
+    pub fn alive_neighbor_count(&self, c: Coord) -> u8 {
+        let alive_at = |&(dy, dx): &(isize, isize)| {
+            let y = self.boundary.resolve(c.row as isize + dy, self.height);
+            let x = self.col_boundary.resolve(c.col as isize + dx, self.width);
+            matches!((y, x), (Some(y), Some(x)) if self.cells[y * self.width + x].is_alive())
+        };
+        self.rule.neighborhood().offsets(c.row).iter().filter(|offset| alive_at(offset)).count() as u8
+    }
+
+    /// Whether nothing overrides the universe's `Rule`
+    pub fn runs_by_rule(&self) -> bool {
+        self.table.is_none() && self.block_rule.is_none() && self.custom_rule.is_none() && self.elementary.is_none()
     }
 
-    fn alive_neighbor_count(&self, c: Coord) -> u8 {
-        let mut cnt = 0;
-        let Coord { row: y, col: x } = c;
+    /// Random number for the cell at index `i` this generation, always the same for any given seed
+    fn roll(&self, i: usize) -> u16 { self.roll_by(&self.rule, i) }
+
+    /// `roll` for a cell going by `rule`, which may not be the universe's
+    fn roll_by(&self, rule: &Rule, i: usize) -> u16 {
+        if !rule.is_stochastic() { return 0; }
+        rng::SplitMix64::new(self.seed ^ self.generation.rotate_left(32) ^ i as u64).next_u64() as u16
+    }
+
+    fn tile_of(&self, c: Coord) -> usize { c.row / TILE * self.width.div_ceil(TILE) + c.col / TILE }
+    fn mark_all_dirty(&mut self) {
+        self.changed_tiles.fill(true);
+        self.revisions.reset(self.changed_tiles.len());
+        self.populations.forget_all(self.changed_tiles.len());
+    }
 
-        for dy in [-1, 0, 1] {
-            for dx in [-1, 0, 1] {
-                if dx == 0 && dy == 0 { continue; }
-                let new_x = (x as i32 + dx).rem_euclid(self.width as i32) as usize;
-                let new_y = (y as i32 + dy).rem_euclid(self.height as i32) as usize;
+    /// Tiles that may change this generation: the ones that changed last time, and the ones around them within
+    /// the neighborhood's reach. Under stochastic rules, tables or custom rules where empty space doesn't stay empty
+    /// or block and elementary rules (whose blocks or rows shift every generation), anything may change anywhere.
+    /// Neither are zones kept track of, nor rules with B0, which go by a different rule every other generation.
+    fn active_tiles(&self) -> Vec<bool> {
+        let quiescent = self.table.as_ref().is_none_or(|table| table.is_quiescent())
+            && self.custom_rule.as_ref().is_none_or(|custom| (custom.0)(Cell::Dead, 0) == Cell::Dead);
+        let shifting = self.block_rule.is_some() || self.elementary.is_some() || !self.zones.is_empty()
+            || self.rule.births_on(0);
+        if self.rule.is_stochastic() || !quiescent || shifting { return vec![true; self.changed_tiles.len()]; }
+        let (tile_rows, tile_cols) = (self.height.div_ceil(TILE), self.width.div_ceil(TILE));
+        let reach = self.rule.neighborhood().radius().div_ceil(TILE) as isize;
+        let mut active = vec![false; self.changed_tiles.len()];
 
-                if self[Coord::new(new_y, new_x)].is_alive() { cnt += 1; }
+        for (t, _) in self.changed_tiles.iter().enumerate().filter(|(_, &changed)| changed) {
+            let (ty, tx) = ((t / tile_cols) as isize, (t % tile_cols) as isize);
+            for dy in -reach..=reach {
+                for dx in -reach..=reach {
+                    let y = self.boundary.resolve(ty + dy, tile_rows);
+                    let x = self.col_boundary.resolve(tx + dx, tile_cols);
+                    if let (Some(y), Some(x)) = (y, x) { active[y * tile_cols + x] = true; }
+                }
             }
         }
+        active
+    }
+
+    /// After a tick, with the previous generation in the back buffer, finds out which tiles changed
+    fn record_changes(&mut self, active: &[bool]) {
+        let tile_cols = self.width.div_ceil(TILE);
+        // What changed last generation changes again for `previous_at`
+        let before = self.changed_tiles.clone();
+        for (t, changed) in self.changed_tiles.iter_mut().enumerate() {
+            *changed = active[t] && {
+                let (rows, cols) = (t / tile_cols * TILE, t % tile_cols * TILE);
+                (rows..(rows + TILE).min(self.height)).any(|y| {
+                    let span = y * self.width + cols..y * self.width + (cols + TILE).min(self.width);
+                    self.cells[span.clone()] != self.back_buffer[span]
+                })
+            };
+        }
+        self.revisions.touch_where(|t| before[t] || self.changed_tiles[t]);
+        for (t, _) in self.changed_tiles.iter().enumerate().filter(|(_, &changed)| changed) { self.populations.forget(t); }
+    }
 
-        cnt
+    /// Alive flags (0 or 1) of row `y`, padded with as many cells on each side as `out` has room for (usually
+    /// one), all resolved through the boundary
+    fn load_padded_row(&self, y: isize, out: &mut [u8]) { self.load_padded(y, out, 0, |cell| cell.is_alive() as u8) }
+
+    /// `load_padded_row` for anything `f` makes of the cells, `dead` being what lies past dead boundaries
+    fn load_padded<T: Copy>(&self, y: isize, out: &mut [T], dead: T, f: impl Fn(Cell) -> T) {
+        let Some(y) = self.boundary.resolve(y, self.height) else { return out.fill(dead) };
+        let w = self.width;
+        let pad = (out.len() - w) / 2;
+        let row = &self.cells[y * w..(y + 1) * w];
+        let at = |x: isize| self.col_boundary.resolve(x, w).map_or(dead, |x| f(row[x]));
+
+        for (out, &cell) in out[pad..pad + w].iter_mut().zip(row) { *out = f(cell); }
+        for i in 0..pad {
+            out[pad - 1 - i] = at(-1 - i as isize);
+            out[pad + w + i] = at((w + i) as isize);
+        }
     }
 
     pub fn coord_to_idx(&self, c: Coord) -> usize { c.col + self.width * c.row }
     pub fn idx_to_coords(&self, i: usize) -> Coord { Coord { row: i / self.width, col: i % self.width } }
 }
 
+/// Which of the cells around the one at column `x` are alive, as `Rule::next_configuration` takes them, from the
+/// padded rows above, on and below it
+fn configuration(rows: &[Vec<u8>; 3], x: usize) -> u8 {
+    let [above, here, below] = [&rows[0][x..x + 3], &rows[1][x..x + 3], &rows[2][x..x + 3]];
+    above[0] | above[1] << 1 | above[2] << 2 | here[0] << 3 | here[2] << 4 | below[0] << 5 | below[1] << 6 | below[2] << 7
+}
+
 impl std::fmt::Display for Cell {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let symbol = match self {
             Cell::Alive => '◼',
+            Cell::Dying(_) => '▣',
+            Cell::Conductor => '▤',
             Cell::Dead => '◻',
         };
         write!(f, "{}", symbol)
@@ -125,6 +1119,74 @@ impl std::fmt::Display for Universe {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseUniverseError {
+    /// Something that isn't a cell nor whitespace, at this (row, column) of the text
+    InvalidCharacter { row: usize, col: usize, found: char },
+}
+
+impl std::fmt::Display for ParseUniverseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseUniverseError::InvalidCharacter { row, col, found } =>
+                write!(f, "unexpected character '{found}' at row {row}, column {col}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseUniverseError {}
+
+impl FromStr for Universe {
+    type Err = ParseUniverseError;
+
+    /// Reads back what `Display` writes, and leniently also `.`/`#`, `.`/`O` or `.`/`*` grids. Whitespace within
+    /// rows and blank lines are ignored, and short rows are padded with dead cells.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rows = s.lines()
+            .filter(|line| !line.trim().is_empty())
+            .enumerate()
+            .map(|(row, line)| {
+                line.chars()
+                    .filter(|c| !c.is_whitespace())
+                    .enumerate()
+                    .map(|(col, c)| match c {
+                        '◼' | '#' | 'O' | 'o' | '*' | 'X' => Ok(Cell::Alive),
+                        '◻' | '.' | '_' | 'b'             => Ok(Cell::Dead),
+                        '▣'                               => Ok(Cell::Dying(1)),
+                        '▤'                               => Ok(Cell::Conductor),
+                        found                             => Err(ParseUniverseError::InvalidCharacter { row, col, found }),
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        let mut universe = Universe::new(rows.len(), width);
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, &cell) in cells.iter().enumerate() { universe[Coord::new(row, col)] = cell; }
+        }
+        Ok(universe)
+    }
+}
+
+/// Universes compare (and hash) by their contents alone: dimensions and cells, regardless of the rule,
+/// generation or history
+impl PartialEq for Universe {
+    fn eq(&self, other: &Self) -> bool {
+        (self.height, self.width) == (other.height, other.width) && self.cells == other.cells
+    }
+}
+
+impl Eq for Universe {}
+
+impl Hash for Universe {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.height.hash(state);
+        self.width.hash(state);
+        self.cells.hash(state);
+    }
+}
+
 impl Index<usize> for Universe {
     type Output = Cell;
 
@@ -135,6 +1197,10 @@ impl Index<usize> for Universe {
 
 impl IndexMut<usize> for Universe {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        let tile = self.tile_of(self.idx_to_coords(index));
+        self.changed_tiles[tile] = true;
+        self.revisions.touch(tile);
+        self.populations.forget(tile);
         &mut self.cells[index]
     }
 }
@@ -149,6 +1215,10 @@ impl Index<Coord> for Universe {
 
 impl IndexMut<Coord> for Universe {
     fn index_mut(&mut self, index: Coord) -> &mut Self::Output {
+        let tile = self.tile_of(index);
+        self.changed_tiles[tile] = true;
+        self.revisions.touch(tile);
+        self.populations.forget(tile);
         let idx = self.coord_to_idx(index);
         &mut self.cells[idx]
     }
@@ -159,8 +1229,91 @@ impl Not for Cell {
 
     fn not(self) -> Self::Output {
         match self {
-            Cell::Dead  => Cell::Alive,
-            Cell::Alive => Cell::Dead,
+            Cell::Dead | Cell::Dying(_) | Cell::Conductor => Cell::Alive,
+            Cell::Alive                                   => Cell::Dead,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn alive(universe: &Universe) -> Vec<Coord> {
+        (0..universe.height).flat_map(|row| (0..universe.width).map(move |col| Coord::new(row, col)))
+            .filter(|&c| universe[c] == Cell::Alive)
+            .collect()
+    }
+
+    #[test]
+    fn clear_and_invert() {
+        let mut universe = Universe::new(3, 4);
+        universe[Coord::new(1, 2)] = Cell::Alive;
+        universe.invert();
+        assert_eq!(alive(&universe).len(), 11);
+        assert_eq!(universe[Coord::new(1, 2)], Cell::Dead);
+        universe.clear();
+        assert!(alive(&universe).is_empty());
+    }
+
+    #[test]
+    fn fill_region_clips_to_the_edges() {
+        let mut universe = Universe::new(4, 5);
+        universe.fill_region(Coord::new(1, 1), Coord::new(2, 2), Cell::Alive);
+        assert_eq!(alive(&universe), [Coord::new(1, 1), Coord::new(1, 2), Coord::new(2, 1), Coord::new(2, 2)]);
+
+        universe.clear();
+        universe.fill_region(Coord::new(3, 3), Coord::new(100, usize::MAX), Cell::Alive);
+        assert_eq!(alive(&universe), [Coord::new(3, 3), Coord::new(3, 4)]);
+
+        universe.clear();
+        universe.fill_region(Coord::new(7, 0), Coord::new(9, 9), Cell::Alive);
+        assert!(alive(&universe).is_empty());
+
+        let mut empty = Universe::new(0, 0);
+        empty.fill_region(Coord::new(0, 0), Coord::new(3, 3), Cell::Alive);
+        assert_eq!(empty.population(), 0);
+    }
+
+    #[test]
+    fn crop_to_keeps_the_settings() {
+        let mut universe = Universe::new(20, 30);
+        universe.set_rule(Rule::CONWAY.with_neighborhood(Neighborhood::Hex));
+        universe.set_boundaries(Boundary::Dead, Boundary::Mirror);
+        universe.set_block_rule(Some(BlockRule::CRITTERS));
+        universe.set_symmetry(Symmetry::Rotational);
+        universe.set_seed(9);
+        universe.set_zone_border(ZoneBorder::Closed);
+        universe.add_zone(Zone { top_left: Coord::new(2, 2), size: Coord::new(10, 10), rule: Rule::CONWAY });
+        universe.add_zone(Zone { top_left: Coord::new(0, 0), size: Coord::new(3, 3), rule: Rule::CONWAY });
+        universe.add_turmite(Turmite::langtons_ant(Coord::new(7, 8)));
+        universe.add_turmite(Turmite::langtons_ant(Coord::new(1, 1)));
+        universe[Coord::new(6, 9)] = Cell::Alive;
+        universe.set_generation(12);
+
+        universe.crop_to(Coord::new(5, 5), 10, 12);
+        assert_eq!((universe.get_height(), universe.get_width()), (10, 12));
+        assert_eq!(alive(&universe), [Coord::new(1, 4)]);
+        assert_eq!(universe.get_neighborhood(), Neighborhood::Hex);
+        assert_eq!(universe.get_boundaries(), (Boundary::Dead, Boundary::Mirror));
+        assert_eq!(universe.get_block_rule(), Some(BlockRule::CRITTERS));
+        assert_eq!(universe.get_symmetry(), Symmetry::Rotational);
+        assert_eq!((universe.get_seed(), universe.get_generation()), (9, 12));
+        assert_eq!(universe.get_zone_border(), ZoneBorder::Closed);
+        assert_eq!(universe.zones().iter().map(|zone| (zone.top_left, zone.size)).collect::<Vec<_>>(),
+                   [(Coord::new(0, 0), Coord::new(7, 7))]);
+        assert_eq!(universe.turmites().iter().map(|t| t.position).collect::<Vec<_>>(), [Coord::new(2, 3)]);
+    }
+
+    #[test]
+    fn conductors_get_a_state_of_their_own() {
+        for rule in [Rule::default(), Rule::WIREWORLD, "B2/S345/C4".parse().unwrap()] {
+            let conductor = rule.conductor_state();
+            let cells = (0..rule.states() as usize).map(|s| Cell::from_state(s, conductor)).collect::<Vec<_>>();
+            assert!(!cells.contains(&Cell::Conductor));
+            assert_eq!(Cell::from_state(Cell::Conductor.state(conductor), conductor), Cell::Conductor);
+            for cell in cells { assert_eq!(Cell::from_state(cell.state(conductor), conductor), cell); }
         }
+        assert_eq!(Rule::WIREWORLD.conductor_state(), 3);
     }
 }
@@ -0,0 +1,149 @@
+use std::fmt;
+
+use crate::{Pattern, Rule};
+
+/// Classic patterns of Conway's Life, built in
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LibraryPattern {
+    Block,
+    Beehive,
+    Loaf,
+    Boat,
+    Blinker,
+    Toad,
+    Beacon,
+    Pulsar,
+    Pentadecathlon,
+    Glider,
+    Lwss,
+    Mwss,
+    Hwss,
+    GosperGliderGun,
+    RPentomino,
+    Acorn,
+    Diehard,
+}
+
+/// What a library pattern does left on its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PatternKind {
+    StillLife,
+    Oscillator { period: u32 },
+    /// Back to its first phase every `period` generations, `displacement` (rows, columns) cells away
+    Spaceship { period: u32, displacement: (u32, u32) },
+    /// Sends out a glider every `period` generations
+    Gun { period: u32 },
+    /// Small patterns that take `lifespan` generations to settle down, or die out
+    Methuselah { lifespan: u32 },
+}
+
+impl LibraryPattern {
+    pub const ALL: [LibraryPattern; 17] = [
+        LibraryPattern::Block, LibraryPattern::Beehive, LibraryPattern::Loaf, LibraryPattern::Boat,
+        LibraryPattern::Blinker, LibraryPattern::Toad, LibraryPattern::Beacon, LibraryPattern::Pulsar,
+        LibraryPattern::Pentadecathlon, LibraryPattern::Glider, LibraryPattern::Lwss, LibraryPattern::Mwss,
+        LibraryPattern::Hwss, LibraryPattern::GosperGliderGun, LibraryPattern::RPentomino, LibraryPattern::Acorn,
+        LibraryPattern::Diehard,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LibraryPattern::Block           => "Block",
+            LibraryPattern::Beehive         => "Beehive",
+            LibraryPattern::Loaf            => "Loaf",
+            LibraryPattern::Boat            => "Boat",
+            LibraryPattern::Blinker         => "Blinker",
+            LibraryPattern::Toad            => "Toad",
+            LibraryPattern::Beacon          => "Beacon",
+            LibraryPattern::Pulsar          => "Pulsar",
+            LibraryPattern::Pentadecathlon  => "Pentadecathlon",
+            LibraryPattern::Glider          => "Glider",
+            LibraryPattern::Lwss            => "Lightweight spaceship",
+            LibraryPattern::Mwss            => "Middleweight spaceship",
+            LibraryPattern::Hwss            => "Heavyweight spaceship",
+            LibraryPattern::GosperGliderGun => "Gosper glider gun",
+            LibraryPattern::RPentomino      => "R-pentomino",
+            LibraryPattern::Acorn           => "Acorn",
+            LibraryPattern::Diehard         => "Diehard",
+        }
+    }
+
+    pub fn kind(&self) -> PatternKind {
+        match self {
+            LibraryPattern::Block | LibraryPattern::Beehive | LibraryPattern::Loaf | LibraryPattern::Boat
+                                            => PatternKind::StillLife,
+            LibraryPattern::Blinker | LibraryPattern::Toad | LibraryPattern::Beacon
+                                            => PatternKind::Oscillator { period: 2 },
+            LibraryPattern::Pulsar          => PatternKind::Oscillator { period: 3 },
+            LibraryPattern::Pentadecathlon  => PatternKind::Oscillator { period: 15 },
+            LibraryPattern::Glider          => PatternKind::Spaceship { period: 4, displacement: (1, 1) },
+            LibraryPattern::Lwss | LibraryPattern::Mwss | LibraryPattern::Hwss
+                                            => PatternKind::Spaceship { period: 4, displacement: (0, 2) },
+            LibraryPattern::GosperGliderGun => PatternKind::Gun { period: 30 },
+            LibraryPattern::RPentomino      => PatternKind::Methuselah { lifespan: 1103 },
+            LibraryPattern::Acorn           => PatternKind::Methuselah { lifespan: 5206 },
+            LibraryPattern::Diehard         => PatternKind::Methuselah { lifespan: 130 },
+        }
+    }
+
+    /// The pattern's cells in the Run Length Encoded format, without the header
+    pub fn rle(&self) -> &'static str {
+        match self {
+            LibraryPattern::Block           => "2o$2o!",
+            LibraryPattern::Beehive         => "b2o$o2bo$b2o!",
+            LibraryPattern::Loaf            => "b2o$o2bo$bobo$2bo!",
+            LibraryPattern::Boat            => "2o$obo$bo!",
+            LibraryPattern::Blinker         => "3o!",
+            LibraryPattern::Toad            => "b3o$3o!",
+            LibraryPattern::Beacon          => "2o$2o$2b2o$2b2o!",
+            LibraryPattern::Pulsar          => "2b3o3b3o2$o4bobo4bo$o4bobo4bo$o4bobo4bo$2b3o3b3o2$2b3o3b3o$o4bobo4bo$\
+                                                o4bobo4bo$o4bobo4bo2$2b3o3b3o!",
+            LibraryPattern::Pentadecathlon  => "2bo4bo$2ob4ob2o$2bo4bo!",
+            LibraryPattern::Glider          => "bo$2bo$3o!",
+            LibraryPattern::Lwss            => "bo2bo$o$o3bo$4o!",
+            LibraryPattern::Mwss            => "3bo$bo3bo$o$o4bo$5o!",
+            LibraryPattern::Hwss            => "3b2o$bo4bo$o$o5bo$6o!",
+            LibraryPattern::GosperGliderGun => "24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b\
+                                                obo$10bo5bo7bo$11bo3bo$12b2o!",
+            LibraryPattern::RPentomino      => "b2o$2o$bo!",
+            LibraryPattern::Acorn           => "bo$3bo$2o2b3o!",
+            LibraryPattern::Diehard         => "6bo$2o$bo3b3o!",
+        }
+    }
+
+    pub fn pattern(&self) -> Pattern {
+        // Roomy enough for any of them, then trimmed down to the pattern's own size
+        let rle = format!("x = 64, y = 64\n{}", self.rle());
+        Pattern::from_rle(&rle).expect("library patterns should parse").0.trim()
+    }
+
+    /// Every library pattern runs under Conway's Life
+    pub fn rule(&self) -> Rule { Rule::CONWAY }
+
+    /// Library pattern by its name, ignoring case, spaces and dashes, or its short name (such as `lwss`)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let simplify = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+        let name = simplify(name);
+        LibraryPattern::ALL.into_iter().find(|p| simplify(p.name()) == name || simplify(&format!("{p:?}")) == name)
+    }
+}
+
+impl fmt::Display for LibraryPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.name()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_pattern_parses_to_its_own_size() {
+        for pattern in LibraryPattern::ALL {
+            let cells = pattern.pattern();
+            assert!(cells.population() > 0, "{pattern} is empty");
+            assert_eq!(cells.trim(), cells, "{pattern} has dead margins");
+        }
+        let gun = LibraryPattern::GosperGliderGun.pattern();
+        assert_eq!((gun.get_height(), gun.get_width()), (9, 36));
+    }
+}
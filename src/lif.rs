@@ -0,0 +1,165 @@
+use std::fmt;
+
+use crate::{rle::{MAX_CELLS, MAX_SIDE}, Cell, Coord, Neighborhood, Pattern, Rule};
+
+/// Longest line `to_life105` writes: wider patterns get split into several blocks
+const LINE_LENGTH: usize = 80;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseLifeError {
+    /// The first line must be `#Life 1.05` or `#Life 1.06`
+    MissingHeader,
+    /// The `#R` line's rule isn't one `Rule` parses
+    UnsupportedRule(String),
+    /// A line that couldn't be made sense of, counting from 1
+    InvalidLine(usize),
+    /// The live cells span more than `MAX_SIDE` to a side, or `MAX_CELLS` in all
+    TooLarge,
+}
+
+impl Pattern {
+    /// Reads a pattern in either of the Life formats, telling them apart by their header. Life 1.05 has `#P x y`
+    /// lines placing each block of `.` and `*` rows below them, and maybe the rule as `#N` (for Conway's) or
+    /// `#R 23/3`. Life 1.06 is just the `x y` coordinates of every live cell, one per line. Either way the
+    /// pattern is as big as its live cells, so blocks left empty don't count.
+    pub fn from_life(s: &str) -> Result<(Pattern, Option<Rule>), ParseLifeError> {
+        let mut lines = s.lines().enumerate().map(|(i, line)| (i + 1, line.trim()));
+        let version = match lines.next() {
+            Some((_, "#Life 1.05")) => 5,
+            Some((_, "#Life 1.06")) => 6,
+            _                       => return Err(ParseLifeError::MissingHeader),
+        };
+
+        // Live cells as (x, y), that is column and row
+        let mut cells = Vec::new();
+        let mut rule = None;
+        // Where the current 1.05 block starts, and the row it's up to
+        let (mut block, mut y) = ((0, 0), 0);
+        for (number, line) in lines.filter(|(_, line)| !line.is_empty()) {
+            let invalid = ParseLifeError::InvalidLine(number);
+            let coordinates = |s: &str| {
+                let mut parts = s.split_whitespace().map(str::parse::<i64>);
+                match (parts.next(), parts.next(), parts.next()) {
+                    (Some(Ok(x)), Some(Ok(y)), None) => Ok((x, y)),
+                    _                                => Err(invalid.clone()),
+                }
+            };
+
+            if version == 6 {
+                if !line.starts_with('#') { cells.push(coordinates(line)?); }
+            } else if let Some(position) = line.strip_prefix("#P") {
+                (block, y) = (coordinates(position)?, 0);
+            } else if line == "#N" {
+                rule = Some(Rule::CONWAY);
+            } else if let Some(name) = line.strip_prefix("#R") {
+                rule = Some(name.trim().parse::<Rule>().map_err(|_| ParseLifeError::UnsupportedRule(name.trim().to_owned()))?);
+            } else if !line.starts_with('#') {
+                for (x, c) in line.chars().enumerate() {
+                    match c {
+                        '.'       => {}
+                        '*' | 'O' => {
+                            let x = block.0.checked_add(x as i64).ok_or(ParseLifeError::TooLarge)?;
+                            cells.push((x, block.1.checked_add(y).ok_or(ParseLifeError::TooLarge)?));
+                        }
+                        _         => return Err(invalid),
+                    }
+                }
+                y += 1;
+            }
+        }
+
+        if cells.is_empty() { return Ok((Pattern::new(0, 0), rule)); }
+        // Coordinates far enough apart don't even fit their difference
+        let extent = |along: fn(&(i64, i64)) -> i64| {
+            let (min, max) = (cells.iter().map(along).min()?, cells.iter().map(along).max()?);
+            let side = usize::try_from(max.checked_sub(min)?.checked_add(1)?).ok().filter(|&side| side <= MAX_SIDE)?;
+            Some((min, side))
+        };
+        let (Some((min_x, width)), Some((min_y, height))) = (extent(|&(x, _)| x), extent(|&(_, y)| y)) else {
+            return Err(ParseLifeError::TooLarge);
+        };
+        if width * height > MAX_CELLS { return Err(ParseLifeError::TooLarge); }
+        let mut pattern = Pattern::new(height, width);
+        for (x, y) in cells { pattern.set(Coord::new((y - min_y) as usize, (x - min_x) as usize), Cell::Alive); }
+        Ok((pattern, rule))
+    }
+
+    /// Writes the pattern in the Life 1.05 format, centered on the origin as the format has it. The rule goes as
+    /// `#N` for Conway's and in survival/birth form for other life-like rules, and any rule written like
+    /// `Rule` does otherwise, which other programs may not read.
+    pub fn to_life105(&self, rule: Option<Rule>) -> String {
+        let mut out = String::from("#Life 1.05\n");
+        match rule {
+            Some(rule) if rule == Rule::CONWAY => out.push_str("#N\n"),
+            Some(rule) if rule.states() == 2 && rule.is_totalistic() && !rule.is_stochastic()
+                && rule.neighborhood() == Neighborhood::Moore => {
+                let digits = |holds: fn(&Rule, u16) -> bool| (0..=8).filter(|&n| holds(&rule, n)).map(|n| n.to_string()).collect::<String>();
+                out.push_str(&format!("#R {}/{}\n", digits(Rule::survives_on), digits(Rule::births_on)));
+            }
+            Some(rule)                         => out.push_str(&format!("#R {rule}\n")),
+            None                               => {}
+        }
+
+        let (height, width) = (self.get_height(), self.get_width());
+        let (left, top) = (-(width as i64 / 2), -(height as i64 / 2));
+        for start in (0..width).step_by(LINE_LENGTH) {
+            out.push_str(&format!("#P {} {top}\n", left + start as i64));
+            for row in 0..height {
+                let line = (start..width.min(start + LINE_LENGTH))
+                    .map(|col| if self.is_alive(Coord::new(row, col)) { '*' } else { '.' })
+                    .collect::<String>();
+                // Empty rows still need a dot, since blank lines don't count as rows
+                let line = line.trim_end_matches('.');
+                out.push_str(if line.is_empty() { "." } else { line });
+                out.push('\n');
+            }
+        }
+        out
+    }
+
+    /// Writes the pattern in the Life 1.06 format, each live cell as its column and row
+    pub fn to_life106(&self) -> String {
+        let mut out = String::from("#Life 1.06\n");
+        for (c, _) in self.iter_cells().filter(|(_, cell)| cell.is_alive()) {
+            out.push_str(&format!("{} {}\n", c.col, c.row));
+        }
+        out
+    }
+}
+
+impl fmt::Display for ParseLifeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseLifeError::MissingHeader         => write!(f, "the file doesn't start with #Life 1.05 or #Life 1.06"),
+            ParseLifeError::UnsupportedRule(rule) => write!(f, "the rule {rule} isn't supported"),
+            ParseLifeError::InvalidLine(line)     => write!(f, "couldn't make sense of line {line}"),
+            ParseLifeError::TooLarge              => write!(f, "the pattern is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParseLifeError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_both_versions() {
+        let (glider, _) = Pattern::from_life("#Life 1.06\n1 0\n2 1\n0 2\n1 2\n2 2\n").unwrap();
+        let (same, rule) = Pattern::from_life("#Life 1.05\n#N\n#P -1 -1\n.*.\n..*\n***\n").unwrap();
+        assert_eq!(glider, same);
+        assert_eq!(rule, Some(Rule::CONWAY));
+        assert_eq!((glider.get_height(), glider.get_width()), (3, 3));
+    }
+
+    #[test]
+    fn rejects_whatever_is_too_large() {
+        let far = |a: i64, b: i64| Pattern::from_life(&format!("#Life 1.06\n{a} 0\n{b} 0\n"));
+        assert_eq!(far(i64::MIN, i64::MAX), Err(ParseLifeError::TooLarge));
+        assert_eq!(far(0, MAX_SIDE as i64), Err(ParseLifeError::TooLarge));
+        assert!(far(0, MAX_SIDE as i64 - 1).is_ok());
+        assert_eq!(Pattern::from_life(&format!("#Life 1.06\n0 0\n{0} {0}\n", 20000)), Err(ParseLifeError::TooLarge));
+        assert_eq!(Pattern::from_life(&format!("#Life 1.05\n#P {} 0\n**\n", i64::MAX)), Err(ParseLifeError::TooLarge));
+    }
+}
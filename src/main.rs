@@ -1,106 +1,2838 @@
-use gameoflife::{Cell, Coord, Universe};
-use macroquad::{prelude::*, text};
+use gameoflife::{circle_cells, line_cells, rectangle_cells, Anchor, BlitMode, BlockRule, Boundary, Cell, Coloring, ContinuousRule, ContinuousUniverse, Coord, Cycle, EditHistory, GifRecorder, HashLife, Heading, LibraryPattern, LoopDetector, Neighborhood, Palette, Pattern, PlaneCoord, Revision, Rule, RuleExplorer, RulePreset, RuleTable, Session,
+                 Symmetry, Turmite, Universe, Universe3D, Zone, ZoneBorder};
+use macroquad::{color::hsl_to_rgb, miniquad::{BlendFactor, BlendState, BlendValue, Equation}, prelude::*, ui::{hash, root_ui, widgets}};
 
 
 
 #[macroquad::main(window_conf)]
 async fn main() {
     // Variables
-    let background_color         = Color::from_rgba(24, 25, 38, 255);
     let grid_thickness           = 2.5;
-    let grid_color               = Color::from_rgba(138, 173, 244, 255);
     let grid_spacing             = 30;
-    let alive_color              = Color::from_rgba(145, 215, 227, 255); // Lavander
-    let dead_color               = Color::from_rgba(0, 0, 0, 0);         // Transparent
-    let text_color               = Color::from_rgba(198, 160, 246, 200);
-    let mut time_between_ticks   = 0.3;                                  // In seconds;
-    let time_between_ticks_delta = 0.01;                                 // In seconds
+    let grid_fade                = (4.0, 12.0);                          // In pixels between lines on screen, hidden to fully drawn
+    let mut speed                = 3.0;                                  // In generations a second
+    let speed_range              = (1.0, 240.0);
+    let speed_step               = 1.25;                                 // Factor, per press of + or -
+    let max_ticks_per_frame      = 16;                                   // Beyond which the simulation falls behind
+    let past_depth               = 100;                                  // In generations
+    let loop_window              = 1000;                                 // In generations
+    let soup_density             = 0.3;
+    let noise_scale              = 6.0;                                  // In cells
+    let noise_threshold          = 0.2;
+    let layer_count              = 16;                                   // For the 3D view
+    let explorer_candidates      = 5;
+    let continuous_spacing       = 6;                                    // Lenia's patterns span dozens of cells
+    let continuous_density       = 0.8;
+    let zoom_step                = 1.1;                                  // Per notch of the mouse wheel
+    let frame_cell_size          = 8;                                    // In pixels, for frame.png and recording.gif
+    let trail_length             = 8;                                    // In generations
+    let minimap_size             = 180.0;                                // In pixels, its longest side
+    let minimap_refresh          = 10;                                   // In frames
+    let animation_time           = 0.15;                                 // In seconds, or a generation if shorter
+    let heat_decay               = 0.9;                                  // Of each cell's activity, per generation
+    let screenshot_dir           = "screenshots";                        // Made if it isn't there
+    let keys_path                = "keys.toml";                          // The default bindings if it isn't there
+    let stick_speed              = 600.0;                                // In pixels a second, with the left stick all the way over
+    let trigger_zoom             = 4.0;                                  // Factor a second, with a trigger all the way down
 
-    let uni_width = || screen_width() as usize / grid_spacing;
-    let uni_height = || screen_height() as usize / grid_spacing;
-    let bare_universe = || Universe::new(uni_width(), uni_height());
+    let age_span                 = 100;                                  // In generations, from the theme's young color to its old one
+
+    // `--world ACROSSxDOWN` gives the world a size of its own in cells, instead of fitting it to the window. So can
+    // the settings window, later on.
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    #[cfg_attr(not(feature = "settings"), allow(unused_mut))]
+    let mut world_size = args.iter().position(|arg| arg == "--world").and_then(|i| {
+        let size = args.drain(i..(i + 2).min(args.len())).nth(1);
+        let parsed = size.as_deref().and_then(parse_world_size);
+        if parsed.is_none() { eprintln!("Couldn't read the world's size, expected --world ACROSSxDOWN (such as --world 500x300)"); }
+        parsed
+    });
+    // Rows run along the screen's x axis
+    let dimensions_of = |world_size: Option<(usize, usize)>| {
+        let (across, down) = world_size.unwrap_or((screen_width() as usize / grid_spacing, screen_height() as usize / grid_spacing));
+        Coord::new(across, down)
+    };
+    let bare_universe = || {
+        let Coord { row: across, col: down } = dimensions_of(world_size);
+        let mut universe = Universe::new(across, down);
+        universe.set_past_depth(past_depth);
+        universe.record_population(true);
+        universe.record_deaths(true);
+        universe
+    };
 
     // Simulation
     let mut universe = bare_universe();
+    // A Golly .rule file, or a pattern to start from, can be given on the command line
+    if let Some(path) = args.first() {
+        if let Err(e) = load(path, &mut universe) { eprintln!("Couldn't load {path}: {e}"); }
+    }
+    let keys = match std::fs::read_to_string(keys_path) {
+        Ok(toml) => KeyMap::from_toml(&toml).unwrap_or_else(|e| {
+            eprintln!("Couldn't read {keys_path}: {e}");
+            KeyMap::new()
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => KeyMap::new(),
+        Err(e) => {
+            eprintln!("Couldn't read {keys_path}: {e}");
+            KeyMap::new()
+        }
+    };
+    let mut history = EditHistory::new();
     let mut paused = true;
-    let mut frames_since_last_tick = 0;
+    let mut tick_debt = 0.0;                                       // Generations due but not run yet
+    let mut loop_detector = LoopDetector::new(loop_window);
+    let mut cycle = None;
+    let mut apgcode = None;                                        // Of what the universe settled into
+    let mut classifying: Option<std::sync::mpsc::Receiver<Option<String>>> = None; // Working out the apgcode on its own thread
+    let mut show_population = false;
+    let mut show_grid = true;
+    let mut show_ages = false;
+    let mut show_trails = false;
+    let mut animate = false;                                       // Births and deaths
+    let mut show_heat = false;                                     // Instead of the cells' own colors
+    let mut since_tick = 0.0;                                      // In seconds, counting this frame
+    let mut show_hud = true;
+    #[cfg(feature = "settings")]
+    let mut show_settings = false;
+    let mut tool = Tool::Pen;
+    let mut nib = Nib::Dot;                                        // What the pen and eraser cover
+    let mut shape_from: Option<Coord> = None;                      // Where the line, rectangle or circle being drawn starts
+    let mut gens_per_second = 0.0;                                 // Measured, averaged over the last few seconds
+    let mut last_generation = universe.get_generation();
+    let mut theme = 0;                                             // Of Theme::ALL
+    let mut brush = Cell::Alive;
+    let mut turmites_only = false;
+    let mut soup_seed = miniquad::date::now().to_bits();
+    let mut layered: Option<(Universe3D, usize)> = None;           // And the layer on view
+    let mut continuous: Option<ContinuousUniverse> = None;
+    let mut explorer: Option<(RuleExplorer, usize)> = None;        // And the candidate Tab goes to next
+    let mut view = View::HOME;
+    let mut comparison: Option<Universe> = None;                   // Ticking alongside, on the right half of the screen
+    let mut compare_view = View::HOME;
+    let mut compare_preset = 0;                                    // Of RulePreset::ALL, the one it runs under
+    let mut compare_texture = CellTexture::new();
+    let mut screen_size = vec2(screen_width(), screen_height());
+    let mut fullscreen = false;
+    let mut cell_texture = CellTexture::new();
+    let mut minimap = Minimap::new(minimap_size);
+    let mut last_mouse: Vec2 = mouse_position().into();
+    let mut fingers = Fingers::default();
+    let mut tapped_on: Option<(Coord, Cell)> = None;               // And what it was before the pen got to it
+    let mut touch_step = false;                                    // From the touch bar, for the next frame
+    let mut gamepad = Gamepad::open();
+    let mut gamepad_wait = 0.0;                                    // In seconds, until looking for one again
+    let mut selection: Option<(Coord, Coord)> = None;              // Where the drag started and where it's at
+    let mut pasting: Option<Pattern> = None;                       // Following the mouse until a click drops it
+    let mut dropping = false;                                      // Since the click that dropped it, until let go
+    let mut paste_name: Option<String> = None;                     // Of the library pattern or file being pasted
+    let mut dropped = Vec::new();                                  // Files dropped on the window so far
+    let mut library_next = 0;
+    let mut recording: Option<GifRecorder> = None;
+    #[cfg(feature = "video")]
+    let mut video: Option<gameoflife::VideoExporter> = None;
+    #[cfg(feature = "net")]
+    let mut fetching: Option<(String, std::sync::mpsc::Receiver<Result<Pattern, gameoflife::FetchError>>)> = None; // Downloading on its own thread
 
     // Main loop
     loop {
+        let Theme { background: background_color, alive: alive_color, dead: dead_color, dying: dying_color,
+                    conductor: conductor_color, turmite: turmite_color, young, old, grid: grid_color, accent: text_color, .. }
+            = Theme::ALL[theme];
+        let palette = [alive_color, dying_color, conductor_color, dead_color];
+        let age_gradient = AgeGradient { young, old, span: age_span };
+        // For exported images, over the background rather than transparent
+        let export_palette = Palette { dead: background_color.into(), alive: alive_color.into(), dying: dying_color.into(),
+                                       conductor: conductor_color.into() };
+        // Live cells' colors under Immigration (the first two) and QuadLife
+        let paints = [alive_color, turmite_color, young, old];
+
         clear_background(background_color);
-        universe.set_dimensions(Coord::new(uni_width(), uni_height()));
+        // The universe grows and shrinks around its middle with the window, and the view follows it, so what was in
+        // the middle of the screen stays there
+        let dimensions = dimensions_of(world_size);
+        if vec2(screen_width(), screen_height()) != screen_size {
+            let (dy, dx) = Anchor::Center.offset(Coord::new(universe.get_height(), universe.get_width()), dimensions);
+            let center = view.world_at(screen_size / 2.0) + vec2(dy as f32, dx as f32) * grid_spacing as f32;
+            screen_size = vec2(screen_width(), screen_height());
+            view.center_on(center);
+            universe.set_dimensions(dimensions, Anchor::Center);
+            if let Some(copy) = &mut comparison { copy.set_dimensions(dimensions, Anchor::Center); }
+        }
+
+        // However long frames take, generations go by at the same pace
+        tick_debt = if paused { 0.0 } else { tick_debt + get_frame_time() * speed };
+        let mut ticks = (tick_debt as u32).min(max_ticks_per_frame);
+        tick_debt = if ticks == max_ticks_per_frame { 0.0 } else { tick_debt.fract() };
+        // Stepping through generations while paused, ten at a time with shift
+        let step = keys.pressed(Action::Step) || std::mem::take(&mut touch_step);
+        if paused && step {
+            ticks = if is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift) { 10 } else { 1 };
+        }
+        since_tick = if ticks > 0 { get_frame_time() } else { since_tick + get_frame_time() };
 
-        let time_since_last_tick = frames_since_last_tick as f32 / get_fps() as f32;
+        if keys.pressed(Action::Pause) { paused = !paused; }
+        if keys.pressed(Action::Faster) {
+            speed = (speed * speed_step).min(speed_range.1);
+        }
+        if keys.pressed(Action::Slower) {
+            speed = (speed / speed_step).max(speed_range.0);
+        }
+        if keys.pressed(Action::Theme) { theme = (theme + 1) % Theme::ALL.len(); }
+        if keys.pressed(Action::Fullscreen) {
+            fullscreen = !fullscreen;
+            set_fullscreen(fullscreen);
+        }
+        if keys.pressed(Action::Layers) {
+            layered = match layered {
+                Some(_) => None,
+                None    => Some((Universe3D::new(layer_count, universe.get_height(), universe.get_width()), layer_count / 2)),
+            };
+        }
+
+        if keys.pressed(Action::Continuous) {
+            continuous = match continuous {
+                Some(_) => None,
+                None    => Some(ContinuousUniverse::new(screen_width() as usize / continuous_spacing,
+                                                        screen_height() as usize / continuous_spacing)),
+            };
+        }
 
-        if !paused && (time_between_ticks < time_since_last_tick) {
-            frames_since_last_tick = 0;
+        if let Some(field) = &mut continuous {
+            for _ in 0..ticks { field.tick(); }
+            if keys.pressed(Action::Soup) {
+                soup_seed = soup_seed.wrapping_add(1);
+                let (height, width) = (field.get_height(), field.get_width());
+                field.randomize_patch(Coord::new(height / 3, width / 3), Coord::new(height / 3, width / 3),
+                                      continuous_density, soup_seed);
+            }
+            if keys.pressed(Action::Rule) {
+                field.set_rule(match field.get_rule() {
+                    ContinuousRule::Lenia { .. } => ContinuousRule::smooth_life(4.0),
+                    _                            => ContinuousRule::orbium(),
+                });
+            }
+            if is_mouse_button_down(MouseButton::Left) {
+                let (globl_x, globl_y) = mouse_position();
+                let (row, col) = (globl_x as isize / continuous_spacing as isize, globl_y as isize / continuous_spacing as isize);
+                for (dy, dx) in (-2..=2).flat_map(|dy| (-2..=2).map(move |dx| (dy, dx))).filter(|(dy, dx)| dy * dy + dx * dx <= 4) {
+                    let (row, col) = (row + dy, col + dx);
+                    if (0..field.get_height() as isize).contains(&row) && (0..field.get_width() as isize).contains(&col) {
+                        field.set(Coord::new(row as usize, col as usize), 1.0);
+                    }
+                }
+            }
+
+            draw_continuous(field, alive_color, continuous_spacing);
+            draw_continuous_controls(text_color, &keys, field, paused, grid_spacing);
+            next_frame().await;
+            continue;
+        }
+
+        if let Some((space, layer)) = &mut layered {
+            for _ in 0..ticks { space.tick(); }
+            if keys.pressed(Action::LayerUp)   { *layer = (*layer + 1).min(space.get_depth() - 1); }
+            if keys.pressed(Action::LayerDown) { *layer = layer.saturating_sub(1); }
+            if keys.pressed(Action::Soup)      {
+                soup_seed = soup_seed.wrapping_add(1);
+                space.randomize(soup_density, soup_seed);
+            }
+            if is_mouse_button_down(MouseButton::Left) {
+                let (globl_x, globl_y) = mouse_position();
+                space.set_pixel(*layer, Coord::new(
+                    (globl_x as usize / grid_spacing).min(space.get_height() - 1),
+                    (globl_y as usize / grid_spacing).min(space.get_width() - 1)),
+                                Cell::Alive);
+            }
+
+            cell_texture.draw(&space.slice(*layer), grid_spacing, palette, paints, Effects::default(), None, View::HOME.visible());
+            draw_grid(GridLines::new(grid_thickness, grid_color, grid_spacing, 1.0, grid_fade), grid_spacing, View::HOME.visible());
+            draw_layer_controls(text_color, &keys, *layer, space, paused, grid_spacing);
+            next_frame().await;
+            continue;
+        }
+
+        for _ in 0..ticks {
+            // In lockstep with the one it's compared with
+            if let Some(copy) = &mut comparison {
+                if turmites_only { copy.tick_turmites(); } else { copy.tick(); }
+            }
+            if turmites_only {
+                universe.tick_turmites();
+                continue;
+            }
             universe.tick();
-        } else {
-            frames_since_last_tick += 1;
+            if cycle.is_none() {
+                cycle = loop_detector.observe(&universe);
+                let rule = universe.get_rule();
+                let life_like = universe.runs_by_rule() && universe.zones().is_empty() && rule.states() == 2
+                    && rule.is_totalistic() && !rule.is_stochastic() && rule.neighborhood() == Neighborhood::Moore;
+                // Which takes running it for up to 1024 more generations, so it's left for another thread
+                if cycle.is_some() && life_like {
+                    let (pattern, (send, receive)) = (Pattern::from(&universe), std::sync::mpsc::channel());
+                    std::thread::spawn(move || { let _ = send.send(pattern.to_apgcode(rule)); });
+                    classifying = Some(receive);
+                }
+            }
+        }
+        if let Some(receive) = &classifying {
+            match receive.try_recv() {
+                Ok(code)                                         => (apgcode, classifying) = (code, None),
+                Err(std::sync::mpsc::TryRecvError::Empty)        => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => classifying = None,
+            }
+        }
+
+        let ctrl = is_key_down(KeyCode::LeftControl) || is_key_down(KeyCode::RightControl);
+        let shift = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+
+        // Zooming keeps what's under the mouse in place, and with control the wheel sizes the brush instead
+        let mouse: Vec2 = mouse_position().into();
+        let (_, wheel) = mouse_wheel();
+        // While comparing, the right half of the screen has a view of its own, for the mouse to move while over it
+        let split = vec2(screen_width() / 2.0, 0.0);
+        let on_copy = comparison.is_some() && mouse.x >= split.x;
+        let (hovered_view, at) = if on_copy { (&mut compare_view, mouse - split) } else { (&mut view, mouse) };
+        match wheel.partial_cmp(&0.0) {
+            Some(std::cmp::Ordering::Greater) if ctrl => nib = nib.bigger(),
+            Some(std::cmp::Ordering::Less) if ctrl    => nib = nib.smaller(),
+            Some(std::cmp::Ordering::Greater)         => hovered_view.zoom_at(at, zoom_step),
+            Some(std::cmp::Ordering::Less)            => hovered_view.zoom_at(at, 1.0 / zoom_step),
+            _                                         => {}
+        }
+        if is_mouse_button_down(MouseButton::Middle) && on_copy { compare_view.corner -= (mouse - last_mouse) / compare_view.zoom; }
+        if keys.pressed(Action::Home) { (view, compare_view) = (View::HOME, View::HOME); }
+        let (touched, tapped) = fingers.update(&mut view);
+        // Controllers can be plugged in at any time, so one is looked for every couple of seconds until there's one
+        if gamepad.is_none() {
+            gamepad_wait -= get_frame_time();
+            if gamepad_wait <= 0.0 { (gamepad, gamepad_wait) = (Gamepad::open(), 2.0); }
+        }
+        if gamepad.as_mut().is_some_and(|pad| !pad.poll()) { gamepad = None; }
+        // The left stick moves the view under a reticle in the middle of the screen (or its left half, while
+        // comparing), and the triggers zoom it
+        let reticle = vec2(split.x / if comparison.is_some() { 1.0 } else { 0.5 }, screen_height()) / 2.0;
+        let pad_toggled = if let Some(pad) = &gamepad {
+            view.corner += vec2(pad.axis(Axis::LeftX), pad.axis(Axis::LeftY)) * stick_speed * get_frame_time() / view.zoom;
+            let zoom = pad.axis(Axis::RightTrigger) - pad.axis(Axis::LeftTrigger);
+            if zoom != 0.0 { view.zoom_at(reticle, f32::powf(trigger_zoom, zoom * get_frame_time())); }
+            if pad.pressed(Button::Start) { paused = !paused; }
+            pad.pressed(Button::A)
+        } else { false };
+        // Only there while part of the universe is off screen, and out of the rule explorer's way
+        let extent = Rect::new(0.0, 0.0, (universe.get_height() * grid_spacing) as f32, (universe.get_width() * grid_spacing) as f32);
+        let visible = view.visible();
+        let minimap_shown = explorer.is_none() && comparison.is_none() && !(visible.left() <= extent.left() && visible.top() <= extent.top()
+                                                    && visible.right() >= extent.right() && visible.bottom() >= extent.bottom());
+        let on_minimap = minimap_shown && minimap.area(&universe).contains(mouse);
+        // Clicks on the toolbar and the settings window are their own
+        let on_window = root_ui().is_mouse_over(mouse);
+        // The copy being compared with is only watched, or swapped in to be edited
+        let on_ui = on_minimap || on_window || on_copy;
+        // Fingers also press the left button, one after another, so none of them paints while two move the view
+        let left_down = is_mouse_button_down(MouseButton::Left) && !on_ui && !fingers.gesturing;
+        if on_minimap && !on_window && is_mouse_button_down(MouseButton::Left) { view.center_on(minimap.world_at(&universe, mouse, grid_spacing)); }
+        // The middle button drags the view around whatever the tool
+        if (is_mouse_button_down(MouseButton::Middle) && !on_copy) || (tool == Tool::Pan && left_down) { view.corner -= (mouse - last_mouse) / view.zoom; }
+        last_mouse = mouse;
+
+        let pressed = |actions: &[Action]| actions.iter().any(|&action| keys.pressed(action));
+        let (turn, flip) = (keys.pressed(Action::Turn) && !shift, keys.pressed(Action::Flip) && shift);
+        let edited = keys.down(Action::Reset) || ((left_down || tapped) && tool.edits()) || pad_toggled || (keys.pressed(Action::StepBack) && !shift)
+            || (shift && pressed(&[Action::MoveLeft, Action::MoveRight, Action::MoveUp, Action::MoveDown]))
+            || (!ctrl && pressed(&[Action::Soup, Action::Noise, Action::Invert, Action::NextCandidate]))
+            || (ctrl && pressed(&[Action::Undo, Action::Redo, Action::LoadSession, Action::Cut, Action::Fill]))
+            || (selection.is_some() && (turn || flip || keys.pressed(Action::Clear)));
+        if edited {
+            loop_detector.clear();
+            (cycle, apgcode, classifying) = (None, None, None);
         }
 
-        if is_key_pressed(KeyCode::Space) { paused = !paused; }
-        if is_key_down(KeyCode::R)        { universe = bare_universe(); }
-        if is_key_down(KeyCode::D)        { time_between_ticks += time_between_ticks_delta; }
-        if is_key_down(KeyCode::U)        {
-            time_between_ticks = (time_between_ticks - time_between_ticks_delta).max(0.0);
+        if keys.pressed(Action::Population) { show_population = !show_population; }
+        if keys.pressed(Action::Grid)       { show_grid = !show_grid; }
+        if keys.pressed(Action::Ages)       { show_ages = !show_ages; }
+        if keys.pressed(Action::Trails) && !ctrl { show_trails = !show_trails; }
+        if keys.pressed(Action::Animations) { animate = !animate; }
+        // Activity is only kept track of while it's shown, and starts over every time it is
+        if keys.pressed(Action::Heatmap) {
+            show_heat = !show_heat;
+            for universe in std::iter::once(&mut universe).chain(&mut comparison) { universe.record_activity(show_heat.then_some(heat_decay)); }
+        }
+        // The same universe under another rule preset, starting over from this one's cells whenever it's changed
+        let compared_under = |preset: usize| {
+            let mut copy = universe.clone();
+            copy.set_rule(RulePreset::ALL[preset].rule());
+            copy
+        };
+        if keys.pressed(Action::Compare) {
+            let next = RulePreset::of(universe.get_rule()).and_then(|p| RulePreset::ALL.iter().position(|&q| q == p)).map_or(0, |i| i + 1);
+            compare_preset = next % RulePreset::ALL.len();
+            comparison = match comparison {
+                Some(_) => None,
+                None    => Some(compared_under(compare_preset)),
+            };
+            compare_view = view;
+        }
+        if keys.pressed(Action::CompareRule) && comparison.is_some() {
+            compare_preset = (compare_preset + 1) % RulePreset::ALL.len();
+            comparison = Some(compared_under(compare_preset));
+        }
+        // Swapping sides makes the copy the one to edit, and what was edited before doesn't apply to it
+        if keys.pressed(Action::CompareSwap) {
+            if let Some(copy) = &mut comparison {
+                std::mem::swap(&mut universe, copy);
+                std::mem::swap(&mut view, &mut compare_view);
+                history = EditHistory::new();
+                loop_detector.clear();
+                (cycle, apgcode, classifying) = (None, None, None);
+            }
+        }
+        if keys.pressed(Action::Hud)        { show_hud = !show_hud; }
+        #[cfg(feature = "settings")]
+        if keys.pressed(Action::Settings)   { show_settings = !show_settings; }
+        if keys.down(Action::Reset)         {
+            history.apply(&mut universe, |u| {
+                u.clear();
+                u.clear_turmites();
+                u.reset_generation();
+            });
+        }
+        if keys.pressed(Action::Invert) { history.apply(&mut universe, Universe::invert); }
+        if keys.pressed(Action::SaveSession) && ctrl {
+            let mut session = Session::new(universe.clone(), 1.0 / speed, paused);
+            session.set_setting("population", show_population);
+            session.set_setting("brush", brush.state(universe.conductor_state()));
+            session.set_setting("ants_only", turmites_only);
+            session.set_setting("theme", Theme::ALL[theme].name);
+            session.set_setting("view", format!("{}, {}, {}", view.corner.x, view.corner.y, view.zoom));
+            match std::fs::write("session.txt", session.to_string()) {
+                Ok(()) => println!("Saved session.txt"),
+                Err(e) => eprintln!("Couldn't write session.txt: {e}"),
+            }
+        }
+        if keys.pressed(Action::LoadSession) && ctrl {
+            let session = std::fs::read_to_string("session.txt").map_err(|e| e.to_string())
+                .and_then(|s| s.parse::<Session>().map_err(|e| e.to_string()));
+            match session {
+                Ok(session) => {
+                    let flag = |key| session.get_setting(key).and_then(|v| v.parse().ok());
+                    show_population = flag("population").unwrap_or(show_population);
+                    turmites_only = flag("ants_only").unwrap_or(turmites_only);
+                    theme = session.get_setting("theme").and_then(|name| Theme::ALL.iter().position(|t| t.name == name)).unwrap_or(theme);
+                    brush = session.get_setting("brush").and_then(|v| v.parse().ok())
+                        .map_or(brush, |state| Cell::from_state(state, session.universe.conductor_state()));
+                    let numbers = |s: &str| s.split(',').map(|n| n.trim().parse::<f32>().ok()).collect::<Option<Vec<_>>>();
+                    if let Some(&[x, y, zoom]) = session.get_setting("view").and_then(numbers).as_deref() {
+                        view = View { zoom: zoom.clamp(View::MIN_ZOOM, View::MAX_ZOOM), corner: vec2(x, y) };
+                    }
+                    speed = (1.0 / session.speed).clamp(speed_range.0, speed_range.1);
+                    paused = session.paused;
+                    universe = session.universe;
+                    universe.set_past_depth(past_depth);
+                    universe.record_population(true);
+                    universe.record_deaths(true);
+                    universe.record_activity(show_heat.then_some(heat_decay));
+                    // Edits made before don't apply to the universe that was loaded
+                    history = EditHistory::new();
+                }
+                Err(e)      => eprintln!("Couldn't load session.txt: {e}"),
+            }
+        }
+        if keys.pressed(Action::Soup) && !ctrl {
+            soup_seed = soup_seed.wrapping_add(1);
+            history.apply(&mut universe, |u| u.randomize(soup_density, soup_seed));
+        }
+        if keys.pressed(Action::Noise) {
+            soup_seed = soup_seed.wrapping_add(1);
+            history.apply(&mut universe, |u| u.randomize_noise(noise_scale, noise_threshold, soup_seed));
+        }
+        if keys.pressed(Action::Symmetry) {
+            universe.set_symmetry(match universe.get_symmetry() {
+                Symmetry::None       => Symmetry::Horizontal,
+                Symmetry::Horizontal => Symmetry::Vertical,
+                Symmetry::Vertical   => Symmetry::Rotational,
+                Symmetry::Rotational => Symmetry::FourFold,
+                Symmetry::FourFold   => Symmetry::EightFold,
+                Symmetry::EightFold  => Symmetry::None,
+            });
+        }
+        if keys.pressed(Action::Hex) {
+            universe.set_rule(match universe.get_neighborhood() {
+                Neighborhood::Hex => Rule::CONWAY,
+                _                 => "B2/S34H".parse().expect("hex life rule should parse"),
+            });
+        }
+        if keys.pressed(Action::Wrap) {
+            // Rows run along the screen's x axis, so wrapping across the screen means wrapping vertically
+            let (vertical, horizontal) = match universe.get_boundaries() {
+                (Boundary::Toroidal, Boundary::Toroidal) => (Boundary::Toroidal, Boundary::Dead),
+                (Boundary::Toroidal, Boundary::Dead)     => (Boundary::Dead, Boundary::Dead),
+                _                                        => (Boundary::Toroidal, Boundary::Toroidal),
+            };
+            universe.set_boundaries(vertical, horizontal);
+        }
+        if keys.pressed(Action::Rule) && !ctrl {
+            let next = match RulePreset::of(universe.get_rule()) {
+                Some(preset) => RulePreset::ALL.into_iter().cycle().skip_while(|&p| p != preset).nth(1),
+                None         => Some(RulePreset::Conway),
+            };
+            universe.set_rule(next.unwrap_or(RulePreset::Conway).rule());
+        }
+        if keys.pressed(Action::Brush) {
+            brush = match brush {
+                Cell::Alive     => Cell::Dying(1),
+                Cell::Dying(_)  => Cell::Conductor,
+                Cell::Conductor => Cell::Dead,
+                Cell::Dead      => Cell::Alive,
+            };
+        }
+        if keys.pressed(Action::AddAnt) {
+            let Vec2 { x: globl_x, y: globl_y } = view.world_at(mouse);
+            universe.add_turmite(Turmite::langtons_ant(Coord::new(
+                (globl_x as usize / grid_spacing).min(universe.get_height().saturating_sub(1)),
+                (globl_y as usize / grid_spacing).min(universe.get_width().saturating_sub(1)))));
+        }
+        if keys.pressed(Action::AntsOnly) { turmites_only = !turmites_only; }
+        if keys.pressed(Action::Colors) && !ctrl {
+            universe.set_coloring(match universe.get_coloring() {
+                None                        => Some(Coloring::Immigration),
+                Some(Coloring::Immigration) => Some(Coloring::QuadLife),
+                Some(Coloring::QuadLife)    => None,
+            });
+        }
+        if keys.pressed(Action::Paint) && !ctrl { universe.set_paint(universe.get_paint() + 1); }
+        if pressed(&[Action::Copy, Action::Cut]) && ctrl {
+            // The selection, or everything there is without one
+            let pattern = match selection {
+                Some((from, to)) => selected_pattern(&universe, from, to),
+                None             => Pattern::from(&universe).trim(),
+            };
+            miniquad::window::clipboard_set(&transpose(&pattern).to_rle(Some(universe.get_rule())));
+        }
+        // Cutting, deleting or filling what's selected
+        if let Some((from, to)) = selection {
+            let (top_left, bottom_right) = corners(from, to);
+            let cleared = (ctrl && keys.pressed(Action::Cut)) || keys.pressed(Action::Clear);
+            if cleared { history.apply(&mut universe, |u| u.fill_region(top_left, bottom_right, Cell::Dead)); }
+            if ctrl && keys.pressed(Action::Fill) { history.apply(&mut universe, |u| u.fill_region(top_left, bottom_right, brush)); }
+        }
+        if keys.pressed(Action::Paste) && ctrl && !shift {
+            // An apgcode or RLE pattern from the clipboard, to be dropped where the mouse is
+            let text = miniquad::window::clipboard_get().unwrap_or_default();
+            let pattern = Pattern::from_apgcode(&text).ok().or_else(|| Pattern::from_rle(&text).ok().map(|(pattern, _)| pattern));
+            (pasting, paste_name) = (pattern.map(|pattern| transpose(&pattern)), None);
+        }
+        // With Shift, the clipboard has the name of a pattern to look up online, away from the frame loop
+        #[cfg(feature = "net")]
+        if keys.pressed(Action::Paste) && ctrl && shift && fetching.is_none() {
+            let text = miniquad::window::clipboard_get().unwrap_or_default().trim().to_owned();
+            if !text.is_empty() {
+                let (rule, name, (send, receive)) = (universe.get_rule(), text.clone(), std::sync::mpsc::channel());
+                std::thread::spawn(move || {
+                    let fetched = match name.contains('_') {
+                        true  => Pattern::fetch_catagolue(&name, rule),
+                        false => Pattern::fetch_lifewiki(&name).map(|(pattern, _)| pattern),
+                    };
+                    // Nobody's waiting anymore if the window was closed meanwhile
+                    let _ = send.send(fetched);
+                });
+                fetching = Some((text, receive));
+            }
+        }
+        #[cfg(feature = "net")]
+        if let Some((name, receive)) = fetching.take() {
+            match receive.try_recv() {
+                Ok(Ok(pattern))                                  => (pasting, paste_name) = (Some(transpose(&pattern)), Some(name)),
+                Ok(Err(e))                                       => eprintln!("Couldn't fetch {name}: {e}"),
+                Err(std::sync::mpsc::TryRecvError::Empty)        => fetching = Some((name, receive)),
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => eprintln!("Couldn't fetch {name}"),
+            }
+        }
+        if keys.pressed(Action::Library) {
+            // Going through the library one pattern at a time
+            let picked = LibraryPattern::ALL[library_next];
+            library_next = (library_next + 1) % LibraryPattern::ALL.len();
+            (pasting, paste_name) = (Some(transpose(&picked.pattern())), Some(picked.name().to_owned()));
+        }
+        // Miniquad keeps the files of the last drop around, so a drop is new when they change
+        let files = (0..miniquad::window::dropped_file_count()).map(miniquad::window::dropped_file_path).collect::<Vec<_>>();
+        if files != dropped {
+            dropped = files;
+            if let Some(path) = dropped.first() {
+                let name = path.as_ref().and_then(|p| p.file_name()).map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                // On the web there are only the bytes, as the path doesn't lead anywhere
+                let bytes = miniquad::window::dropped_file_bytes(0)
+                    .or_else(|| path.as_ref().and_then(|path| std::fs::read(path).ok()));
+                match bytes.ok_or_else(|| "couldn't read it".to_owned()).and_then(|bytes| read_pattern(&name, &bytes)) {
+                    Ok(pattern) => (pasting, paste_name) = (Some(transpose(&pattern)), Some(name)),
+                    Err(e)      => eprintln!("Couldn't load {name}: {e}"),
+                }
+            }
+        }
+        if keys.pressed(Action::Cancel) { (selection, pasting, shape_from) = (None, None, None); }
+        let hovered = hovered_cell(&universe, &view, grid_spacing);
+        if is_mouse_button_pressed(MouseButton::Right) { selection = hovered.map(|c| (c, c)); }
+        if is_mouse_button_down(MouseButton::Right) {
+            if let (Some((_, to)), Some(c)) = (&mut selection, hovered) { *to = c; }
+        }
+        if keys.pressed(Action::BlockRules) {
+            universe.set_block_rule(match universe.get_block_rule() {
+                None                      => Some(BlockRule::CRITTERS),
+                Some(BlockRule::CRITTERS) => Some(BlockRule::TRON),
+                Some(BlockRule::TRON)     => Some(BlockRule::BILLIARD_BALL),
+                Some(_)                   => None,
+            });
         }
-        if is_mouse_button_down(MouseButton::Left) {
-            let (globl_x, globl_y) = mouse_position();
-            universe.set_pixel(Coord::new(
-                (globl_x as usize / grid_spacing).min(uni_width() - 1),
-                (globl_y as usize / grid_spacing).min(uni_height() - 1)),
-                               Cell::Alive);
+        if keys.pressed(Action::Explorer) && !ctrl {
+            explorer = match explorer {
+                Some(_) => None,
+                None    => Some((RuleExplorer::new(soup_seed, explorer_candidates), 0)),
+            };
+        }
+        if let Some((explorer, next)) = &mut explorer {
+            explorer.try_one();
+            if keys.pressed(Action::NextCandidate) && !explorer.candidates().is_empty() {
+                *next %= explorer.candidates().len();
+                universe.set_rule(explorer.candidates()[*next].rule);
+                soup_seed = soup_seed.wrapping_add(1);
+                history.apply(&mut universe, |u| u.randomize(soup_density, soup_seed));
+                *next += 1;
+            }
+        }
+        if keys.pressed(Action::Zones) {
+            // The right half of the screen goes by Day & Night, to compare it with whatever runs on the left
+            match (universe.zones().is_empty(), universe.get_zone_border()) {
+                (true, _)                 => {
+                    let (height, width) = (universe.get_height(), universe.get_width());
+                    universe.add_zone(Zone::new(Coord::new(height / 2, 0), Coord::new(height - height / 2, width),
+                                                RulePreset::DayAndNight.rule()));
+                    universe.set_zone_border(ZoneBorder::Open);
+                }
+                (false, ZoneBorder::Open) => universe.set_zone_border(ZoneBorder::Closed),
+                (false, _)                => universe.clear_zones(),
+            }
+        }
+        #[cfg(feature = "video")]
+        if keys.pressed(Action::RecordVideo) && shift {
+            match video.take() {
+                Some(exporter) => {
+                    let frames = exporter.frame_count();
+                    match exporter.finish() {
+                        Ok(())  => println!("Saved {frames} frames to recording.mp4"),
+                        Err(e)  => eprintln!("Couldn't write recording.mp4: {e}"),
+                    }
+                }
+                // As big as the window is in real pixels, a frame for every generation shown
+                None           => match gameoflife::VideoExporter::ffmpeg("recording.mp4",
+                    (screen_width() * screen_dpi_scale()) as usize, (screen_height() * screen_dpi_scale()) as usize,
+                    speed.round() as u32, export_palette) {
+                    Ok(mut exporter) => {
+                        exporter.set_transposed(true);
+                        video = Some(exporter);
+                    }
+                    Err(e)           => eprintln!("Couldn't start ffmpeg: {e}"),
+                },
+            }
+        }
+        #[cfg(feature = "video")]
+        if let Some(exporter) = &mut video {
+            if let Err(e) = exporter.capture(&universe) {
+                eprintln!("Couldn't write recording.mp4: {e}");
+                video = None;
+            }
+        }
+        if keys.pressed(Action::RecordGif) && !(shift && cfg!(feature = "video")) {
+            match recording.take() {
+                Some(recorder) => match recorder.save("recording.gif") {
+                    Ok(())  => println!("Saved {} frames to recording.gif", recorder.frame_count()),
+                    Err(e)  => eprintln!("Couldn't write recording.gif: {e}"),
+                },
+                None           => {
+                    // Frames stay on for as long as generations do on screen
+                    let mut recorder = GifRecorder::new(frame_cell_size, export_palette, (100.0 / speed) as u16);
+                    recorder.set_transposed(true);
+                    recording = Some(recorder);
+                }
+            }
+        }
+        if let Some(recorder) = &mut recording { recorder.capture(&universe); }
+        if keys.pressed(Action::SaveSvg) && shift {
+            let svg = transpose(&Pattern::from(&universe).trim()).to_svg(grid_spacing as f32, &export_palette, Some(grid_color.into()));
+            match std::fs::write("pattern.svg", svg) {
+                Ok(()) => println!("Saved pattern.svg"),
+                Err(e) => eprintln!("Couldn't write pattern.svg: {e}"),
+            }
+        }
+        if keys.pressed(Action::SaveRle) && !shift {
+            let rle = transpose(&Pattern::from(&universe).trim()).to_rle(Some(universe.get_rule()));
+            match std::fs::write("pattern.rle", rle) {
+                Ok(()) => println!("Saved pattern.rle"),
+                Err(e) => eprintln!("Couldn't write pattern.rle: {e}"),
+            }
+        }
+        #[cfg(feature = "image")]
+        if keys.pressed(Action::SavePng) && !ctrl {
+            // Transposed back, so it comes out the way it looks on screen
+            let image = universe.to_image(frame_cell_size as u32, &export_palette);
+            let image = image::imageops::flip_horizontal(&image::imageops::rotate90(&image));
+            match image.save("frame.png") {
+                Ok(()) => println!("Saved frame.png"),
+                Err(e) => eprintln!("Couldn't write frame.png: {e}"),
+            }
+        }
+        if keys.pressed(Action::Elementary) {
+            match universe.get_elementary_rule() {
+                Some(_) => universe.set_rule(Rule::CONWAY),
+                None    => {
+                    universe.set_elementary_rule(Some(30));
+                    // A single live cell in the middle of the current generation
+                    history.apply(&mut universe, |u| {
+                        u.clear();
+                        let (last, middle) = (u.get_height() - 1, u.get_width() / 2);
+                        u.set_pixel(Coord::new(last, middle), Cell::Alive);
+                    });
+                }
+            }
+        }
+        if let Some(number) = universe.get_elementary_rule() {
+            if keys.pressed(Action::RuleDown) { universe.set_elementary_rule(Some(number.wrapping_sub(1))); }
+            if keys.pressed(Action::RuleUp)   { universe.set_elementary_rule(Some(number.wrapping_add(1))); }
+        }
+        if ctrl && keys.pressed(Action::Undo) { history.undo(&mut universe); }
+        if ctrl && keys.pressed(Action::Redo) { history.redo(&mut universe); }
+        // Reversible block rules can always go back, without remembering anything
+        if keys.pressed(Action::StepBack) && !shift {
+            if !universe.tick_backward() { universe.step_back(); }
+            if let Some(copy) = &mut comparison {
+                if !copy.tick_backward() { copy.step_back(); }
+            }
+        }
+        if shift {
+            // The frontend's coordinates are transposed: rows run along the screen's x axis
+            for (action, dy, dx) in [(Action::MoveLeft, -1, 0), (Action::MoveRight, 1, 0), (Action::MoveUp, 0, -1), (Action::MoveDown, 0, 1)] {
+                if keys.pressed(action) { history.apply(&mut universe, |u| u.shift(dy, dx, true)); }
+            }
+        }
+        let left_pressed = is_mouse_button_pressed(MouseButton::Left) && !on_ui && !fingers.gesturing;
+        if let Some(aimed) = cell_at(&universe, &view, reticle, grid_spacing).filter(|_| pad_toggled) {
+            history.toggle_pixel(&mut universe, aimed);
+        }
+        // Whatever was done before might have moved the view or resized the universe
+        let hovered = hovered_cell(&universe, &view, grid_spacing);
+        if touched && !on_ui { tapped_on = hovered.map(|c| (c, universe[c])); }
+        // Turning a selection lifts it into hand first
+        if (turn || flip) && pasting.is_none() {
+            if let Some((from, to)) = selection.take() {
+                let (top_left, bottom_right) = corners(from, to);
+                (pasting, paste_name) = (Some(selected_pattern(&universe, from, to)), None);
+                history.apply(&mut universe, |u| u.fill_region(top_left, bottom_right, Cell::Dead));
+            }
+        }
+        // Turning the pattern in hand as it looks on screen, where rows run across
+        if let Some(pattern) = &mut pasting {
+            if turn { *pattern = transpose(&transpose(pattern).rotate_cw()); }
+            if flip { *pattern = transpose(&transpose(pattern).flip_h()); }
+        }
+        // Patterns in hand drop on the click, and holding the button down after doesn't paint. Off the grid they stay
+        // in hand.
+        if let Some(at) = pasting.as_ref().and_then(|pattern| paste_at(pattern, hovered)).filter(|_| left_pressed) {
+            if let Some(pattern) = pasting.take() {
+                history.apply(&mut universe, |u| u.blit(&pattern, at, BlitMode::Or));
+                // Stamps stay in hand to be placed again
+                if tool == Tool::Stamp { pasting = Some(pattern); }
+                dropping = true;
+            }
+        }
+        match tool {
+            _ if pasting.is_some() || dropping => {}
+            Tool::Pen | Tool::Eraser => {
+                if left_pressed { history.begin_group(); }
+                if let Some(center) = hovered.filter(|_| left_down) {
+                    let (height, width) = (universe.get_height(), universe.get_width());
+                    for c in nib.cells(center).filter(|c| c.row < height && c.col < width) {
+                        history.set_pixel(&mut universe, c, if tool == Tool::Eraser { Cell::Dead } else { brush });
+                    }
+                }
+            }
+            Tool::Select => {
+                if left_pressed { selection = hovered.map(|c| (c, c)); }
+                if left_down {
+                    if let (Some((_, to)), Some(c)) = (&mut selection, hovered) { *to = c; }
+                }
+            }
+            Tool::Fill if left_pressed => if let Some(c) = hovered { history.apply(&mut universe, |u| u.flood_fill(c, brush)); },
+            Tool::Line | Tool::Rectangle | Tool::Circle if left_pressed => shape_from = hovered,
+            Tool::Stamp | Tool::Fill | Tool::Pan | Tool::Line | Tool::Rectangle | Tool::Circle => {}
+        }
+        // Shapes only land once the button's let go, previewed until then
+        // Let go of off the grid, they're let go of altogether
+        let shape = shape_from.zip(hovered).and_then(|(from, to)| tool.shape(from, to, shift));
+        if is_mouse_button_released(MouseButton::Left) && shape_from.take().is_some() {
+            let (height, width) = (universe.get_height(), universe.get_width());
+            history.begin_group();
+            for &c in shape.iter().flatten().filter(|c| c.row < height && c.col < width) {
+                history.set_pixel(&mut universe, c, brush);
+            }
+        }
+        if is_mouse_button_released(MouseButton::Left) {
+            dropping = false;
+            history.end_group();
+        }
+        // Without a right button to erase with, tapping a cell with the pen toggles it: what the pen painted as the
+        // finger came down is taken back if the cell was already alive
+        if tapped && tool == Tool::Pen && pasting.is_none() && !on_ui {
+            // What a finger that went on to drag came down on doesn't count
+            let came_down = tapped_on.take().filter(|&(c, _)| Some(c) == hovered);
+            if let Some((c, before)) = came_down.or(hovered.map(|c| (c, universe[c]))) {
+                let toggled = if before == Cell::Dead { brush } else { Cell::Dead };
+                if universe[c] != toggled { history.set_pixel(&mut universe, c, toggled); }
+            }
         }
 
-        draw_universe(&universe, grid_spacing, alive_color, dead_color);
-        draw_grid(grid_thickness, grid_color, grid_spacing);
-        draw_controls(text_color, time_between_ticks, paused, grid_spacing);
+        let half = Rect::new(0.0, 0.0, split.x, screen_height());
+        let main_camera = match comparison {
+            Some(_) => view.camera_in(half),
+            None    => view.camera(),
+        };
+        set_camera(&main_camera);
+        // Ants don't leave anything to animate
+        let transition = Some(since_tick / (1.0 / speed).min(animation_time)).filter(|t| animate && !turmites_only && *t < 1.0);
+        let heatmap = Heatmap { ash: grid_color, glow: dying_color };
+        let effects = Effects { ages: show_ages.then_some(age_gradient), trails: show_trails.then_some(trail_length), transition,
+                                heat: show_heat.then_some(heatmap) };
+        let draw_world = |universe: &Universe, texture: &mut CellTexture, view: &View, visible: Rect| {
+            let grid = show_grid.then(|| GridLines::new(grid_thickness, grid_color, grid_spacing, view.zoom, grid_fade));
+            match universe.get_neighborhood() {
+                Neighborhood::Hex => draw_universe(universe, grid_spacing, palette, paints, effects, visible),
+                _                 => texture.draw(universe, grid_spacing, palette, paints, effects, grid, visible),
+            }
+            // Which the cell shader already took care of
+            if let Some(lines) = grid.filter(|_| universe.get_neighborhood() != Neighborhood::Hex && !texture.is_shaded()) {
+                let extent = Rect::new(0.0, 0.0, (universe.get_height() * grid_spacing) as f32, (universe.get_width() * grid_spacing) as f32);
+                if let Some(area) = visible.intersect(extent) { draw_grid(lines, grid_spacing, area); }
+            }
+            draw_turmites(universe, turmite_color, grid_spacing);
+            draw_zones(universe, turmite_color, grid_spacing);
+        };
+        match &comparison {
+            Some(copy) => {
+                draw_world(&universe, &mut cell_texture, &view, view.visible_in(half.size()));
+                let right = Rect { x: split.x, ..half };
+                set_camera(&compare_view.camera_in(right));
+                draw_world(copy, &mut compare_texture, &compare_view, compare_view.visible_in(right.size()));
+                set_camera(&main_camera);
+            }
+            None       => draw_world(&universe, &mut cell_texture, &view, view.visible()),
+        }
+        // Shift+F12 leaves out everything drawn over the universe
+        let screenshot = keys.pressed(Action::Screenshot);
+        if screenshot && shift { save_screenshot(screenshot_dir); }
+        if let Some((from, to)) = selection { draw_selection(from, to, grid_color, grid_spacing); }
+        if matches!(tool, Tool::Pen | Tool::Eraser) && pasting.is_none() && !on_ui {
+            if let Some(c) = hovered { draw_nib(nib, c, grid_color, grid_spacing); }
+        }
+        if let Some(cells) = &shape { draw_cells(cells, Color { a: alive_color.a * 0.5, ..alive_color }, grid_spacing); }
+        if let Some((pattern, at)) = pasting.as_ref().and_then(|pattern| Some((pattern, paste_at(pattern, hovered)?))) {
+            draw_paste(pattern, paste_name.as_deref(), at, alive_color, grid_spacing);
+        }
+        set_default_camera();
+        if let Some(copy) = &comparison { draw_comparison(text_color, grid_color, copy, grid_spacing); }
+        if show_population { draw_population(universe.population_history(), universe.get_generation(), alive_color, text_color, grid_spacing); }
+        // Going back a generation, or loading an earlier one, doesn't count
+        let advanced = universe.get_generation().saturating_sub(last_generation);
+        gens_per_second += (advanced as f32 / get_frame_time().max(1e-3) - gens_per_second) * 0.05;
+        last_generation = universe.get_generation();
+        let hovered = hovered_cell(&universe, &view, grid_spacing);
+        if gamepad.is_some() { draw_reticle(text_color, reticle); }
+        if show_hud { draw_hud(text_color, &universe, gens_per_second, hovered, grid_spacing); }
+        // Alt shows what's going on with the cell under the mouse
+        if let Some(c) = hovered.filter(|_| keys.down(Action::Inspect) && !on_ui) { draw_inspector(text_color, &universe, c, grid_spacing); }
+        draw_toolbar(&mut tool, nib);
+        if fingers.used { draw_touch_bar(&mut paused, &mut touch_step); }
+        #[cfg(feature = "settings")]
+        if show_settings {
+            let sized = world_size;
+            draw_settings(&mut universe, &mut speed, speed_range, &mut view, &mut theme, &mut world_size, grid_spacing);
+            // Around the middle, like when the window's resized
+            if world_size != sized {
+                let dimensions = dimensions_of(world_size);
+                universe.set_dimensions(dimensions, Anchor::Center);
+                if let Some(copy) = &mut comparison { copy.set_dimensions(dimensions, Anchor::Center); }
+            }
+        }
+        if minimap_shown {
+            minimap.refresh(&universe, alive_color, minimap_refresh);
+            minimap.draw(&universe, &view, grid_color, grid_spacing);
+        }
+        draw_controls(&Theme::ALL[theme], &keys, speed, paused, &universe, brush, cycle.map(|cycle| (cycle, apgcode.as_deref())),
+                      grid_spacing);
+        if let Some((explorer, _)) = &explorer { draw_explorer(text_color, explorer, grid_spacing); }
+        if let Some(recorder) = &recording { draw_recording(turmite_color, recorder.frame_count(), grid_spacing); }
+        #[cfg(feature = "video")]
+        if let Some(exporter) = &video { draw_recording(turmite_color, exporter.frame_count() as usize, grid_spacing); }
+        // The toolbar and settings windows only get drawn by next_frame
+        if screenshot && !shift { save_screenshot(screenshot_dir); }
 
         next_frame().await
     }
 }
 
-fn draw_controls(text_color: Color, time_between_ticks: f32, paused: bool, grid_spacing: usize) {
+fn draw_layer_controls(text_color: Color, keys: &KeyMap, layer: usize, space: &Universe3D, paused: bool, grid_spacing: usize) {
     let grid_spacing = grid_spacing as f32;
-    let tps = (time_between_ticks + 1.0) / (1.0/get_fps() as f32 + time_between_ticks);
     let is_p = if paused { "On" } else { "Off" };
 
     draw_rectangle(0.0, 0.0,
-                    grid_spacing*10.0, grid_spacing*7.0,
+                    grid_spacing*11.0, grid_spacing*6.0,
+                    Color::from_rgba(0, 0, 0, 200));
+    draw_text(&format!("{}: Back to 2D", keys.key(Action::Layers)), 10.0, grid_spacing*0.8 + 0.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Change layer", keys.keys(&[Action::LayerUp, Action::LayerDown])), 10.0, grid_spacing*0.8 + 1.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Random soup", keys.key(Action::Soup)), 10.0, grid_spacing*0.8 + 2.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Pause ({is_p})", keys.key(Action::Pause)), 10.0, grid_spacing*0.8 + 3.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Layer {}/{}, gen {}", layer + 1, space.get_depth(), thousands(space.get_generation())),
+                                                   10.0, grid_spacing*0.8 + 5.0*grid_spacing, grid_spacing, text_color);
+}
+
+fn draw_continuous_controls(text_color: Color, keys: &KeyMap, field: &ContinuousUniverse, paused: bool, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let is_p = if paused { "On" } else { "Off" };
+    let rule = match field.get_rule() {
+        ContinuousRule::Lenia { .. }      => "Lenia",
+        ContinuousRule::SmoothLife { .. } => "SmoothLife",
+    };
+
+    draw_rectangle(0.0, 0.0,
+                    grid_spacing*11.0, grid_spacing*6.0,
+                    Color::from_rgba(0, 0, 0, 200));
+    draw_text(&format!("{}: Back to 2D", keys.key(Action::Continuous)), 10.0, grid_spacing*0.8 + 0.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Rule ({rule})", keys.key(Action::Rule)), 10.0, grid_spacing*0.8 + 1.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Random soup", keys.key(Action::Soup)), 10.0, grid_spacing*0.8 + 2.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Pause ({is_p})", keys.key(Action::Pause)), 10.0, grid_spacing*0.8 + 3.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Gen {}, mass {:.0}", thousands(field.get_generation()), field.mass()),
+                                                   10.0, grid_spacing*0.8 + 5.0*grid_spacing, grid_spacing, text_color);
+}
+
+#[allow(clippy::too_many_arguments)]
+fn draw_controls(theme: &Theme, keys: &KeyMap, speed: f32, paused: bool, universe: &Universe, brush: Cell,
+                 cycle: Option<(Cycle, Option<&str>)>, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let symmetry = universe.get_symmetry();
+    let topology = match universe.get_boundaries() {
+        (Boundary::Toroidal, Boundary::Toroidal) => "Torus",
+        (Boundary::Toroidal, Boundary::Dead)     => "Cylinder",
+        (Boundary::Dead, Boundary::Dead)         => "Box",
+        _                                        => "Other",
+    };
+    let rule = rule_name(universe);
+    let zones = match (universe.zones().is_empty(), universe.get_zone_border()) {
+        (true, _)                   => "Off",
+        (false, ZoneBorder::Open)   => "Open",
+        (false, ZoneBorder::Closed) => "Closed",
+    };
+    let coloring = match universe.get_coloring() {
+        Some(coloring) => format!("{coloring:?}, paint {}", universe.get_paint() + 1),
+        None           => "Off".to_owned(),
+    };
+    let is_p = if paused { "On" } else { "Off" };
+    let text_color = theme.accent;
+
+    draw_rectangle(0.0, 0.0,
+                    grid_spacing*13.0, grid_spacing*33.6,
+                    Color::from_rgba(0, 0, 0, 200));
+    draw_text(&format!("{}: Faster/Slower", keys.keys(&[Action::Faster, Action::Slower])), 10.0, grid_spacing*0.8 + 0.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Reset/Invert", keys.keys(&[Action::Reset, Action::Invert])), 10.0, grid_spacing*0.8 + 1.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Pause ({is_p})", keys.key(Action::Pause)), 10.0, grid_spacing*0.8 + 2.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Ctrl+{}: Undo/Redo", keys.keys(&[Action::Undo, Action::Redo])), 10.0, grid_spacing*0.8 + 3.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Ctrl+{}: Save/Load session", keys.keys(&[Action::SaveSession, Action::LoadSession])), 10.0, grid_spacing*0.8 + 4.0*grid_spacing, grid_spacing, text_color);
+    #[cfg(feature = "net")]
+    let clipboard = format!("Ctrl+{}: Cut/Copy/Paste (Shift: fetch)", keys.keys(&[Action::Cut, Action::Copy, Action::Paste]));
+    #[cfg(not(feature = "net"))]
+    let clipboard = format!("Ctrl+{}: Cut/Copy/Paste", keys.keys(&[Action::Cut, Action::Copy, Action::Paste]));
+    draw_text(&clipboard,                          10.0, grid_spacing*0.8 + 5.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}/Ctrl+{}: Clear/Fill selection", keys.key(Action::Clear), keys.key(Action::Fill)), 10.0, grid_spacing*0.8 + 6.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}/Shift+{}: Turn/Flip selection", keys.key(Action::Turn), keys.key(Action::Flip)), 10.0, grid_spacing*0.8 + 7.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Step back/on (Shift: 10)", keys.keys(&[Action::StepBack, Action::Step])), 10.0, grid_spacing*0.8 + 8.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Random/Noise soup", keys.keys(&[Action::Soup, Action::Noise])), 10.0, grid_spacing*0.8 + 9.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Symmetry ({symmetry:?})", keys.key(Action::Symmetry)), 10.0, grid_spacing*0.8 + 10.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Shift+Arrows/{}: Move/Inspect", keys.key(Action::Inspect)), 10.0, grid_spacing*0.8 + 11.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Wheel/Middle/{}: Zoom/Pan/Home", keys.key(Action::Home)), 10.0, grid_spacing*0.8 + 12.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Graph/Grid/Ages/Trails", keys.keys(&[Action::Population, Action::Grid, Action::Ages, Action::Trails])), 10.0, grid_spacing*0.8 + 13.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Animations/Heatmap", keys.keys(&[Action::Animations, Action::Heatmap])), 10.0, grid_spacing*0.8 + 14.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: HUD/Settings/Full", keys.keys(&[Action::Hud, Action::Settings, Action::Fullscreen])), 10.0, grid_spacing*0.8 + 15.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Screenshot (Shift: clean)", keys.key(Action::Screenshot)), 10.0, grid_spacing*0.8 + 16.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: 3D/Hex/Theme ({})", keys.keys(&[Action::Layers, Action::Hex, Action::Theme]), theme.name), 10.0, grid_spacing*0.8 + 17.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Wrap ({topology})", keys.key(Action::Wrap)), 10.0, grid_spacing*0.8 + 18.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Rule ({rule})", keys.key(Action::Rule)), 10.0, grid_spacing*0.8 + 19.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Brush ({brush:?})", keys.key(Action::Brush)), 10.0, grid_spacing*0.8 + 20.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Add ant/Ants only", keys.keys(&[Action::AddAnt, Action::AntsOnly])), 10.0, grid_spacing*0.8 + 21.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Colors ({coloring})", keys.keys(&[Action::Colors, Action::Paint])), 10.0, grid_spacing*0.8 + 22.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Block rules/Library", keys.keys(&[Action::BlockRules, Action::Library])), 10.0, grid_spacing*0.8 + 23.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}, {}: 1D, its rule number", keys.key(Action::Elementary), keys.keys(&[Action::RuleDown, Action::RuleUp])), 10.0, grid_spacing*0.8 + 24.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Zones ({zones})", keys.key(Action::Zones)), 10.0, grid_spacing*0.8 + 25.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Rule explorer", keys.key(Action::Explorer)), 10.0, grid_spacing*0.8 + 26.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("{}: Compare/Its rule/Swap", keys.keys(&[Action::Compare, Action::CompareRule, Action::CompareSwap])),
+                                                   10.0, grid_spacing*0.8 + 27.0*grid_spacing, grid_spacing, text_color);
+    #[cfg(feature = "image")]
+    let save = format!("{}/Shift+{}/{}: .rle/.svg/.png", keys.key(Action::SaveRle), keys.key(Action::SaveSvg), keys.key(Action::SavePng));
+    #[cfg(not(feature = "image"))]
+    let save = format!("{}/Shift+{}: .rle/.svg", keys.key(Action::SaveRle), keys.key(Action::SaveSvg));
+    draw_text(&save,                               10.0, grid_spacing*0.8 + 28.0*grid_spacing, grid_spacing, text_color);
+    #[cfg(feature = "video")]
+    let record = format!("{}/Shift+{}: Record gif/mp4", keys.key(Action::RecordGif), keys.key(Action::RecordVideo));
+    #[cfg(not(feature = "video"))]
+    let record = format!("{}: Record GIF", keys.key(Action::RecordGif));
+    draw_text(&record,                             10.0, grid_spacing*0.8 + 29.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("Speed: {speed:.1} gen/s"), 10.0, grid_spacing*0.8 + 30.0*grid_spacing, grid_spacing, text_color);
+    if let Some((cycle, apgcode)) = cycle {
+        let kind = match cycle.is_still() {
+            true  => "a still life".to_owned(),
+            false => format!("a period-{} oscillator", cycle.period),
+        };
+        draw_text(&format!("Stabilized into {kind}"),           10.0, grid_spacing*0.8 + 31.0*grid_spacing, grid_spacing*0.6, text_color);
+        draw_text(&format!("at gen {}", thousands(cycle.since)), 10.0, grid_spacing*0.8 + 31.6*grid_spacing, grid_spacing*0.6, text_color);
+        if let Some(apgcode) = apgcode {
+            draw_text(apgcode,                                   10.0, grid_spacing*0.8 + 32.2*grid_spacing, grid_spacing*0.6, text_color);
+        }
+    }
+}
+
+/// Whatever the universe runs by, by the name the controls give it
+fn rule_name(universe: &Universe) -> String {
+    match (universe.get_elementary_rule(), universe.get_block_rule(), universe.get_rule_table()) {
+        (Some(number), _, _)                   => format!("Rule {number}"),
+        (_, Some(BlockRule::CRITTERS), _)      => "Critters".to_owned(),
+        (_, Some(BlockRule::TRON), _)          => "Tron".to_owned(),
+        (_, Some(BlockRule::BILLIARD_BALL), _) => "Billiard ball".to_owned(),
+        (_, Some(_), _)                        => "Block rule".to_owned(),
+        (_, None, Some(table))                 => table.get_name().to_owned(),
+        (_, None, None)                        => RulePreset::of(universe.get_rule()).map_or(universe.get_rule().to_string(), |p| p.to_string()),
+    }
+}
+
+/// A line down the middle of the screen, and what the universe on the right runs by and how it's doing
+fn draw_comparison(text_color: Color, line_color: Color, copy: &Universe, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let middle = screen_width() / 2.0;
+    draw_line(middle, 0.0, middle, screen_height(), 2.0, line_color);
+    let text = format!("{}   Pop {}", rule_name(copy), thousands(copy.population() as u64));
+    let size = measure_text(&text, None, grid_spacing as u16, 1.0);
+    draw_rectangle(middle + 10.0, grid_spacing*1.5, size.width + 20.0, grid_spacing*1.2, Color::from_rgba(0, 0, 0, 200));
+    draw_text(&text, middle + 20.0, grid_spacing*2.3, grid_spacing, text_color);
+}
+
+/// Generation, population, how many generations a second are actually going by, frames a second and the
+/// `hovered` cell's (row, column), along the top of the screen
+fn draw_hud(text_color: Color, universe: &Universe, gens_per_second: f32, hovered: Option<Coord>, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let mut text = format!("Gen {}   Pop {}   {gens_per_second:.1} gen/s   {} FPS", thousands(universe.get_generation()),
+                           thousands(universe.population() as u64), get_fps());
+    if let Some(Coord { row, col }) = hovered { text.push_str(&format!("   ({row}, {col})")); }
+    let size = measure_text(&text, None, grid_spacing as u16, 1.0);
+    let x = (screen_width() - size.width) / 2.0;
+    draw_rectangle(x - 10.0, 0.0, size.width + 20.0, grid_spacing*1.2, Color::from_rgba(0, 0, 0, 200));
+    draw_text(&text, x, grid_spacing*0.8, grid_spacing, text_color);
+}
+
+/// The state, age and live neighbors of the cell at `c`, by the mouse
+fn draw_inspector(text_color: Color, universe: &Universe, c: Coord, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let state = match universe[c] {
+        Cell::Alive     => "Alive".to_owned(),
+        Cell::Dying(k)  => format!("Dying ({k})"),
+        Cell::Conductor => "Conductor".to_owned(),
+        Cell::Dead      => "Dead".to_owned(),
+    };
+    let lines = [state, format!("Age {}", universe.age_at(c)), format!("{} neighbors alive", universe.alive_neighbor_count(c))];
+    let (x, y) = mouse_position();
+    let (x, y) = (x + grid_spacing*0.6, y + grid_spacing*0.6);
+    draw_rectangle(x, y, grid_spacing*7.0, grid_spacing*0.7 * lines.len() as f32 + grid_spacing*0.3, Color::from_rgba(0, 0, 0, 200));
+    for (i, line) in lines.iter().enumerate() {
+        draw_text(line, x + 6.0, y + grid_spacing*0.7 * (i + 1) as f32, grid_spacing*0.7, text_color);
+    }
+}
+
+/// A button for each tool along the bottom of the screen, then the brush's size
+fn draw_toolbar(tool: &mut Tool, nib: Nib) {
+    let size = vec2(Tool::ALL.len() as f32 * 70.0 + 160.0, 44.0);
+    let position = vec2((screen_width() - size.x) / 2.0, screen_height() - size.y - 10.0);
+    widgets::Window::new(hash!(), position, size).titlebar(false).movable(false).ui(&mut root_ui(), |ui| {
+        for t in Tool::ALL {
+            if widgets::Button::new(t.name()).size(vec2(64.0, 30.0)).selected(*tool == t).ui(ui) { *tool = t; }
+            ui.same_line(0.0);
+        }
+        ui.label(None, &format!("{nib} (Ctrl+Wheel)"));
+    });
+}
+
+/// Crosshairs for the gamepad to aim with
+fn draw_reticle(color: Color, at: Vec2) {
+    draw_circle_lines(at.x, at.y, 10.0, 2.0, color);
+    draw_line(at.x - 16.0, at.y, at.x - 6.0, at.y, 2.0, color);
+    draw_line(at.x + 6.0, at.y, at.x + 16.0, at.y, 2.0, color);
+    draw_line(at.x, at.y - 16.0, at.x, at.y - 6.0, 2.0, color);
+    draw_line(at.x, at.y + 6.0, at.x, at.y + 16.0, 2.0, color);
+}
+
+/// Play/pause and step buttons in the bottom right corner, for when there's no keyboard
+fn draw_touch_bar(paused: &mut bool, step: &mut bool) {
+    let size = vec2(160.0, 44.0);
+    let position = vec2(screen_width() - size.x - 10.0, screen_height() - size.y - 64.0);
+    widgets::Window::new(hash!(), position, size).titlebar(false).movable(false).ui(&mut root_ui(), |ui| {
+        if widgets::Button::new(if *paused { "Play" } else { "Pause" }).size(vec2(70.0, 30.0)).ui(ui) { *paused = !*paused; }
+        ui.same_line(0.0);
+        if widgets::Button::new("Step").size(vec2(70.0, 30.0)).ui(ui) { *step = true; }
+    });
+}
+
+/// A window under the HUD to pick the rule, speed, size of the cells and of the world, theme and boundaries with the
+/// mouse. It's drawn with macroquad's own widgets, egui-macroquad not being a dependency.
+#[cfg(feature = "settings")]
+fn draw_settings(universe: &mut Universe, speed: &mut f32, speed_range: (f32, f32), view: &mut View, theme: &mut usize,
+                 world_size: &mut Option<(usize, usize)>, grid_spacing: usize) {
+    let rules = RulePreset::ALL.iter().map(RulePreset::name).chain(["Custom"]).collect::<Vec<_>>();
+    let themes = Theme::ALL.map(|theme| theme.name);
+    let boundaries = [Boundary::Toroidal, Boundary::Dead, Boundary::Mirror];
+    let boundary_names = ["Wrap", "Dead", "Mirror"];
+
+    let position = vec2(screen_width() / 2.0 - 170.0, grid_spacing as f32 * 1.5);
+    widgets::Window::new(hash!(), position, vec2(340.0, 240.0)).label("Settings (F2)").ui(&mut root_ui(), |ui| {
+        let current = RulePreset::of(universe.get_rule()).and_then(|p| RulePreset::ALL.iter().position(|&q| q == p));
+        let mut rule = current.unwrap_or(RulePreset::ALL.len());
+        widgets::ComboBox::new(hash!(), &rules).label("Rule").ui(ui, &mut rule);
+        if Some(rule) != current && rule < RulePreset::ALL.len() { universe.set_rule(RulePreset::ALL[rule].rule()); }
+
+        ui.slider(hash!(), "Gen/s", speed_range.0..speed_range.1, speed);
+
+        // The size cells are drawn at, by zooming around the middle of the screen
+        let mut cell_size = grid_spacing as f32 * view.zoom;
+        ui.slider(hash!(), "Cell size", 2.0..120.0, &mut cell_size);
+        let factor = cell_size / (grid_spacing as f32 * view.zoom);
+        if (factor - 1.0).abs() > 1e-3 { view.zoom_at(vec2(screen_width(), screen_height()) / 2.0, factor); }
+
+        // In cells, fitting the window until either is moved. Rows run along the screen's x axis.
+        let mut fit = world_size.is_none();
+        ui.checkbox(hash!(), "Fit the window", &mut fit);
+        let (mut width, mut height) = (universe.get_height() as f32, universe.get_width() as f32);
+        // Worlds made bigger than that some other way are left as they are
+        let most = width.max(height).max(2000.0);
+        ui.slider(hash!(), "Width", 8.0..most, &mut width);
+        ui.slider(hash!(), "Height", 8.0..most, &mut height);
+        let size = (width.round() as usize, height.round() as usize);
+        *world_size = match fit {
+            _ if size != (universe.get_height(), universe.get_width()) => Some(size),
+            true                                                       => None,
+            false                                                      => Some(size),
+        };
+
+        widgets::ComboBox::new(hash!(), &themes).label("Theme").ui(ui, theme);
+
+        // Rows run along the screen's x axis, so across the screen is vertical to the universe
+        let (vertical, horizontal) = universe.get_boundaries();
+        let index = |b: Boundary| boundaries.iter().position(|&c| c == b).unwrap_or(0);
+        let (mut across, mut down) = (index(vertical), index(horizontal));
+        widgets::ComboBox::new(hash!(), &boundary_names).label("Across").ui(ui, &mut across);
+        widgets::ComboBox::new(hash!(), &boundary_names).label("Down").ui(ui, &mut down);
+        if (boundaries[across], boundaries[down]) != (vertical, horizontal) {
+            universe.set_boundaries(boundaries[across], boundaries[down]);
+        }
+    });
+}
+
+/// Dot and frame count in the bottom right corner while a GIF is being recorded
+fn draw_recording(color: Color, frames: usize, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let (x, y) = (screen_width() - grid_spacing*5.0, screen_height() - grid_spacing);
+    draw_circle(x, y - grid_spacing*0.3, grid_spacing*0.3, color);
+    draw_text(&format!("{} frames", thousands(frames as u64)), x + grid_spacing*0.6, y, grid_spacing, color);
+}
+
+/// Best rules the explorer found so far, in the top right corner
+fn draw_explorer(text_color: Color, explorer: &RuleExplorer, grid_spacing: usize) {
+    let grid_spacing = grid_spacing as f32;
+    let left = screen_width() - grid_spacing*11.0;
+
+    draw_rectangle(left, 0.0,
+                    grid_spacing*11.0, grid_spacing*(3.0 + explorer.candidates().len() as f32),
                     Color::from_rgba(0, 0, 0, 200));
-    draw_text("U: Increase Speed",                 10.0, grid_spacing*0.8 + 0.0*grid_spacing, grid_spacing, text_color);
-    draw_text("D: Decrease Speed",                 10.0, grid_spacing*0.8 + 1.0*grid_spacing, grid_spacing, text_color);
-    draw_text("R: Reset",                          10.0, grid_spacing*0.8 + 2.0*grid_spacing, grid_spacing, text_color);
-    draw_text(&format!("Space: Pause ({is_p})"),   10.0, grid_spacing*0.8 + 3.0*grid_spacing, grid_spacing, text_color);
-    draw_text(&format!("Speed: {tps:.2} tps",),    10.0, grid_spacing*0.8 + 5.0*grid_spacing, grid_spacing, text_color);
+    draw_text(&format!("X: Explorer ({} tried)", explorer.get_tried()), left + 10.0, grid_spacing*0.8, grid_spacing, text_color);
+    draw_text("Tab: Try the next one",             left + 10.0, grid_spacing*0.8 + 1.0*grid_spacing, grid_spacing, text_color);
+    for (i, candidate) in explorer.candidates().iter().enumerate() {
+        draw_text(&format!("{} ({:.0}%)", candidate.rule, candidate.score * 100.0),
+                  left + 10.0, grid_spacing*0.8 + (2.0 + i as f32)*grid_spacing, grid_spacing, text_color);
+    }
+}
+
+/// Population over the last generations, up to `generation`, as a line graph in the bottom right corner. Its axes
+/// stretch to fit what's on it: as many generations as there are pixels across, and from the lowest population
+/// among them to the highest.
+fn draw_population(history: &std::collections::VecDeque<u64>, generation: u64, line_color: Color, text_color: Color, grid_spacing: usize) {
+    let (graph_w, graph_h) = (grid_spacing as f32 * 10.0, grid_spacing as f32 * 4.0);
+    let (x0, y0) = (screen_width() - graph_w, screen_height() - graph_h);
+    draw_rectangle(x0, y0, graph_w, graph_h, Color::from_rgba(0, 0, 0, 200));
+
+    // Room for the labels, and a margin above and below the line
+    let font_size = grid_spacing as f32 * 0.5;
+    let (left, top, bottom) = (x0 + font_size * 0.5, y0 + font_size * 1.2, y0 + graph_h - font_size * 1.2);
+    // A generation per pixel at most
+    let recent = history.range(history.len().saturating_sub((x0 + graph_w - left) as usize)..).copied().collect::<Vec<_>>();
+    let (low, high) = (recent.iter().copied().min().unwrap_or(0), recent.iter().copied().max().unwrap_or(0));
+    let step = (x0 + graph_w - left) / recent.len().saturating_sub(1).max(1) as f32;
+    let point = |i: usize, p: u64| {
+        let height = if high > low { (p - low) as f32 / (high - low) as f32 } else { 0.5 };
+        vec2(left + i as f32 * step, bottom - (bottom - top) * height)
+    };
+    draw_line(left, top, left, bottom, 1.0, text_color);
+    draw_line(left, bottom, x0 + graph_w, bottom, 1.0, text_color);
+    for (i, pair) in recent.windows(2).enumerate() {
+        let (a, b) = (point(i, pair[0]), point(i + 1, pair[1]));
+        draw_line(a.x, a.y, b.x, b.y, 2.0, line_color);
+    }
+
+    let first = generation.saturating_sub(recent.len().saturating_sub(1) as u64);
+    draw_text(&thousands(high), left + 4.0, top - font_size * 0.3, font_size, text_color);
+    draw_text(&format!("{} (gen {}-{})", thousands(low), thousands(first), thousands(generation)),
+              left + 4.0, bottom + font_size * 0.9, font_size, text_color);
+}
+
+/// Saves what's been drawn this frame so far as `dir`/screenshot-YYYY-MM-DD_HH-MM-SS.png, in UTC. Those taken
+/// within the same second go on to -2, -3 and so on instead of overwriting it.
+fn save_screenshot(dir: &str) {
+    let stamp = timestamp(miniquad::date::now());
+    match std::fs::create_dir_all(dir) {
+        Ok(()) => {
+            let mut path = format!("{dir}/screenshot-{stamp}.png");
+            for n in 2.. {
+                if !std::path::Path::new(&path).exists() { break; }
+                path = format!("{dir}/screenshot-{stamp}-{n}.png");
+            }
+            get_screen_data().export_png(&path);
+            println!("Saved {path}");
+        }
+        Err(e) => eprintln!("Couldn't make {dir}: {e}"),
+    }
+}
+
+/// `seconds` since the Unix epoch as a UTC date and time, `1e9` => `"2001-09-09_01-46-40"`
+fn timestamp(seconds: f64) -> String {
+    let (days, seconds) = ((seconds as i64).div_euclid(86400), (seconds as i64).rem_euclid(86400));
+    // Howard Hinnant's civil_from_days, from 400 year eras starting on March 1st
+    let (era, day_of_era) = ((days + 719468).div_euclid(146097), (days + 719468).rem_euclid(146097));
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month + 2) / 5 + 1;
+    let (year, month) = match month {
+        0..=9 => (era * 400 + year_of_era, month + 3),
+        _     => (era * 400 + year_of_era + 1, month - 9),
+    };
+    format!("{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// `1203` => `"1,203"`
+fn thousands(n: u64) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() * 4 / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) { out.push(','); }
+        out.push(c);
+    }
+    out
+}
+
+/// The cells within `visible`, the part of the world on screen
+fn visible_cells(universe: &Universe, grid_spacing: usize, visible: Rect) -> impl Iterator<Item = Coord> {
+    // Rows run along the screen's x axis, and hex grids shift columns by half a cell
+    let cells = |from: f32, length: f32, cells: usize| {
+        let spacing = grid_spacing as f32;
+        ((from / spacing).floor().max(0.0) as usize).min(cells)..(((from + length) / spacing).ceil().max(0.0) as usize + 1).min(cells)
+    };
+    let (rows, cols) = (cells(visible.x, visible.w, universe.get_height()), cells(visible.y, visible.h, universe.get_width()));
+    rows.flat_map(move |row| cols.clone().map(move |col| Coord::new(row, col)))
+}
+
+/// A rectangle for each of the cells within `visible`, colored by `cell_color`. Slow for big universes, but
+/// `CellTexture` can't shift the columns of hex grids.
+fn draw_universe(universe: &Universe, grid_spacing: usize, palette: [Color; 4], paints: [Color; 4], effects: Effects,
+                 visible: Rect) {
+    for c in visible_cells(universe, grid_spacing, visible) {
+        let x = (c.row * grid_spacing) as f32;
+        let y = (c.col * grid_spacing) as f32 + hex_shift(universe, c.row, grid_spacing);
+        draw_rectangle(x, y, grid_spacing as f32, grid_spacing as f32, cell_color(universe, c, palette, paints, effects));
+    }
+    draw_births(universe, grid_spacing, palette, paints, effects, visible);
+}
+
+/// The cells within `visible` that were born in the last generation, which `cell_color` leaves out while they're
+/// animated, growing from their middle
+fn draw_births(universe: &Universe, grid_spacing: usize, palette: [Color; 4], paints: [Color; 4], effects: Effects,
+               visible: Rect) {
+    let Some(t) = effects.transition.filter(|_| effects.heat.is_none()) else { return };
+    let size = grid_spacing as f32 * t;
+    let born = visible_cells(universe, grid_spacing, visible).filter(|&c| universe.is_alive(c) && universe.previous_at(c) != Cell::Alive);
+    for c in born {
+        let x = (c.row * grid_spacing) as f32;
+        let y = (c.col * grid_spacing) as f32 + hex_shift(universe, c.row, grid_spacing);
+        let color = cell_color(universe, c, palette, paints, Effects { transition: None, ..effects });
+        let margin = (grid_spacing as f32 - size) / 2.0;
+        draw_rectangle(x + margin, y + margin, size, size, color);
+    }
+}
+
+/// Live cells go by their age along `ages` if given, and by their color under Immigration and QuadLife otherwise.
+/// Cells that died less than `trails` generations ago linger as fading ghosts. Partway through a `transition`,
+/// cells that just died are still fading out, and the ones just born are left for `draw_births`. A `heat` map
+/// leaves all of that out.
+fn cell_color(universe: &Universe, c: Coord, palette: [Color; 4], paints: [Color; 4], effects: Effects) -> Color {
+    let [alive_color, dying_color, conductor_color, dead_color] = palette;
+    let (cyclic, states) = (universe.get_rule().is_cyclic(), universe.states() as f32);
+    let decay_steps = states - 1.0;
+
+    let Effects { ages, trails, transition, heat } = effects;
+    let cell = universe[c];
+    if let Some(heatmap) = heat {
+        let base = if cell == Cell::Dead { dead_color } else { heatmap.ash };
+        return heatmap.color(base, universe.activity_at(c));
+    }
+    let alive_color = match (ages, universe.get_coloring()) {
+        (Some(ages), _) => ages.color(universe.age_at(c)),
+        (None, Some(_)) => paints[universe.color_at(c) as usize],
+        (None, None)    => alive_color,
+    };
+    let color = match cell {
+        // Cyclic rules go round the color wheel
+        _ if cyclic     => hsl_to_rgb(cell.state(universe.conductor_state()) as f32 / states, 0.6, 0.7),
+        Cell::Alive     => alive_color,
+        Cell::Dying(k)  => Color { a: dying_color.a * (1.0 - k.saturating_sub(1) as f32 / decay_steps), ..dying_color },
+        Cell::Conductor => conductor_color,
+        Cell::Dead      => match (trails, universe.since_death(c)) {
+            (Some(length), since) if since > 0 && since <= length
+                            => Color { a: alive_color.a * 0.5 * (1.0 - since as f32 / (length + 1) as f32), ..alive_color },
+            _               => dead_color,
+        },
+    };
+    match transition {
+        Some(t) if !cyclic => match (universe.previous_at(c) == Cell::Alive, cell == Cell::Alive) {
+            (false, true) => dead_color,
+            (true, false) => {
+                // Into transparent cells the color stays, and only the alpha goes
+                let to = if color.a == 0.0 { Color { a: 0.0, ..alive_color } } else { color };
+                Color::from_vec(alive_color.to_vec() + (to.to_vec() - alive_color.to_vec()) * t)
+            }
+            _             => color,
+        },
+        _                  => color,
+    }
+}
+
+/// Each cell as opaque as it is full
+fn draw_continuous(field: &ContinuousUniverse, color: Color, spacing: usize) {
+    for row in 0..field.get_height() {
+        for col in 0..field.get_width() {
+            let value = field.get(Coord::new(row, col));
+            if value <= 0.0 { continue; }
+            draw_rectangle((row * spacing) as f32, (col * spacing) as f32, spacing as f32, spacing as f32,
+                           Color { a: color.a * value, ..color });
+        }
+    }
+}
+
+/// A dot for each turmite, nudged towards where it's heading
+fn draw_turmites(universe: &Universe, color: Color, grid_spacing: usize) {
+    let spacing = grid_spacing as f32;
+    for turmite in universe.turmites() {
+        let Coord { row, col } = turmite.position;
+        // Rows run along the screen's x axis
+        let (dx, dy) = match turmite.heading {
+            Heading::North => (-1.0, 0.0),
+            Heading::East  => (0.0, 1.0),
+            Heading::South => (1.0, 0.0),
+            Heading::West  => (0.0, -1.0),
+        };
+        let x = (row as f32 + 0.5 + dx * 0.2) * spacing;
+        let y = (col as f32 + 0.5 + dy * 0.2) * spacing + hex_shift(universe, row, grid_spacing);
+        draw_circle(x, y, spacing * 0.3, color);
+    }
+}
+
+/// Outline of every zone
+fn draw_zones(universe: &Universe, color: Color, grid_spacing: usize) {
+    let spacing = grid_spacing as f32;
+    for zone in universe.zones() {
+        // Rows run along the screen's x axis
+        draw_rectangle_lines(zone.top_left.row as f32 * spacing, zone.top_left.col as f32 * spacing,
+                             zone.size.row as f32 * spacing, zone.size.col as f32 * spacing, 4.0, color);
+    }
+}
+
+/// Cells across and down, as in `500x300`, neither of them zero
+fn parse_world_size(s: &str) -> Option<(usize, usize)> {
+    let (across, down) = s.split_once(['x', 'X'])?;
+    let size = (across.trim().parse().ok()?, down.trim().parse().ok()?);
+    (size.0 > 0 && size.1 > 0).then_some(size)
+}
+
+/// Loads a Golly .rule file, or an .rle, .cells, .lif or .mc pattern (or, with the `image` feature, a PNG)
+/// into the middle of the universe
+fn load(path: &str, universe: &mut Universe) -> Result<(), String> {
+    let extension = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+    // Patterns come in transposed, like the frontend's coordinates
+    let (height, width) = (universe.get_width(), universe.get_height());
+    let (pattern, rule) = match extension.as_str() {
+        #[cfg(feature = "image")]
+        "png"          => (Pattern::from(&Universe::from_image(path, 0.5, true).map_err(|e| e.to_string())?), None),
+        #[cfg(feature = "image")]
+        "jpg" | "jpeg" => return Err("only PNG images are supported".to_owned()),
+        extension      => {
+            let s = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+            match extension {
+                "rle"          => Pattern::from_rle(&s).map_err(|e| e.to_string())?,
+                "cells"        => (Pattern::from_plaintext(&s).map_err(|e| e.to_string())?, None),
+                "lif" | "life" => Pattern::from_life(&s).map_err(|e| e.to_string())?,
+                // Macrocell patterns can be far bigger than the screen, so only what's around the origin gets loaded
+                "mc"           => {
+                    let hl = HashLife::from_macrocell(&s).map_err(|e| e.to_string())?;
+                    let top_left = PlaneCoord::new(-(height as i64 / 2), -(width as i64 / 2));
+                    (Pattern::from(&hl.to_universe(top_left, height, width)), Some(hl.get_rule()))
+                }
+                _              => {
+                    universe.set_rule_table(Some(s.parse::<RuleTable>().map_err(|e| e.to_string())?));
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    if let Some(rule) = rule { universe.set_rule(rule); }
+    let pattern = transpose(&pattern);
+    let at = |len: usize, of: usize| len.saturating_sub(of) / 2;
+    let at = Coord::new(at(universe.get_height(), pattern.get_height()), at(universe.get_width(), pattern.get_width()));
+    universe.blit(&pattern, at, BlitMode::Copy);
+    Ok(())
+}
+
+/// Pattern out of the contents of an .rle, .cells or .lif file (or, with the `image` feature, a PNG), going by
+/// the extension of its `name`
+fn read_pattern(name: &str, bytes: &[u8]) -> Result<Pattern, String> {
+    let extension = name.rsplit('.').next().unwrap_or_default().to_lowercase();
+    let s = || String::from_utf8_lossy(bytes);
+    match extension.as_str() {
+        #[cfg(feature = "image")]
+        "png"          => Universe::from_image_bytes(bytes, 0.5, true).map(|u| Pattern::from(&u)).map_err(|e| e.to_string()),
+        #[cfg(feature = "image")]
+        "jpg" | "jpeg" => Err("only PNG images are supported".to_owned()),
+        "rle"          => Pattern::from_rle(&s()).map(|(pattern, _)| pattern).map_err(|e| e.to_string()),
+        "cells"        => Pattern::from_plaintext(&s()).map_err(|e| e.to_string()),
+        "lif" | "life" => Pattern::from_life(&s()).map(|(pattern, _)| pattern).map_err(|e| e.to_string()),
+        _              => Err(format!("{name} isn't a pattern")),
+    }
+}
+
+/// Swaps rows and columns, since the frontend's rows run along the screen's x axis
+fn transpose(pattern: &Pattern) -> Pattern { pattern.rotate_cw().flip_h() }
+
+/// Outline of the cells between two corners
+fn draw_selection(from: Coord, to: Coord, color: Color, grid_spacing: usize) {
+    let spacing = grid_spacing as f32;
+    let (top_left, bottom_right) = corners(from, to);
+    // Rows run along the screen's x axis
+    let area = Rect::new(top_left.row as f32 * spacing, top_left.col as f32 * spacing,
+                         (bottom_right.row - top_left.row + 1) as f32 * spacing, (bottom_right.col - top_left.col + 1) as f32 * spacing);
+    draw_rectangle_lines(area.x, area.y, area.w, area.h, 4.0, Color::from_rgba(0, 0, 0, 200));
+    draw_marching_ants(area, spacing / 2.0, 2.0, color);
+}
+
+/// A dashed outline, its dashes crawling clockwise around `area` by a gap and a dash every second
+fn draw_marching_ants(area: Rect, dash: f32, thickness: f32, color: Color) {
+    let corners = [area.point(), vec2(area.right(), area.top()), area.point() + area.size(), vec2(area.left(), area.bottom())];
+    let period = dash * 2.0;
+    let phase = (get_time() as f32 * period) % period;
+    // How far around the outline the current side starts
+    let mut along = 0.0;
+    for (i, &from) in corners.iter().enumerate() {
+        let to = corners[(i + 1) % corners.len()];
+        let length = from.distance(to);
+        if length <= 0.0 { continue; }
+        let point = |at: f32| from + (to - from) * ((at - along) / length);
+        let mut start = ((along - phase) / period).floor() * period + phase;
+        while start < along + length {
+            let (a, b) = (start.max(along), (start + dash).min(along + length));
+            if b > a { draw_line(point(a).x, point(a).y, point(b).x, point(b).y, thickness, color); }
+            start += period;
+        }
+        along += length;
+    }
+}
+
+/// Faint squares over the cells the pen or eraser would cover around `at`
+fn draw_nib(nib: Nib, at: Coord, color: Color, grid_spacing: usize) {
+    draw_cells(&nib.cells(at).collect::<Vec<_>>(), Color { a: color.a * 0.25, ..color }, grid_spacing);
+}
+
+fn draw_cells(cells: &[Coord], color: Color, grid_spacing: usize) {
+    let spacing = grid_spacing as f32;
+    for Coord { row, col } in cells {
+        draw_rectangle(*row as f32 * spacing, *col as f32 * spacing, spacing, spacing, color);
+    }
+}
+
+/// Top left and bottom right corners of the selection between `from` and `to`, both included
+fn corners(from: Coord, to: Coord) -> (Coord, Coord) {
+    (Coord::new(from.row.min(to.row), from.col.min(to.col)), Coord::new(from.row.max(to.row), from.col.max(to.col)))
+}
+
+/// The cells of the selection between `from` and `to`
+fn selected_pattern(universe: &Universe, from: Coord, to: Coord) -> Pattern {
+    let (top_left, bottom_right) = corners(from, to);
+    Pattern::from(&universe.extract(top_left, bottom_right.row - top_left.row + 1, bottom_right.col - top_left.col + 1))
+}
+
+/// The pattern's cells that aren't dead, see-through and in a faint box, as they'd land with their top left corner
+/// at `at`, under its name if it has one
+fn draw_paste(pattern: &Pattern, name: Option<&str>, at: Coord, color: Color, grid_spacing: usize) {
+    let spacing = grid_spacing as f32;
+    draw_rectangle_lines(at.row as f32 * spacing, at.col as f32 * spacing, pattern.get_height() as f32 * spacing,
+                         pattern.get_width() as f32 * spacing, spacing / 10.0, Color { a: color.a * 0.3, ..color });
+    for (Coord { row, col }, _) in pattern.iter_cells().filter(|(_, cell)| *cell != Cell::Dead) {
+        draw_rectangle((at.row + row) as f32 * spacing, (at.col + col) as f32 * spacing, spacing, spacing,
+                       Color { a: color.a * 0.5, ..color });
+    }
+    if let Some(name) = name { draw_text(name, at.row as f32 * spacing, at.col as f32 * spacing - spacing*0.3, spacing, color); }
+}
+
+/// Where the pattern in hand would land: centered on the `hovered` cell, as far as the universe's top left corner
+/// lets it, or `None` off the grid
+fn paste_at(pattern: &Pattern, hovered: Option<Coord>) -> Option<Coord> {
+    hovered.map(|c| Coord::new(c.row.saturating_sub(pattern.get_height() / 2), c.col.saturating_sub(pattern.get_width() / 2)))
+}
+
+/// Cell under the mouse, or `None` off the grid
+fn hovered_cell(universe: &Universe, view: &View, grid_spacing: usize) -> Option<Coord> {
+    cell_at(universe, view, mouse_position().into(), grid_spacing)
+}
+
+/// Cell under `screen`, or `None` off the grid
+fn cell_at(universe: &Universe, view: &View, screen: Vec2, grid_spacing: usize) -> Option<Coord> {
+    let Vec2 { x: globl_x, y: globl_y } = view.world_at(screen);
+    let spacing = grid_spacing as f32;
+    if globl_x < 0.0 { return None; }
+    let row = (globl_x / spacing) as usize;
+    let globl_y = globl_y - hex_shift(universe, row, grid_spacing);
+    if globl_y < 0.0 { return None; }
+    Some(Coord::new(row, (globl_y / spacing) as usize)).filter(|c| c.row < universe.get_height() && c.col < universe.get_width())
 }
 
-fn draw_universe(universe: &Universe, grid_spacing: usize, alive_color: Color, dead_color: Color) {
-    for (i, _) in universe.cells.iter().enumerate() {
-    let c@Coord { row, col } = universe.idx_to_coords(i);
-        let x = row * grid_spacing;
-        let y = col * grid_spacing;
+/// On a hex grid odd rows sit half a cell further along
+fn hex_shift(universe: &Universe, row: usize, grid_spacing: usize) -> f32 {
+    match universe.get_neighborhood() {
+        Neighborhood::Hex if row % 2 == 1 => grid_spacing as f32 / 2.0,
+        _                                 => 0.0,
+    }
+}
+
+/// `grid_lines` between the cells in `area` of the world
+fn draw_grid(grid_lines: GridLines, grid_spacing: usize, area: Rect) {
+    let GridLines { color: grid_color, thickness: grid_thickness, thin, bold } = grid_lines;
+    let spacing = grid_spacing as f32;
+    if bold == 0.0 { return; }
+
+    let lines = |from: f32, length: f32| ((from / spacing).ceil() as usize..=((from + length) / spacing).floor() as usize)
+        .map(|i| (i as f32 * spacing, i % 10 == 0));
+    let style = |bold_line: bool| match bold_line {
+        true  => (grid_thickness * 2.0, Color { a: grid_color.a * bold, ..grid_color }),
+        false => (grid_thickness, Color { a: grid_color.a * thin, ..grid_color }),
+    };
+    for (y, bold_line) in lines(area.y, area.h).filter(|&(_, bold_line)| bold_line || thin > 0.0) {
+        let (thickness, color) = style(bold_line);
+        draw_line(area.x, y,
+                  area.x + area.w, y,
+                  thickness, color);
+    }
+    for (x, bold_line) in lines(area.x, area.w).filter(|&(_, bold_line)| bold_line || thin > 0.0) {
+        let (thickness, color) = style(bold_line);
+        draw_line(x, area.y,
+                  x, area.y + area.h,
+                  thickness, color);
+    }
+}
+
+/// What the left mouse button does, picked from the toolbar. Whatever the tool, a pattern being pasted is dropped
+/// where the button's let go.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tool {
+    /// Sets cells to the brush
+    Pen,
+    /// Kills cells
+    Eraser,
+    /// Drags out a selection, like the right button
+    Select,
+    /// Keeps the pattern being pasted in hand once dropped, to drop it again
+    Stamp,
+    /// Sets the region of same state cells that was clicked to the brush
+    Fill,
+    /// Drags the view around, like the middle button
+    Pan,
+    /// Drags out a straight line
+    Line,
+    /// Drags out a rectangle from one corner to the other, filled in with shift
+    Rectangle,
+    /// Drags out a circle from its center, filled in with shift
+    Circle,
+}
+
+impl Tool {
+    const ALL: [Tool; 9] = [Tool::Pen, Tool::Eraser, Tool::Select, Tool::Stamp, Tool::Fill, Tool::Pan, Tool::Line,
+                            Tool::Rectangle, Tool::Circle];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Tool::Pen       => "Pen",
+            Tool::Eraser    => "Eraser",
+            Tool::Select    => "Select",
+            Tool::Stamp     => "Stamp",
+            Tool::Fill      => "Fill",
+            Tool::Pan       => "Pan",
+            Tool::Line      => "Line",
+            Tool::Rectangle => "Rect",
+            Tool::Circle    => "Circle",
+        }
+    }
+
+    /// The brush cells of the shape dragged out from `from` to `to`, for the tools that draw one
+    fn shape(&self, from: Coord, to: Coord, filled: bool) -> Option<Vec<Coord>> {
+        match self {
+            Tool::Line      => Some(line_cells(from, to)),
+            Tool::Rectangle => Some(rectangle_cells(from, to, filled)),
+            Tool::Circle    => {
+                let radius = ((from.row.abs_diff(to.row).pow(2) + from.col.abs_diff(to.col).pow(2)) as f32).sqrt().round();
+                Some(circle_cells(from, radius as usize, filled))
+            }
+            _               => None,
+        }
+    }
+
+    /// Whether using it changes the cells
+    fn edits(&self) -> bool { !matches!(self, Tool::Select | Tool::Pan) }
+}
+
+/// The cells the pen and the eraser cover around the one under the mouse, sized with control and the wheel
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Nib {
+    Dot,
+    /// 3×3
+    Square,
+    Circle { radius: usize },
+}
+
+impl Nib {
+    const MAX_RADIUS: usize = 16;
+
+    fn bigger(self) -> Nib {
+        match self {
+            Nib::Dot               => Nib::Square,
+            Nib::Square            => Nib::Circle { radius: 2 },
+            Nib::Circle { radius } => Nib::Circle { radius: (radius + 1).min(Nib::MAX_RADIUS) },
+        }
+    }
+
+    fn smaller(self) -> Nib {
+        match self {
+            Nib::Dot | Nib::Square    => Nib::Dot,
+            Nib::Circle { radius: 2 } => Nib::Square,
+            Nib::Circle { radius }    => Nib::Circle { radius: radius - 1 },
+        }
+    }
+
+    /// Those around `center`, leaving out any past the universe's top left edges
+    fn cells(self, center: Coord) -> impl Iterator<Item = Coord> {
+        let radius = match self {
+            Nib::Dot               => 0,
+            Nib::Square            => 1,
+            Nib::Circle { radius } => radius as isize,
+        };
+        let round = matches!(self, Nib::Circle { .. });
+        (-radius..=radius).flat_map(move |dy| (-radius..=radius).map(move |dx| (dy, dx)))
+            .filter(move |(dy, dx)| !round || dy * dy + dx * dx <= radius * radius + radius)
+            .filter_map(move |(dy, dx)| Some(Coord::new(center.row.checked_add_signed(dy)?, center.col.checked_add_signed(dx)?)))
+    }
+}
+
+impl std::fmt::Display for Nib {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Nib::Dot               => write!(f, "1×1"),
+            Nib::Square            => write!(f, "3×3"),
+            Nib::Circle { radius } => write!(f, "Circle r{radius}"),
+        }
+    }
+}
+
+/// What cells are colored by on top of the palette, each of them off if `None`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+struct Effects {
+    ages: Option<AgeGradient>,
+    /// In generations
+    trails: Option<u32>,
+    /// How far along the last generation's births and deaths are animated, from 0 to 1
+    transition: Option<f32>,
+    /// Over everything else
+    heat: Option<Heatmap>,
+}
+
+/// Colors live cells go through as they age, from `young` when just born to `old` once `span` generations old
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct AgeGradient {
+    young: Color,
+    old: Color,
+    span: u32,
+}
+
+impl AgeGradient {
+    /// How far along from `young` to `old` a cell `age` generations old is, from 0 to 1
+    fn progress(&self, age: u32) -> f32 { (age.saturating_sub(1) as f32 / self.span.max(1) as f32).min(1.0) }
+
+    fn color(&self, age: u32) -> Color {
+        let t = self.progress(age);
+        let (young, old) = (self.young.to_vec(), self.old.to_vec());
+        Color::from_vec(young + (old - young) * t)
+    }
+}
+
+/// Cells colored by how much they've been changing (see `Universe::activity_at`) rather than by what they are: glowing
+/// while busy and fading back as they settle, into `ash` if they're still alive
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Heatmap {
+    ash: Color,
+    glow: Color,
+}
+
+impl Heatmap {
+    /// How hot a cell with that `activity` looks, from 0 to 1: already most of the way for a cell that only just
+    /// changed
+    fn heat(activity: f32) -> f32 { 1.0 - (-activity).exp() }
+
+    /// Over `base`, what the cell would be with nothing going on
+    fn color(&self, base: Color, activity: f32) -> Color {
+        // Into transparent cells the color stays, and only the alpha goes
+        let base = if base.a == 0.0 { Color { a: 0.0, ..self.glow } } else { base };
+        Color::from_vec(base.to_vec() + (self.glow.to_vec() - base.to_vec()) * Heatmap::heat(activity))
+    }
+}
+
+/// Lines between cells, bolder every 10 cells, the way they look at some zoom
+#[derive(Clone, Copy, Debug)]
+struct GridLines {
+    color: Color,
+    /// Of the thin ones, in world units
+    thickness: f32,
+    /// Opacity of the thin and bold ones
+    thin: f32,
+    bold: f32,
+}
+
+impl GridLines {
+    /// As thick as `thickness` pixels whatever the `zoom`, and fading out as the lines get closer together on
+    /// screen, from `fade.1` pixels apart down to `fade.0`
+    fn new(thickness: f32, color: Color, grid_spacing: usize, zoom: f32, fade: (f32, f32)) -> Self {
+        let opacity = |every: f32| ((grid_spacing as f32 * every * zoom - fade.0) / (fade.1 - fade.0)).clamp(0.0, 1.0);
+        Self { color, thickness: thickness / zoom, thin: opacity(1.0), bold: opacity(10.0) }
+    }
+}
+
+/// The universe as textures with a pixel per cell, each drawn scaled up in a single call. A fragment shader colors
+/// the cells and draws the grid over them, so the pixels hold what it needs to know of each cell (see
+/// `cell_data`) rather than its color. The universe is split into square chunks and only those on screen are
+/// written, so huge universes cost no more to draw than what's in view. Those last drawn the longest ago are let go
+/// of past `CACHED`, so the ones scrolled back to are mostly still around. Chunks only write the tiles of cells
+/// that changed since they were last written (see `Universe::changed_since`), and only upload what that changed.
+/// Ages, trails and heat change all over every generation, so with them on every generation goes in whole.
+struct CellTexture {
+    /// In cells, rows along the screen's x axis
+    size: (usize, usize),
+    /// A row of chunks after another, `None` until drawn or after being let go of
+    chunks: Vec<Option<Chunk>>,
+    /// Times drawn, to tell which chunks were drawn the longest ago
+    frames: u64,
+    /// Or `None` if the shader couldn't be compiled, and the pixels are colored by `cell_color` instead
+    material: Option<Material>,
+    /// Whatever else the pixels were written with, short of the shader's uniforms
+    written_with: Option<(Effects, [Color; 4], [Color; 4])>,
+}
+
+/// Part of a `CellTexture`, starting at `top_left`
+struct Chunk {
+    top_left: Coord,
+    image: Image,
+    texture: Texture2D,
+    /// Of the universe, when it was last written, or `None` to write it all
+    written: Option<(Revision, u64)>,
+    /// The `CellTexture`'s frame it was last drawn on
+    drawn: u64,
+}
+
+impl CellTexture {
+    /// Side of a chunk, in cells
+    const CHUNK: usize = 256;
+    /// Most chunks kept at once, unless more than that are on screen
+    const CACHED: usize = 64;
+    const UNIFORMS: [(&'static str, UniformType); 24] = [
+        ("Size", UniformType::Float2), ("Offset", UniformType::Float2), ("Alive", UniformType::Float4),
+        ("Dying", UniformType::Float4), ("Conductor", UniformType::Float4), ("Dead", UniformType::Float4),
+        ("Paint0", UniformType::Float4), ("Paint1", UniformType::Float4), ("Paint2", UniformType::Float4),
+        ("Paint3", UniformType::Float4), ("Young", UniformType::Float4), ("Old", UniformType::Float4),
+        ("Ages", UniformType::Float1), ("Coloring", UniformType::Float1), ("Cyclic", UniformType::Float1),
+        ("States", UniformType::Float1), ("Trails", UniformType::Float1), ("Transition", UniformType::Float1),
+        ("Grid", UniformType::Float4), ("GridOpacity", UniformType::Float2), ("GridThickness", UniformType::Float1),
+        ("Heat", UniformType::Float1), ("Ash", UniformType::Float4), ("Glow", UniformType::Float4),
+    ];
+
+    fn new() -> Self {
+        let params = MaterialParams {
+            uniforms: CellTexture::UNIFORMS.iter().map(|&(name, kind)| (name.to_owned(), kind)).collect(),
+            pipeline_params: PipelineParams {
+                color_blend: Some(BlendState::new(Equation::Add, BlendFactor::Value(BlendValue::SourceAlpha),
+                                                  BlendFactor::OneMinusValue(BlendValue::SourceAlpha))),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let material = match load_material(ShaderSource::Glsl { vertex: CELL_VERTEX_SHADER, fragment: CELL_FRAGMENT_SHADER }, params) {
+            Ok(material) => Some(material),
+            Err(e)       => {
+                eprintln!("Couldn't compile the cell shader, coloring cells without it: {e}");
+                None
+            }
+        };
+        Self { size: (0, 0), chunks: Vec::new(), frames: 0, material, written_with: None }
+    }
+
+    /// Whether the shader draws the cells, and the `grid` with them
+    fn is_shaded(&self) -> bool { self.material.is_some() }
+
+    /// Like `draw_universe`, but without hex grids' shifted columns
+    #[allow(clippy::too_many_arguments)]
+    fn draw(&mut self, universe: &Universe, grid_spacing: usize, palette: [Color; 4], paints: [Color; 4], effects: Effects,
+            grid: Option<GridLines>, visible: Rect) {
+        let (height, width) = (universe.get_height(), universe.get_width());
+        if height == 0 || width == 0 { return; }
+        let across = height.div_ceil(CellTexture::CHUNK);
+        if self.size != (height, width) {
+            self.size = (height, width);
+            self.chunks = (0..across * width.div_ceil(CellTexture::CHUNK)).map(|_| None).collect();
+        }
+        // Rows run along the screen's x axis
+        let chunks = |from: f32, to: f32, cells: usize| {
+            let cell = |at: f32| ((at / grid_spacing as f32).max(0.0) as usize).min(cells);
+            cell(from) / CellTexture::CHUNK..(cell(to) + 1).min(cells).div_ceil(CellTexture::CHUNK)
+        };
+        let (rows, cols) = (chunks(visible.left(), visible.right(), height), chunks(visible.top(), visible.bottom(), width));
+        let on_screen = |i: usize| rows.contains(&(i % across)) && cols.contains(&(i / across));
+        // The shader does the coloring and animating, and the pixels only tell it about the cells, which ages, trails
+        // and heat are the only effects to change
+        let written_with = match self.material {
+            Some(_) => (Effects { transition: None, ..effects }, [BLANK; 4], [BLANK; 4]),
+            None    => (effects, palette, paints),
+        };
+        if self.written_with != Some(written_with) {
+            for chunk in self.chunks.iter_mut().flatten() { chunk.written = None; }
+            self.written_with = Some(written_with);
+        }
+
+        self.frames += 1;
+        let mut drawn = Vec::new();
+        for (i, slot) in self.chunks.iter_mut().enumerate().filter(|&(i, _)| on_screen(i)) {
+            let top_left = Coord::new(i % across * CellTexture::CHUNK, i / across * CellTexture::CHUNK);
+            let chunk = slot.get_or_insert_with(|| Chunk::blank(top_left, (height - top_left.row).min(CellTexture::CHUNK),
+                                                                (width - top_left.col).min(CellTexture::CHUNK)));
+            chunk.write(universe, self.material.is_some(), palette, paints, effects);
+            chunk.drawn = self.frames;
+            drawn.push(i);
+        }
+        // Off screen chunks are kept for when they're back, but only so many
+        let mut kept: Vec<_> = self.chunks.iter().enumerate().filter_map(|(i, chunk)| Some((chunk.as_ref()?.drawn, i))).collect();
+        if kept.len() > CellTexture::CACHED {
+            kept.sort_unstable();
+            let stale = kept.len() - CellTexture::CACHED.max(drawn.len());
+            for &(_, i) in &kept[..stale] { self.chunks[i] = None; }
+        }
+
+        let extent = |chunk: &Chunk| (vec2((chunk.top_left.row * grid_spacing) as f32, (chunk.top_left.col * grid_spacing) as f32),
+                                      vec2(chunk.image.width as f32, chunk.image.height as f32));
+        let Some(material) = &self.material else {
+            for chunk in drawn.iter().filter_map(|&i| self.chunks[i].as_ref()) {
+                let (at, size) = extent(chunk);
+                let params = DrawTextureParams { dest_size: Some(size * grid_spacing as f32), ..Default::default() };
+                draw_texture_ex(&chunk.texture, at.x, at.y, WHITE, params);
+            }
+            let universe_extent = Rect::new(0.0, 0.0, (height * grid_spacing) as f32, (width * grid_spacing) as f32);
+            if let Some(area) = visible.intersect(universe_extent) { draw_births(universe, grid_spacing, palette, paints, effects, area); }
+            return;
+        };
+        let [alive, dying, conductor, dead] = palette.map(|color| color.to_vec());
+        let rule = universe.get_rule();
+        let ages = effects.ages.unwrap_or(AgeGradient { young: WHITE, old: WHITE, span: 1 });
+        let grid = grid.unwrap_or(GridLines { color: BLANK, thickness: 0.0, thin: 0.0, bold: 0.0 });
+        let heat = effects.heat.unwrap_or(Heatmap { ash: WHITE, glow: WHITE });
+        let flag = |on: bool| if on { 1.0_f32 } else { 0.0 };
+        for (name, color) in [("Alive", alive), ("Dying", dying), ("Conductor", conductor), ("Dead", dead),
+                              ("Young", ages.young.to_vec()), ("Old", ages.old.to_vec()), ("Grid", grid.color.to_vec()),
+                              ("Ash", heat.ash.to_vec()), ("Glow", heat.glow.to_vec())] {
+            material.set_uniform(name, color);
+        }
+        for (i, paint) in paints.iter().enumerate() { material.set_uniform(&format!("Paint{i}"), paint.to_vec()); }
+        material.set_uniform("Ages", flag(effects.ages.is_some()));
+        material.set_uniform("Heat", flag(effects.heat.is_some()));
+        material.set_uniform("Coloring", flag(universe.get_coloring().is_some()));
+        material.set_uniform("Cyclic", flag(rule.is_cyclic()));
+        material.set_uniform("States", universe.states() as f32);
+        material.set_uniform("Trails", effects.trails.unwrap_or(0) as f32);
+        material.set_uniform("Transition", effects.transition.unwrap_or(1.0));
+        material.set_uniform("GridOpacity", vec2(grid.thin, grid.bold));
+        // In cells
+        material.set_uniform("GridThickness", grid.thickness / grid_spacing as f32);
+        gl_use_material(material);
+        // Each chunk is a texture of its own, so a draw call of its own with the uniforms as they were set for it
+        for chunk in drawn.iter().filter_map(|&i| self.chunks[i].as_ref()) {
+            let (at, size) = extent(chunk);
+            material.set_uniform("Size", size);
+            material.set_uniform("Offset", vec2(chunk.top_left.row as f32, chunk.top_left.col as f32));
+            let params = DrawTextureParams { dest_size: Some(size * grid_spacing as f32), ..Default::default() };
+            draw_texture_ex(&chunk.texture, at.x, at.y, WHITE, params);
+        }
+        gl_use_default_material();
+    }
+}
+
+impl Chunk {
+    fn blank(top_left: Coord, height: usize, width: usize) -> Self {
+        // Rows run along the screen's x axis
+        let image = Image::gen_image_color(height as u16, width as u16, BLANK);
+        let texture = Texture2D::from_image(&image);
+        texture.set_filter(FilterMode::Nearest);
+        Self { top_left, image, texture, written: None, drawn: 0 }
+    }
+
+    /// Brings the pixels of the tiles that changed up to date with the universe: what the shader needs to know if
+    /// `shaded`, or else the cells' colors
+    fn write(&mut self, universe: &Universe, shaded: bool, palette: [Color; 4], paints: [Color; 4], effects: Effects) {
+        let (height, width) = (self.image.width as usize, self.image.height as usize);
+        let every_generation = effects.ages.is_some() || effects.trails.is_some() || effects.heat.is_some();
+        let since = self.written
+            .filter(|&(_, generation)| !every_generation || generation == universe.get_generation())
+            .map(|(revision, _)| revision);
+        // Of the pixels that changed, as the image's (x, y) corners
+        let mut changed: Option<((usize, usize), (usize, usize))> = None;
+        for (from, size) in universe.changed_since(since, self.top_left, height, width) {
+            for c in (from.row..from.row + size.row).flat_map(|row| (from.col..from.col + size.col).map(move |col| Coord::new(row, col))) {
+                let data = match shaded {
+                    true  => cell_data(universe, c, effects),
+                    false => cell_color(universe, c, palette, paints, effects).into(),
+                };
+                let (x, y) = (c.row - self.top_left.row, c.col - self.top_left.col);
+                let i = (y * height + x) * 4;
+                let pixel = &mut self.image.bytes[i..i + 4];
+                if *pixel != data {
+                    pixel.copy_from_slice(&data);
+                    changed = Some(changed.map_or(((x, y), (x, y)), |(from, to)| ((from.0.min(x), from.1.min(y)), (to.0.max(x), to.1.max(y)))));
+                }
+            }
+        }
+        self.written = Some((universe.revision(), universe.get_generation()));
+
+        // Only what changed goes up to the GPU
+        let Some(((x0, y0), (x1, y1))) = changed else { return };
+        let (w, h) = (x1 - x0 + 1, y1 - y0 + 1);
+        if (w, h) == (height, width) { return self.texture.update(&self.image); }
+        let bytes = (y0..=y1).flat_map(|y| &self.image.bytes[(y * height + x0) * 4..(y * height + x1 + 1) * 4]).copied().collect();
+        let part = Image { bytes, width: w as u16, height: h as u16 };
+        self.texture.update_part(&part, x0 as i32, y0 as i32, w as i32, h as i32);
+    }
+}
+
+/// What the cell shader needs to know of a cell, as a pixel: its state (0 dead, 1 alive, 2 a conductor and 2 + k
+/// dying k steps, or the rule's own under cyclic rules), how hot it is on a heatmap, or how far along `ages` it is,
+/// or else its color, how many generations ago it died if there are `trails` and whether it was alive a generation
+/// ago
+fn cell_data(universe: &Universe, c: Coord, effects: Effects) -> [u8; 4] {
+    let cell = universe[c];
+    let state = match cell {
+        _ if universe.get_rule().is_cyclic() => cell.state(universe.conductor_state()).min(255) as u8,
+        Cell::Dead                           => 0,
+        Cell::Alive                          => 1,
+        Cell::Conductor                      => 2,
+        Cell::Dying(k)                       => k.saturating_add(2),
+    };
+    let shade = match (effects.heat, effects.ages) {
+        (Some(_), _)    => (Heatmap::heat(universe.activity_at(c)) * 255.0).round() as u8,
+        (_, Some(ages)) => (ages.progress(universe.age_at(c)) * 255.0).round() as u8,
+        (_, None)       => universe.color_at(c),
+    };
+    let since_death = effects.trails.map_or(0, |_| universe.since_death(c).min(255) as u8);
+    [state, shade, since_death, if universe.previous_at(c) == Cell::Alive { 255 } else { 0 }]
+}
+
+const CELL_VERTEX_SHADER: &str = r#"#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+
+varying highp vec2 uv;
+
+uniform mat4 Model;
+uniform mat4 Projection;
+
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    uv = texcoord;
+}
+"#;
+
+/// `cell_color` and `draw_grid`, for the pixels of `CellTexture`
+const CELL_FRAGMENT_SHADER: &str = r#"#version 100
+// Big universes need more than a few bits to tell their cells apart
+#ifdef GL_FRAGMENT_PRECISION_HIGH
+precision highp float;
+#else
+precision mediump float;
+#endif
+
+varying vec2 uv;
+
+uniform sampler2D Texture;
+// In cells, rows along x: of the chunk, and where it starts in the universe
+uniform vec2 Size;
+uniform vec2 Offset;
+uniform vec4 Alive;
+uniform vec4 Dying;
+uniform vec4 Conductor;
+uniform vec4 Dead;
+uniform vec4 Paint0;
+uniform vec4 Paint1;
+uniform vec4 Paint2;
+uniform vec4 Paint3;
+uniform vec4 Young;
+uniform vec4 Old;
+// Flags, on at 1
+uniform float Ages;
+uniform float Coloring;
+uniform float Cyclic;
+uniform float States;
+// In generations, 0 if off
+uniform float Trails;
+// 1 once done
+uniform float Transition;
+uniform vec4 Grid;
+// Of the thin and bold lines
+uniform vec2 GridOpacity;
+// Of the thin lines, in cells, 0 if off
+uniform float GridThickness;
+// A flag, and the colors of cells that are alive but still and of the ones changing the most
+uniform float Heat;
+uniform vec4 Ash;
+uniform vec4 Glow;
+
+vec3 hsl(float h, float s, float l) {
+    vec3 rgb = clamp(abs(mod(h * 6.0 + vec3(0.0, 4.0, 2.0), 6.0) - 3.0) - 1.0, 0.0, 1.0);
+    return l + s * (rgb - 0.5) * (1.0 - abs(2.0 * l - 1.0));
+}
+
+// `top` blended over `bottom`, neither premultiplied
+vec4 over(vec4 top, vec4 bottom) {
+    float a = top.a + bottom.a * (1.0 - top.a);
+    if (a == 0.0) { return vec4(0.0); }
+    return vec4((top.rgb * top.a + bottom.rgb * bottom.a * (1.0 - top.a)) / a, a);
+}
+
+// Opacity of the grid line closest to `p`, along one axis in cells, if it's close enough to be on it
+float gridLine(float p) {
+    float nearest = floor(p + 0.5);
+    bool bold = mod(nearest, 10.0) < 0.5;
+    float halfWidth = bold ? GridThickness : GridThickness / 2.0;
+    if (abs(p - nearest) >= halfWidth) { return 0.0; }
+    return bold ? GridOpacity.y : GridOpacity.x;
+}
+
+void main() {
+    vec2 local = uv * Size;
+    vec2 cell = min(floor(local), Size - 1.0);
+    // Where in the universe, for the grid's bold lines to line up across chunks
+    vec2 p = local + Offset;
+    // State, shade (heat, age or color), generations since death and whether it was alive
+    vec4 data = floor(texture2D(Texture, (cell + 0.5) / Size) * 255.0 + 0.5);
+    float state = data.r;
+
+    vec4 alive = Alive;
+    if (Ages > 0.5) {
+        alive = mix(Young, Old, data.g / 255.0);
+    } else if (Coloring > 0.5) {
+        alive = data.g < 0.5 ? Paint0 : data.g < 1.5 ? Paint1 : data.g < 2.5 ? Paint2 : Paint3;
+    }
+
+    vec4 color = Dead;
+    if (Cyclic > 0.5) {
+        color = vec4(hsl(state / States, 0.6, 0.7), 1.0);
+    } else if (state == 1.0) {
+        color = alive;
+    } else if (state == 2.0) {
+        color = Conductor;
+    } else if (state > 2.0) {
+        color = vec4(Dying.rgb, Dying.a * (1.0 - (state - 3.0) / (States - 1.0)));
+    } else if (data.b > 0.0 && data.b <= Trails) {
+        color = vec4(alive.rgb, alive.a * 0.5 * (1.0 - data.b / (Trails + 1.0)));
+    }
 
-        let cell_color = 
-            if universe.is_alive(c) { alive_color }
-        else { dead_color };
+    // Cells just born grow from their middle, and the ones that just died fade into what they are now
+    if (Transition < 1.0 && Cyclic < 0.5) {
+        bool wasAlive = data.a > 127.0;
+        bool isAlive = state == 1.0;
+        if (isAlive && !wasAlive) {
+            vec2 offset = abs(fract(p) - 0.5);
+            color = max(offset.x, offset.y) < Transition / 2.0 ? alive : Dead;
+        } else if (wasAlive && !isAlive) {
+            vec4 to = color.a == 0.0 ? vec4(alive.rgb, 0.0) : color;
+            color = mix(alive, to, Transition);
+        }
+    }
+
+    // How hot cells are, in their shade, stands in for all of the above
+    if (Heat > 0.5) {
+        vec4 base = state == 0.0 ? Dead : Ash;
+        if (base.a == 0.0) { base = vec4(Glow.rgb, 0.0); }
+        color = mix(base, Glow, data.g / 255.0);
+    }
+
+    if (GridThickness > 0.0) {
+        float line = max(gridLine(p.x), gridLine(p.y));
+        color = over(vec4(Grid.rgb, Grid.a * line), color);
+    }
+    gl_FragColor = color;
+}
+"#;
+
+/// The whole universe shrunk down into the top right corner, `size` pixels on its longest side, with a pixel for
+/// each block of cells that has any alive. Only the blocks where the universe changed get looked at again.
+struct Minimap {
+    size: f32,
+    image: Image,
+    texture: Texture2D,
+    /// Since it was last redrawn
+    frames: u32,
+    /// Of the universe when it was last redrawn, and the color it was drawn in
+    drawn: Option<(Revision, Color)>,
+}
+
+impl Minimap {
+    fn new(size: f32) -> Self {
+        let image = Image::gen_image_color(1, 1, BLANK);
+        let texture = Texture2D::from_image(&image);
+        Self { size, image, texture, frames: u32::MAX, drawn: None }
+    }
+
+    /// Where on screen it goes, the universe's rows across
+    fn area(&self, universe: &Universe) -> Rect {
+        let scale = self.size / universe.get_height().max(universe.get_width()).max(1) as f32;
+        let (w, h) = (universe.get_height() as f32 * scale, universe.get_width() as f32 * scale);
+        Rect::new(screen_width() - w - 10.0, 10.0, w, h)
+    }
+
+    /// The point of the world `screen` is over, if it's on the minimap
+    fn world_at(&self, universe: &Universe, screen: Vec2, grid_spacing: usize) -> Vec2 {
+        let area = self.area(universe);
+        let extent = vec2((universe.get_height() * grid_spacing) as f32, (universe.get_width() * grid_spacing) as f32);
+        (screen - area.point()) / area.size() * extent
+    }
+
+    /// Redraws it once every `every` frames, or right away if the universe changed size
+    fn refresh(&mut self, universe: &Universe, color: Color, every: u32) {
+        let (rows, cols) = (universe.get_height(), universe.get_width());
+        if rows == 0 || cols == 0 { return; }
+        // Cells to a side of the blocks that make up a pixel
+        let block = (rows.max(cols) as f32 / self.size).ceil().max(1.0) as usize;
+        let (width, height) = (rows.div_ceil(block), cols.div_ceil(block));
+        let resized = (self.image.width as usize, self.image.height as usize) != (width, height);
+        self.frames = self.frames.saturating_add(1);
+        if self.frames < every && !resized { return; }
+        self.frames = 0;
+
+        if resized { self.image = Image::gen_image_color(width as u16, height as u16, BLANK); }
+        let since = self.drawn.filter(|&(_, drawn)| !resized && drawn == color).map(|(revision, _)| revision);
+        self.drawn = Some((universe.revision(), color));
+        // Blocks with any cell in a tile that changed, by their (x, y) on the image
+        let mut blocks = vec![false; width * height];
+        for (from, size) in universe.changed_since(since, Coord::new(0, 0), rows, cols) {
+            for y in from.col / block..=(from.col + size.col - 1) / block {
+                for x in from.row / block..=(from.row + size.row - 1) / block { blocks[y * width + x] = true; }
+            }
+        }
+        if !blocks.contains(&true) { return; }
+
+        let color: [u8; 4] = color.into();
+        for (i, _) in blocks.iter().enumerate().filter(|(_, &changed)| changed) {
+            let (x, y) = (i % width, i / width);
+            let (block_rows, block_cols) = (x * block..((x + 1) * block).min(rows), y * block..((y + 1) * block).min(cols));
+            let any = block_rows.flat_map(|row| block_cols.clone().map(move |col| Coord::new(row, col))).any(|c| universe[c] != Cell::Dead);
+            self.image.bytes[i * 4..i * 4 + 4].copy_from_slice(if any { &color } else { &[0; 4] });
+        }
+        match resized {
+            true  => {
+                self.texture = Texture2D::from_image(&self.image);
+                self.texture.set_filter(FilterMode::Nearest);
+            }
+            false => self.texture.update(&self.image),
+        }
+    }
+
+    /// With the part of the universe on view outlined in `color`
+    fn draw(&self, universe: &Universe, view: &View, color: Color, grid_spacing: usize) {
+        let area = self.area(universe);
+        draw_rectangle(area.x, area.y, area.w, area.h, Color::from_rgba(0, 0, 0, 200));
+        draw_texture_ex(&self.texture, area.x, area.y, WHITE, DrawTextureParams { dest_size: Some(area.size()), ..Default::default() });
+
+        let extent = vec2((universe.get_height() * grid_spacing) as f32, (universe.get_width() * grid_spacing) as f32);
+        if let Some(seen) = view.visible().intersect(Rect::new(0.0, 0.0, extent.x, extent.y)) {
+            let on_map = |world: Vec2| area.point() + world / extent * area.size();
+            let (from, to) = (on_map(seen.point()), on_map(seen.point() + seen.size()));
+            draw_rectangle_lines(from.x, from.y, to.x - from.x, to.y - from.y, 2.0, color);
+        }
+    }
+}
+
+/// Everything a key does, other than Ctrl and Shift changing what some of the others do. The keys of the ones taken
+/// with Ctrl or Shift still need it held down, whatever they're bound to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Action {
+    Pause,
+    /// While paused, ten generations with Shift
+    Step,
+    StepBack,
+    Faster,
+    Slower,
+    Reset,
+    Invert,
+    Soup,
+    Noise,
+    /// With Ctrl, like the rest down to `Fill`
+    Undo,
+    Redo,
+    SaveSession,
+    LoadSession,
+    Cut,
+    Copy,
+    Paste,
+    Fill,
+    /// What's selected
+    Clear,
+    /// The selection or the pattern in hand
+    Turn,
+    /// With Shift, like the moves and `SaveSvg` and `RecordVideo`
+    Flip,
+    Cancel,
+    MoveLeft,
+    MoveRight,
+    MoveUp,
+    MoveDown,
+    Home,
+    /// Held down
+    Inspect,
+    Population,
+    Grid,
+    Ages,
+    Trails,
+    Animations,
+    Heatmap,
+    Hud,
+    Settings,
+    Fullscreen,
+    Screenshot,
+    Layers,
+    LayerUp,
+    LayerDown,
+    Continuous,
+    Hex,
+    Theme,
+    Wrap,
+    Rule,
+    Symmetry,
+    Brush,
+    AddAnt,
+    AntsOnly,
+    Colors,
+    Paint,
+    BlockRules,
+    Library,
+    Elementary,
+    RuleDown,
+    RuleUp,
+    Zones,
+    Explorer,
+    NextCandidate,
+    /// Against a copy under another rule, side by side
+    Compare,
+    CompareRule,
+    CompareSwap,
+    SaveRle,
+    SaveSvg,
+    SavePng,
+    RecordGif,
+    RecordVideo,
+}
+
+impl Action {
+    const ALL: [Action; 67] = [
+        Action::Pause, Action::Step, Action::StepBack, Action::Faster, Action::Slower, Action::Reset, Action::Invert,
+        Action::Soup, Action::Noise, Action::Undo, Action::Redo, Action::SaveSession, Action::LoadSession, Action::Cut,
+        Action::Copy, Action::Paste, Action::Fill, Action::Clear, Action::Turn, Action::Flip, Action::Cancel,
+        Action::MoveLeft, Action::MoveRight, Action::MoveUp, Action::MoveDown, Action::Home, Action::Inspect,
+        Action::Population, Action::Grid, Action::Ages, Action::Trails, Action::Animations, Action::Heatmap,
+        Action::Hud, Action::Settings, Action::Fullscreen, Action::Screenshot, Action::Layers, Action::LayerUp,
+        Action::LayerDown, Action::Continuous, Action::Hex, Action::Theme, Action::Wrap, Action::Rule, Action::Symmetry,
+        Action::Brush, Action::AddAnt, Action::AntsOnly, Action::Colors, Action::Paint, Action::BlockRules,
+        Action::Library, Action::Elementary, Action::RuleDown, Action::RuleUp, Action::Zones, Action::Explorer,
+        Action::NextCandidate, Action::Compare, Action::CompareRule, Action::CompareSwap, Action::SaveRle,
+        Action::SaveSvg, Action::SavePng, Action::RecordGif, Action::RecordVideo,
+    ];
+
+    /// Where it is in `ALL`, and so among a `KeyMap`'s keys
+    fn index(&self) -> usize { Action::ALL.iter().position(|action| action == self).expect("every action is in Action::ALL") }
+
+    /// The modifier its keys need held down, if any. Actions only get in each other's way if they need the same.
+    fn modifier(&self) -> Option<&'static str> {
+        match self {
+            Action::Undo | Action::Redo | Action::SaveSession | Action::LoadSession | Action::Cut | Action::Copy
+                | Action::Paste | Action::Fill => Some("Ctrl"),
+            Action::Flip | Action::MoveLeft | Action::MoveRight | Action::MoveUp | Action::MoveDown | Action::SaveSvg
+                | Action::RecordVideo          => Some("Shift"),
+            _                                  => None,
+        }
+    }
+
+    /// As written in `keys.toml`
+    fn name(&self) -> &'static str {
+        match self {
+            Action::Pause         => "pause",
+            Action::Step          => "step",
+            Action::StepBack      => "step_back",
+            Action::Faster        => "faster",
+            Action::Slower        => "slower",
+            Action::Reset         => "reset",
+            Action::Invert        => "invert",
+            Action::Soup          => "soup",
+            Action::Noise         => "noise",
+            Action::Undo          => "undo",
+            Action::Redo          => "redo",
+            Action::SaveSession   => "save_session",
+            Action::LoadSession   => "load_session",
+            Action::Cut           => "cut",
+            Action::Copy          => "copy",
+            Action::Paste         => "paste",
+            Action::Fill          => "fill",
+            Action::Clear         => "clear",
+            Action::Turn          => "turn",
+            Action::Flip          => "flip",
+            Action::Cancel        => "cancel",
+            Action::MoveLeft      => "move_left",
+            Action::MoveRight     => "move_right",
+            Action::MoveUp        => "move_up",
+            Action::MoveDown      => "move_down",
+            Action::Home          => "home",
+            Action::Inspect       => "inspect",
+            Action::Population    => "population",
+            Action::Grid          => "grid",
+            Action::Ages          => "ages",
+            Action::Trails        => "trails",
+            Action::Animations    => "animations",
+            Action::Heatmap       => "heatmap",
+            Action::Hud           => "hud",
+            Action::Settings      => "settings",
+            Action::Fullscreen    => "fullscreen",
+            Action::Screenshot    => "screenshot",
+            Action::Layers        => "layers",
+            Action::LayerUp       => "layer_up",
+            Action::LayerDown     => "layer_down",
+            Action::Continuous    => "continuous",
+            Action::Hex           => "hex",
+            Action::Theme         => "theme",
+            Action::Wrap          => "wrap",
+            Action::Rule          => "rule",
+            Action::Symmetry      => "symmetry",
+            Action::Brush         => "brush",
+            Action::AddAnt        => "add_ant",
+            Action::AntsOnly      => "ants_only",
+            Action::Colors        => "colors",
+            Action::Paint         => "paint",
+            Action::BlockRules    => "block_rules",
+            Action::Library       => "library",
+            Action::Elementary    => "elementary",
+            Action::RuleDown      => "rule_down",
+            Action::RuleUp        => "rule_up",
+            Action::Zones         => "zones",
+            Action::Explorer      => "explorer",
+            Action::NextCandidate => "next_candidate",
+            Action::Compare       => "compare",
+            Action::CompareRule   => "compare_rule",
+            Action::CompareSwap   => "compare_swap",
+            Action::SaveRle       => "save_rle",
+            Action::SaveSvg       => "save_svg",
+            Action::SavePng       => "save_png",
+            Action::RecordGif     => "record_gif",
+            Action::RecordVideo   => "record_video",
+        }
+    }
+
+    fn default_keys(&self) -> &'static [KeyCode] {
+        match self {
+            Action::Pause         => &[KeyCode::Space],
+            Action::Step          => &[KeyCode::Period],
+            Action::StepBack      => &[KeyCode::Left],
+            Action::Faster        => &[KeyCode::Equal, KeyCode::KpAdd],
+            Action::Slower        => &[KeyCode::Minus, KeyCode::KpSubtract],
+            Action::Reset         => &[KeyCode::R],
+            Action::Invert        => &[KeyCode::I],
+            Action::Soup          => &[KeyCode::S],
+            Action::Noise         => &[KeyCode::N],
+            Action::Undo          => &[KeyCode::Z],
+            Action::Redo          => &[KeyCode::Y],
+            Action::SaveSession   => &[KeyCode::S],
+            Action::LoadSession   => &[KeyCode::L],
+            Action::Cut           => &[KeyCode::X],
+            Action::Copy          => &[KeyCode::C],
+            Action::Paste         => &[KeyCode::V],
+            Action::Fill          => &[KeyCode::F],
+            Action::Clear         => &[KeyCode::Delete, KeyCode::Backspace],
+            Action::Turn          => &[KeyCode::Comma],
+            Action::Flip          => &[KeyCode::Comma],
+            Action::Cancel        => &[KeyCode::Escape],
+            Action::MoveLeft      => &[KeyCode::Left],
+            Action::MoveRight     => &[KeyCode::Right],
+            Action::MoveUp        => &[KeyCode::Up],
+            Action::MoveDown      => &[KeyCode::Down],
+            Action::Home          => &[KeyCode::Key0],
+            Action::Inspect       => &[KeyCode::LeftAlt, KeyCode::RightAlt],
+            Action::Population    => &[KeyCode::P],
+            Action::Grid          => &[KeyCode::Q],
+            Action::Ages          => &[KeyCode::J],
+            Action::Trails        => &[KeyCode::Z],
+            Action::Animations    => &[KeyCode::D],
+            Action::Heatmap       => &[KeyCode::U],
+            Action::Hud           => &[KeyCode::F1],
+            Action::Settings      => &[KeyCode::F2],
+            Action::Fullscreen    => &[KeyCode::F11],
+            Action::Screenshot    => &[KeyCode::F12],
+            Action::Layers        => &[KeyCode::Key3],
+            Action::LayerUp       => &[KeyCode::PageUp],
+            Action::LayerDown     => &[KeyCode::PageDown],
+            Action::Continuous    => &[KeyCode::Key4],
+            Action::Hex           => &[KeyCode::H],
+            Action::Theme         => &[KeyCode::Key5],
+            Action::Wrap          => &[KeyCode::W],
+            Action::Rule          => &[KeyCode::L],
+            Action::Symmetry      => &[KeyCode::M],
+            Action::Brush         => &[KeyCode::E],
+            Action::AddAnt        => &[KeyCode::A],
+            Action::AntsOnly      => &[KeyCode::K],
+            Action::Colors        => &[KeyCode::C],
+            Action::Paint         => &[KeyCode::V],
+            Action::BlockRules    => &[KeyCode::B],
+            Action::Library       => &[KeyCode::T],
+            Action::Elementary    => &[KeyCode::Key1],
+            Action::RuleDown      => &[KeyCode::LeftBracket],
+            Action::RuleUp        => &[KeyCode::RightBracket],
+            Action::Zones         => &[KeyCode::Key2],
+            Action::Explorer      => &[KeyCode::X],
+            Action::NextCandidate => &[KeyCode::Tab],
+            Action::Compare       => &[KeyCode::Key6],
+            Action::CompareRule   => &[KeyCode::Key7],
+            Action::CompareSwap   => &[KeyCode::Key8],
+            Action::SaveRle       => &[KeyCode::O],
+            Action::SaveSvg       => &[KeyCode::O],
+            Action::SavePng       => &[KeyCode::F],
+            Action::RecordGif     => &[KeyCode::G],
+            Action::RecordVideo   => &[KeyCode::G],
+        }
+    }
+}
+
+/// Which keys do each `Action`: the defaults, but for whatever `keys.toml` binds instead
+struct KeyMap {
+    /// In the order of `Action::ALL`
+    keys: Vec<Vec<KeyCode>>,
+}
+
+impl KeyMap {
+    /// Keys that can be bound to something, all but those that are only modifiers
+    const KEYS: [KeyCode; 112] = [
+        KeyCode::A, KeyCode::B, KeyCode::C, KeyCode::D, KeyCode::E, KeyCode::F, KeyCode::G, KeyCode::H, KeyCode::I,
+        KeyCode::J, KeyCode::K, KeyCode::L, KeyCode::M, KeyCode::N, KeyCode::O, KeyCode::P, KeyCode::Q, KeyCode::R,
+        KeyCode::S, KeyCode::T, KeyCode::U, KeyCode::V, KeyCode::W, KeyCode::X, KeyCode::Y, KeyCode::Z,
+        KeyCode::Key0, KeyCode::Key1, KeyCode::Key2, KeyCode::Key3, KeyCode::Key4, KeyCode::Key5, KeyCode::Key6,
+        KeyCode::Key7, KeyCode::Key8, KeyCode::Key9,
+        KeyCode::Space, KeyCode::Apostrophe, KeyCode::Comma, KeyCode::Minus, KeyCode::Period, KeyCode::Slash,
+        KeyCode::Semicolon, KeyCode::Equal, KeyCode::LeftBracket, KeyCode::Backslash, KeyCode::RightBracket,
+        KeyCode::GraveAccent, KeyCode::Escape, KeyCode::Enter, KeyCode::Tab, KeyCode::Backspace, KeyCode::Insert,
+        KeyCode::Delete, KeyCode::Right, KeyCode::Left, KeyCode::Down, KeyCode::Up, KeyCode::PageUp, KeyCode::PageDown,
+        KeyCode::Home, KeyCode::End, KeyCode::CapsLock, KeyCode::ScrollLock, KeyCode::NumLock, KeyCode::PrintScreen,
+        KeyCode::Pause, KeyCode::Menu,
+        KeyCode::F1, KeyCode::F2, KeyCode::F3, KeyCode::F4, KeyCode::F5, KeyCode::F6, KeyCode::F7, KeyCode::F8,
+        KeyCode::F9, KeyCode::F10, KeyCode::F11, KeyCode::F12, KeyCode::F13, KeyCode::F14, KeyCode::F15, KeyCode::F16,
+        KeyCode::F17, KeyCode::F18, KeyCode::F19, KeyCode::F20, KeyCode::F21, KeyCode::F22, KeyCode::F23, KeyCode::F24,
+        KeyCode::F25,
+        KeyCode::Kp0, KeyCode::Kp1, KeyCode::Kp2, KeyCode::Kp3, KeyCode::Kp4, KeyCode::Kp5, KeyCode::Kp6, KeyCode::Kp7,
+        KeyCode::Kp8, KeyCode::Kp9, KeyCode::KpDecimal, KeyCode::KpDivide, KeyCode::KpMultiply, KeyCode::KpSubtract,
+        KeyCode::KpAdd, KeyCode::KpEnter, KeyCode::KpEqual, KeyCode::LeftAlt, KeyCode::RightAlt,
+    ];
+
+    fn new() -> Self { Self { keys: Action::ALL.iter().map(|action| action.default_keys().to_vec()).collect() } }
+
+    /// The defaults, rebound by the `[keys]` table of a TOML file such as
+    ///
+    /// ```toml
+    /// [keys]
+    /// pause = "P"
+    /// faster = ["Equal", "KpAdd"]
+    /// library = []
+    /// ```
+    ///
+    /// where actions go by `Action::name` and keys by their `KeyCode` (`Key1` can also be `1`), or by how the
+    /// controls show them. Keys rebound to an action stop doing what they did by default, and binding a key to two
+    /// actions that need the same modifier is an error.
+    fn from_toml(toml: &str) -> Result<Self, String> {
+        let mut map = KeyMap::new();
+        // The actions the file rebinds, by `Action::index`
+        let mut rebound = [false; Action::ALL.len()];
+        let mut in_keys = false;
+        for (i, line) in toml.lines().enumerate() {
+            let invalid = |why: String| format!("line {}: {why}", i + 1);
+            // None of the names have a # in them
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() { continue; }
+            if let Some(table) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                in_keys = table.trim() == "keys";
+                continue;
+            }
+            if !in_keys { continue; }
+
+            let (name, value) = line.split_once('=').ok_or_else(|| invalid("expected action = \"Key\"".to_owned()))?;
+            let name = name.trim();
+            let action = Action::ALL.into_iter().find(|action| action.name() == name)
+                .ok_or_else(|| invalid(format!("there's no {name} action")))?;
+            let value = value.trim();
+            let list = value.strip_prefix('[').and_then(|value| value.strip_suffix(']')).unwrap_or(value);
+            let keys = list.split(',').map(str::trim).filter(|key| !key.is_empty()).map(|key| {
+                let key = key.strip_prefix('"').and_then(|key| key.strip_suffix('"'))
+                    .ok_or_else(|| invalid(format!("{key} should be in quotes")))?;
+                KeyMap::parse_key(key).ok_or_else(|| invalid(format!("there's no {key} key")))
+            }).collect::<Result<Vec<_>, _>>()?;
+            for other in Action::ALL.into_iter().filter(|&other| other != action && other.modifier() == action.modifier()) {
+                let taken = &mut map.keys[other.index()];
+                if let Some(&key) = taken.iter().find(|key| keys.contains(key)).filter(|_| rebound[other.index()]) {
+                    return Err(invalid(format!("{} is bound to {} already", KeyMap::label(key), other.name())));
+                }
+                taken.retain(|key| !keys.contains(key));
+            }
+            map.keys[action.index()] = keys;
+            rebound[action.index()] = true;
+        }
+        Ok(map)
+    }
+
+    fn parse_key(name: &str) -> Option<KeyCode> {
+        KeyMap::KEYS.into_iter().find(|&key| format!("{key:?}").eq_ignore_ascii_case(name) || KeyMap::label(key) == name)
+    }
+
+    /// How the controls show `key`
+    fn label(key: KeyCode) -> String {
+        match key {
+            KeyCode::Apostrophe   => "'".to_owned(),
+            KeyCode::Comma        => ",".to_owned(),
+            KeyCode::Minus        => "-".to_owned(),
+            KeyCode::Period       => ".".to_owned(),
+            KeyCode::Slash        => "/".to_owned(),
+            KeyCode::Semicolon    => ";".to_owned(),
+            KeyCode::Equal        => "=".to_owned(),
+            KeyCode::LeftBracket  => "[".to_owned(),
+            KeyCode::Backslash    => "\\".to_owned(),
+            KeyCode::RightBracket => "]".to_owned(),
+            KeyCode::GraveAccent  => "`".to_owned(),
+            KeyCode::Escape       => "Esc".to_owned(),
+            KeyCode::Delete       => "Del".to_owned(),
+            KeyCode::PageUp       => "PgUp".to_owned(),
+            KeyCode::PageDown     => "PgDn".to_owned(),
+            KeyCode::KpAdd        => "+".to_owned(),
+            KeyCode::LeftAlt | KeyCode::RightAlt
+                                  => "Alt".to_owned(),
+            key                   => {
+                let name = format!("{key:?}");
+                name.strip_prefix("Key").map(str::to_owned).unwrap_or(name)
+            }
+        }
+    }
+
+    fn pressed(&self, action: Action) -> bool { self.keys[action.index()].iter().any(|&key| is_key_pressed(key)) }
+    fn down(&self, action: Action) -> bool    { self.keys[action.index()].iter().any(|&key| is_key_down(key)) }
+
+    /// The first key bound to `action`, for the controls, or `-` if there's none
+    fn key(&self, action: Action) -> String {
+        self.keys[action.index()].first().map_or("-".to_owned(), |&key| KeyMap::label(key))
+    }
+
+    /// The keys of several actions, as in `+/-`
+    fn keys(&self, actions: &[Action]) -> String {
+        actions.iter().map(|&action| self.key(action)).collect::<Vec<_>>().join("/")
+    }
+}
+
+/// Colors the world and the controls are drawn in, switched between at runtime
+#[derive(Clone, Copy, Debug)]
+struct Theme {
+    name: &'static str,
+    background: Color,
+    alive: Color,
+    dead: Color,
+    /// Decaying cells, fading out as they go
+    dying: Color,
+    conductor: Color,
+    /// Of turmites, zones and the recording indicator, and Immigration's second color
+    turmite: Color,
+    /// Newborn and old cells along the age gradient, and QuadLife's last two colors
+    young: Color,
+    old: Color,
+    grid: Color,
+    /// Of the controls' text
+    accent: Color,
+}
+
+impl Theme {
+    const ALL: [Theme; 8] = [
+        // Catppuccin Macchiato
+        Theme { name: "Classic", background: rgba(24, 25, 38, 255), alive: rgba(145, 215, 227, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(245, 169, 127, 255), conductor: rgba(238, 212, 159, 255), turmite: rgba(237, 135, 150, 255),
+                young: rgba(166, 218, 149, 255), old: rgba(198, 160, 246, 255),
+                grid: rgba(138, 173, 244, 255), accent: rgba(198, 160, 246, 200) },
+        Theme { name: "Dark", background: rgba(10, 10, 12, 255), alive: rgba(230, 230, 230, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(245, 169, 127, 255), conductor: rgba(238, 212, 159, 255), turmite: rgba(237, 135, 150, 255),
+                young: rgba(166, 218, 149, 255), old: rgba(198, 160, 246, 255),
+                grid: rgba(64, 64, 72, 255), accent: rgba(200, 200, 210, 200) },
+        Theme { name: "Solarized", background: rgba(0, 43, 54, 255), alive: rgba(181, 137, 0, 255), dead: rgba(7, 54, 66, 255),
+                dying: rgba(245, 169, 127, 255), conductor: rgba(238, 212, 159, 255), turmite: rgba(237, 135, 150, 255),
+                young: rgba(166, 218, 149, 255), old: rgba(198, 160, 246, 255),
+                grid: rgba(88, 110, 117, 255), accent: rgba(147, 161, 161, 220) },
+        Theme { name: "Neon", background: rgba(8, 0, 20, 255), alive: rgba(57, 255, 20, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(245, 169, 127, 255), conductor: rgba(238, 212, 159, 255), turmite: rgba(237, 135, 150, 255),
+                young: rgba(166, 218, 149, 255), old: rgba(198, 160, 246, 255),
+                grid: rgba(255, 0, 200, 160), accent: rgba(0, 240, 255, 220) },
+        // The next three out of Okabe and Ito's colors, which stay apart under each kind of color blindness
+        Theme { name: "Deuteranopia", background: rgba(16, 16, 24, 255), alive: rgba(86, 180, 233, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(230, 159, 0, 255), conductor: rgba(240, 228, 66, 255), turmite: rgba(213, 94, 0, 255),
+                young: rgba(240, 228, 66, 255), old: rgba(0, 114, 178, 255),
+                grid: rgba(96, 96, 112, 255), accent: rgba(240, 228, 66, 220) },
+        Theme { name: "Protanopia", background: rgba(16, 16, 24, 255), alive: rgba(240, 228, 66, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(86, 180, 233, 255), conductor: rgba(230, 230, 230, 255), turmite: rgba(0, 114, 178, 255),
+                young: rgba(230, 159, 0, 255), old: rgba(204, 121, 167, 255),
+                grid: rgba(96, 96, 112, 255), accent: rgba(86, 180, 233, 220) },
+        Theme { name: "Tritanopia", background: rgba(16, 16, 24, 255), alive: rgba(0, 158, 115, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(213, 94, 0, 255), conductor: rgba(230, 230, 230, 255), turmite: rgba(204, 121, 167, 255),
+                young: rgba(230, 230, 230, 255), old: rgba(213, 94, 0, 255),
+                grid: rgba(96, 96, 112, 255), accent: rgba(230, 230, 230, 220) },
+        // Pure colors on black
+        Theme { name: "High contrast", background: rgba(0, 0, 0, 255), alive: rgba(255, 255, 255, 255), dead: rgba(0, 0, 0, 0),
+                dying: rgba(255, 0, 255, 255), conductor: rgba(255, 255, 0, 255), turmite: rgba(255, 0, 0, 255),
+                young: rgba(0, 255, 0, 255), old: rgba(0, 160, 255, 255),
+                grid: rgba(160, 160, 160, 255), accent: rgba(255, 255, 0, 255) },
+    ];
+}
+
+/// `Color::from_rgba`, for constants
+const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+    Color::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, a as f32 / 255.0)
+}
+
+/// What part of the world, where cells are `grid_spacing` pixels wide, is on screen: `zoom` screen pixels to each
+/// of the world's, with the screen's top left corner at `corner`
+#[derive(Clone, Copy, Debug)]
+struct View {
+    zoom: f32,
+    corner: Vec2,
+}
+
+impl View {
+    /// Cells their own size, from the top left corner of the universe
+    const HOME: View = View { zoom: 1.0, corner: Vec2::ZERO };
+    const MIN_ZOOM: f32 = 0.05;
+    const MAX_ZOOM: f32 = 20.0;
+
+    fn world_at(&self, screen: Vec2) -> Vec2 { self.corner + screen / self.zoom }
+    fn visible(&self) -> Rect { self.visible_in(vec2(screen_width(), screen_height())) }
+    /// What's on view in a part of the screen `size` pixels big
+    fn visible_in(&self, size: Vec2) -> Rect { Rect::new(self.corner.x, self.corner.y, size.x / self.zoom, size.y / self.zoom) }
+
+    fn camera(&self) -> Camera2D {
+        let visible = self.visible();
+        // Positive zooms keep y going down the screen
+        Camera2D { target: visible.center(), zoom: vec2(2.0 / visible.w, 2.0 / visible.h), ..Default::default() }
+    }
+
+    /// Like `camera`, but only drawing to `area` of the screen, the view's corner at the area's own
+    fn camera_in(&self, area: Rect) -> Camera2D {
+        let visible = self.visible_in(area.size());
+        // Viewports are in the framebuffer's pixels, from its bottom left corner
+        let dpi = screen_dpi_scale();
+        let viewport = ((area.x * dpi) as i32, ((screen_height() - area.bottom()) * dpi) as i32, (area.w * dpi) as i32, (area.h * dpi) as i32);
+        Camera2D { target: visible.center(), zoom: vec2(2.0 / visible.w, 2.0 / visible.h), viewport: Some(viewport), ..Default::default() }
+    }
+
+    /// Moves the view so `world` is in the middle of the screen
+    fn center_on(&mut self, world: Vec2) { self.corner = world - vec2(screen_width(), screen_height()) / (2.0 * self.zoom); }
 
-        draw_rectangle(x as f32, y as f32, grid_spacing as f32, grid_spacing as f32, cell_color);
+    /// Zooms in by `factor`, or out below 1, keeping the world where it is under `screen`
+    fn zoom_at(&mut self, screen: Vec2, factor: f32) {
+        let world = self.world_at(screen);
+        self.zoom = (self.zoom * factor).clamp(View::MIN_ZOOM, View::MAX_ZOOM);
+        self.corner = world - screen / self.zoom;
     }
 }
 
-fn draw_grid(grid_thickness: f32, grid_color: Color, grid_spacing: usize) {
-    for y in (0..screen_height() as usize).step_by(grid_spacing) {
-        draw_line(0.0, y as f32,
-                  screen_width(), y as f32,
-                  grid_thickness, grid_color);
+/// What's needed of the fingers on the screen from one frame to the next: two of them pan the view and pinch to
+/// zoom it, and a lone one that comes down and goes up again without going far taps
+#[derive(Clone, Debug, Default)]
+struct Fingers {
+    /// The two moving the view, as they were last frame
+    pair: Option<[Touch; 2]>,
+    /// From a second finger coming down until they're all lifted
+    gesturing: bool,
+    /// The lone finger that came down, and where, until it goes too far to be a tap
+    tap: Option<(u64, Vec2)>,
+    /// Of the fingers there last frame
+    last_ids: Vec<u64>,
+    /// Whether the screen has been touched at all, for the touch bar to only show up then
+    used: bool,
+}
+
+impl Fingers {
+    /// How far a finger can go, in pixels, and still tap
+    const TAP_SLOP: f32 = 12.0;
+
+    /// Pans and zooms `view` with two fingers. Returns whether a lone finger came down this frame, and whether one
+    /// tapped.
+    fn update(&mut self, view: &mut View) -> (bool, bool) {
+        let mut touches = touches();
+        touches.sort_by_key(|touch| touch.id);
+        self.used |= !touches.is_empty();
+        let lifted = |touch: &Touch| matches!(touch.phase, TouchPhase::Ended | TouchPhase::Cancelled);
+        let down = touches.iter().filter(|touch| !lifted(touch)).cloned().collect::<Vec<_>>();
+        if down.len() >= 2 { (self.gesturing, self.tap) = (true, None); }
+
+        let (mut touched, mut tapped) = (false, false);
+        if let [touch] = touches.as_slice() {
+            match touch.phase {
+                TouchPhase::Started if !self.gesturing => (self.tap, touched) = (Some((touch.id, touch.position)), true),
+                // Down and up within a frame, on a quick tap, it never got to be Started
+                TouchPhase::Ended if !self.gesturing => {
+                    tapped = self.tap.is_some_and(|(id, _)| id == touch.id) || !self.last_ids.contains(&touch.id);
+                }
+                _ => {}
+            }
+        }
+        if let Some((id, at)) = self.tap {
+            if touches.iter().any(|touch| touch.id == id && touch.position.distance(at) > Fingers::TAP_SLOP) { self.tap = None; }
+        }
+        if tapped { self.tap = None; }
+
+        let pair = <[Touch; 2]>::try_from(down.iter().take(2).cloned().collect::<Vec<_>>()).ok();
+        if let (Some([a, b]), Some([last_a, last_b])) = (&pair, &self.pair) {
+            if (a.id, b.id) == (last_a.id, last_b.id) {
+                let center = (a.position + b.position) / 2.0;
+                view.corner -= (center - (last_a.position + last_b.position) / 2.0) / view.zoom;
+                let spread = last_a.position.distance(last_b.position);
+                if spread > 1.0 { view.zoom_at(center, a.position.distance(b.position) / spread); }
+            }
+        }
+        self.pair = pair;
+        if down.is_empty() { self.gesturing = false; }
+        self.last_ids = touches.iter().map(|touch| touch.id).collect();
+        (touched, tapped)
     }
-    for x in (0..screen_width() as usize).step_by(grid_spacing) {
-        draw_line(x as f32, 0.0,
-                  x as f32, screen_height(),
-                  grid_thickness, grid_color);
+}
+
+/// Gamepad buttons the frontend uses, numbered as Linux's joystick driver does an Xbox-like controller's
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Button {
+    A     = 0,
+    Start = 7,
+}
+
+/// Sticks and triggers, numbered like `Button`s
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Axis {
+    LeftX        = 0,
+    LeftY        = 1,
+    LeftTrigger  = 2,
+    RightTrigger = 5,
+}
+
+/// A controller, read through Linux's joystick device (`/dev/input/js*`), as there's nothing for gamepads in
+/// macroquad yet. There's never one anywhere else.
+struct Gamepad {
+    #[cfg(target_os = "linux")]
+    device: std::fs::File,
+    /// From -1 to 1, as the driver last had them
+    axes: [f32; 8],
+    /// Bit per button, of those pressed since the last poll
+    pressed: u32,
+}
+
+impl Gamepad {
+    /// How far sticks can be off center, out of 1, and still count as let go of
+    const DEAD_ZONE: f32 = 0.15;
+
+    /// The first controller plugged in, if any
+    #[cfg(target_os = "linux")]
+    fn open() -> Option<Self> {
+        use std::os::unix::fs::OpenOptionsExt;
+        // For polling to stop at the last event instead of waiting for the next one
+        let open = |path: String| std::fs::OpenOptions::new().read(true).custom_flags(libc::O_NONBLOCK).open(path).ok();
+        let device = (0..4).find_map(|i| open(format!("/dev/input/js{i}")))?;
+        Some(Self { device, axes: [0.0; 8], pressed: 0 })
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn open() -> Option<Self> { None }
+
+    /// Takes in every event since the last poll, returning whether the controller's still plugged in
+    #[cfg(target_os = "linux")]
+    fn poll(&mut self) -> bool {
+        use std::io::Read;
+        self.pressed = 0;
+        // Each a timestamp, value, type and number; the driver starts with one for every button and axis
+        let mut event = [0; 8];
+        loop {
+            match self.device.read(&mut event) {
+                Ok(8) => {}
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return true,
+                _ => return false,
+            }
+            let value = i16::from_le_bytes([event[4], event[5]]);
+            let (kind, number) = (event[6] & !0x80, event[7] as usize);
+            match kind {
+                // Presses only, and not the ones the driver reports as it starts
+                1 if number < 32 && value != 0 && event[6] & 0x80 == 0 => self.pressed |= 1 << number,
+                2 if number < self.axes.len() => self.axes[number] = (value as f32 / i16::MAX as f32).clamp(-1.0, 1.0),
+                _ => {}
+            }
+        }
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    fn poll(&mut self) -> bool { false }
+
+    /// Sticks from -1 to 1, right and down being positive, and triggers from 0 (let go of) to 1, nothing within
+    /// the dead zone
+    fn axis(&self, axis: Axis) -> f32 {
+        let value = match axis {
+            // They rest all the way at -1
+            Axis::LeftTrigger | Axis::RightTrigger => (self.axes[axis as usize] + 1.0) / 2.0,
+            Axis::LeftX | Axis::LeftY              => self.axes[axis as usize],
+        };
+        if value.abs() < Gamepad::DEAD_ZONE { 0.0 } else { value }
     }
+
+    fn pressed(&self, button: Button) -> bool { self.pressed & 1 << button as u32 != 0 }
 }
 
 fn window_conf() -> Conf {
@@ -110,6 +2842,9 @@ fn window_conf() -> Conf {
         window_resizable: true,
         window_width: 1080,
         window_height: 720,
+        // Drawn at the display's own resolution, while sizes and the mouse stay in logical pixels on high-DPI
+        // screens, text included
+        high_dpi: true,
         ..Default::default()
     }
 }
@@ -0,0 +1,117 @@
+use crate::{Boundary, Cell, Universe};
+
+/// Rule over the Margolus neighborhood: the grid is split into 2×2 blocks, each of which turns into another block
+/// on its own, and the split shifts one cell down and right every other generation so blocks don't stay apart.
+/// Blocks are 4 bits: top left 1, top right 2, bottom left 4 and bottom right 8.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct BlockRule {
+    table: [u8; 16],
+}
+
+impl BlockRule {
+    /// Blocks with exactly two live cells stay as they are, and every other one flips, turning around too if it
+    /// had three. Empty space flips along with the rest, so it blinks every generation.
+    pub const CRITTERS: BlockRule = BlockRule { table: [15, 14, 13, 3, 11, 5, 6, 1, 7, 9, 10, 2, 12, 4, 8, 0] };
+    /// Full and empty blocks flip, and the rest stay
+    pub const TRON: BlockRule = BlockRule { table: [15, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 0] };
+    /// Billiard ball machine: lone cells move over to the opposite corner, and two facing each other from
+    /// opposite corners bounce off to the other two
+    pub const BILLIARD_BALL: BlockRule = BlockRule { table: [0, 8, 4, 3, 2, 5, 9, 7, 1, 6, 10, 11, 12, 13, 14, 15] };
+
+    /// `table[block]` is what each block turns into, only its lowest 4 bits counting
+    pub fn new(table: [u8; 16]) -> Self {
+        Self { table: table.map(|block| block & 15) }
+    }
+
+    pub fn next(&self, block: u8) -> u8 { self.table[block as usize & 15] }
+
+    /// Whether every block comes from a different one, so that any generation can be told from the next
+    pub fn is_reversible(&self) -> bool { self.inverse().is_some() }
+
+    /// Rule undoing this one, if it's reversible
+    pub fn inverse(&self) -> Option<BlockRule> {
+        let mut inverse = [16; 16];
+        for (block, &next) in self.table.iter().enumerate() {
+            if inverse[next as usize] != 16 { return None; }
+            inverse[next as usize] = block as u8;
+        }
+        Some(BlockRule { table: inverse })
+    }
+}
+
+/// Blocks of the split into pairs of rows (or columns), the first one starting `phase` cells before the edge.
+/// A toroidal edge only wraps a block around for an even `len`, otherwise the cells past the edge are dead.
+fn block_count(phase: usize, len: usize, wraps: bool) -> usize {
+    if wraps { len / 2 } else { (len + phase).div_ceil(2) }
+}
+
+/// The two rows (or columns) making up the `b`th block, leaving out the ones past the edge
+fn block_lines(b: usize, phase: usize, len: usize, wraps: bool) -> [Option<usize>; 2] {
+    let first = 2 * b as isize - phase as isize;
+    [first, first + 1].map(|line| match wraps {
+        true  => Some(line.rem_euclid(len as isize) as usize),
+        false => (0..len as isize).contains(&line).then_some(line as usize),
+    })
+}
+
+impl Universe {
+    pub fn get_block_rule(&self) -> Option<BlockRule> { self.block_rule }
+
+    /// Runs the universe by a rule over 2×2 blocks instead of its `Rule`, until the next `set_rule`, or `None` to
+    /// go back to it. Cells stop dying gradually: anything that isn't alive counts as dead.
+    pub fn set_block_rule(&mut self, rule: Option<BlockRule>) {
+        self.block_rule = rule;
+        if rule.is_some() {
+            self.table = None;
+            self.custom_rule = None;
+            self.elementary = None;
+        }
+        self.mark_all_dirty();
+    }
+
+    /// Runs the block rule backwards by a generation, as long as it's reversible. Unlike `step_back` it needs no
+    /// remembered generations, so it goes back past edits too, down to generation 0. It only undoes `tick`
+    /// exactly on a torus with even sides, since blocks cut by an edge lose what crosses it.
+    pub fn tick_backward(&mut self) -> bool {
+        let Some(inverse) = self.block_rule.and_then(|rule| rule.inverse()) else { return false };
+        if self.generation == 0 { return false; }
+        self.generation -= 1;
+        let mut previous = std::mem::take(&mut self.back_buffer);
+        self.tick_blocks(&inverse, &mut previous);
+        self.back_buffer = previous;
+        std::mem::swap(&mut self.cells, &mut self.back_buffer);
+        self.past.pop_back();
+        for age in self.ages.iter_mut() { *age = age.saturating_sub(1); }
+        self.forget_deaths();
+        if let Some(log) = &mut self.population_log {
+            if log.len() > 1 { log.pop_back(); }
+        }
+        self.mark_all_dirty();
+        true
+    }
+
+    /// Writes into `next` what every block turns into by `rule`, split the way this generation splits them
+    pub(crate) fn tick_blocks(&self, rule: &BlockRule, next: &mut [Cell]) {
+        let phase = (self.generation % 2) as usize;
+        let row_wraps = self.boundary == Boundary::Toroidal && self.height.is_multiple_of(2);
+        let col_wraps = self.col_boundary == Boundary::Toroidal && self.width.is_multiple_of(2);
+
+        for by in 0..block_count(phase, self.height, row_wraps) {
+            let rows = block_lines(by, phase, self.height, row_wraps);
+            for bx in 0..block_count(phase, self.width, col_wraps) {
+                let cols = block_lines(bx, phase, self.width, col_wraps);
+                let cells = [(rows[0], cols[0]), (rows[0], cols[1]), (rows[1], cols[0]), (rows[1], cols[1])]
+                    .map(|(row, col)| row.zip(col).map(|(row, col)| row * self.width + col));
+
+                let block = cells.iter().enumerate()
+                    .filter(|(_, i)| i.is_some_and(|i| self.cells[i].is_alive()))
+                    .fold(0, |block, (bit, _)| block | 1 << bit);
+                let after = rule.next(block);
+                for (bit, i) in cells.iter().enumerate() {
+                    let Some(i) = *i else { continue };
+                    next[i] = if after & 1 << bit != 0 { Cell::Alive } else { Cell::Dead };
+                }
+            }
+        }
+    }
+}
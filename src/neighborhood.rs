@@ -0,0 +1,60 @@
+/// Which cells around a given one count as its neighbors
+///
+/// Only `Universe` honors anything but `Moore`: the other engines always count the 8 surrounding cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The 8 surrounding cells
+    #[default]
+    Moore,
+    /// The 4 orthogonally adjacent cells
+    VonNeumann,
+    /// The 6 cells around a hexagon, on a grid where odd rows sit half a cell to the right of even ones.
+    /// Wrapping around vertically only lines up on universes with an even height.
+    Hex,
+    /// Larger than Life's square of every cell up to `radius` rows and columns away, the cell itself only
+    /// counting with `include_center`
+    Box { radius: u8, include_center: bool },
+}
+
+impl Neighborhood {
+    /// Greatest possible neighbor count
+    pub fn size(&self) -> u16 {
+        match *self {
+            Neighborhood::Box { radius, include_center } => (2 * radius as u16 + 1).pow(2) - !include_center as u16,
+            _                                            => self.offsets(0).len() as u16,
+        }
+    }
+
+    /// How many cells away the farthest neighbor is
+    pub fn radius(&self) -> usize {
+        match *self {
+            Neighborhood::Box { radius, .. } => radius as usize,
+            _                                => 1,
+        }
+    }
+
+    /// Like `offsets`, but listing `Box` neighborhoods' too
+    pub(crate) fn all_offsets(&self, row: usize) -> Vec<(isize, isize)> {
+        match *self {
+            Neighborhood::Box { radius, include_center } => {
+                let r = radius as isize;
+                (-r..=r).flat_map(|dy| (-r..=r).map(move |dx| (dy, dx)))
+                    .filter(|&offset| include_center || offset != (0, 0))
+                    .collect()
+            }
+            _ => self.offsets(row).to_vec(),
+        }
+    }
+
+    /// (row, column) offsets of the neighbors of a cell on row `row`. Empty for `Box`, which is counted with
+    /// running sums instead.
+    pub(crate) fn offsets(&self, row: usize) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Moore                        => &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)],
+            Neighborhood::VonNeumann                   => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighborhood::Hex if row.is_multiple_of(2) => &[(-1, -1), (-1, 0), (0, -1), (0, 1), (1, -1), (1, 0)],
+            Neighborhood::Hex                          => &[(-1, 0), (-1, 1), (0, -1), (0, 1), (1, 0), (1, 1)],
+            Neighborhood::Box { .. }                   => &[],
+        }
+    }
+}
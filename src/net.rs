@@ -0,0 +1,95 @@
+use std::{fmt, process::Command};
+
+use crate::{ParseRleError, Pattern, Rule};
+
+/// Where LifeWiki keeps the RLE file of every pattern it has an article on
+const LIFEWIKI: &str = "https://conwaylife.com/patterns";
+const CATAGOLUE: &str = "https://catagolue.hatsya.com/rle";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FetchError {
+    /// `curl` couldn't be run, or the download failed for some reason other than the pattern not being there
+    Download(String),
+    NotFound,
+    /// What was asked for can't be an apgcode, which only has lowercase letters, digits and underscores
+    InvalidApgcode(String),
+    Parse(ParseRleError),
+}
+
+impl Pattern {
+    /// Downloads the pattern the LifeWiki has under `name`, such as `Gosper glider gun` or `lwss`, along with its
+    /// rule if it names one
+    pub fn fetch_lifewiki(name: &str) -> Result<(Pattern, Option<Rule>), FetchError> {
+        let file = name.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+        if file.is_empty() { return Err(FetchError::NotFound); }
+        Pattern::from_rle(&download(&format!("{LIFEWIKI}/{}.rle", percent_encode(&file)))?).map_err(FetchError::Parse)
+    }
+
+    /// The object known to Catagolue as `apgcode` under that `rule`. Still lifes, oscillators and spaceships are
+    /// read right out of their code, without downloading anything.
+    pub fn fetch_catagolue(apgcode: &str, rule: Rule) -> Result<Pattern, FetchError> {
+        if let Ok(pattern) = Pattern::from_apgcode(apgcode) { return Ok(pattern); }
+        let apgcode = apgcode.trim();
+        let valid = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_';
+        if apgcode.is_empty() || !apgcode.chars().all(valid) { return Err(FetchError::InvalidApgcode(apgcode.to_owned())); }
+        // Catagolue spells rules like b3s23
+        let rule = rule.to_string().to_lowercase().replace('/', "");
+        let rle = download(&format!("{CATAGOLUE}/{}/{}", percent_encode(&rule), percent_encode(apgcode)))?;
+        Pattern::from_rle(&rle).map(|(pattern, _)| pattern).map_err(FetchError::Parse)
+    }
+}
+
+/// `segment` made safe to put in a URL's path, everything but unreserved characters going as `%XX`
+fn percent_encode(segment: &str) -> String {
+    segment.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => char::from(b).to_string(),
+        _                                                                   => format!("%{b:02X}"),
+    }).collect()
+}
+
+/// Body of `url`, through `curl`, which already knows about TLS and proxies. Globbing is off, so that brackets
+/// and braces in the URL are taken as they are.
+fn download(url: &str) -> Result<String, FetchError> {
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--location", "--globoff", "--max-time", "30", url])
+        .output()
+        .map_err(|e| FetchError::Download(format!("couldn't run curl: {e}")))?;
+    match output.status.code() {
+        Some(0)  => Ok(String::from_utf8_lossy(&output.stdout).into_owned()),
+        // What --fail exits with on an HTTP error, which is most likely a 404
+        Some(22) => Err(FetchError::NotFound),
+        _        => Err(FetchError::Download(String::from_utf8_lossy(&output.stderr).trim().to_owned())),
+    }
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::Download(reason)  => write!(f, "download failed: {reason}"),
+            FetchError::NotFound          => write!(f, "no such pattern"),
+            FetchError::InvalidApgcode(s) => write!(f, "{s:?} isn't an apgcode"),
+            FetchError::Parse(e)          => write!(f, "couldn't read the pattern: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for FetchError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percent_encodes_everything_but_unreserved_characters() {
+        assert_eq!(percent_encode("xp2_7e"), "xp2_7e");
+        assert_eq!(percent_encode("a b[1]{2}/?#"), "a%20b%5B1%5D%7B2%7D%2F%3F%23");
+        assert_eq!(percent_encode("é"), "%C3%A9");
+    }
+
+    #[test]
+    fn only_apgcodes_go_to_catagolue() {
+        for bad in ["xp2_[a-z]", "yl{1,2}_x", "xq4 _153", "XP2_7", "../../etc", "", "   "] {
+            assert_eq!(Pattern::fetch_catagolue(bad, Rule::CONWAY), Err(FetchError::InvalidApgcode(bad.trim().to_owned())));
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::rng::SplitMix64;
+
+/// 2D Perlin noise over a seeded lattice of gradients. Values lie roughly within -1 to 1, and vary smoothly
+/// over lattice cells one unit wide.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Perlin {
+    seed: u64,
+}
+
+impl Perlin {
+    pub(crate) fn new(seed: u64) -> Self { Self { seed } }
+
+    pub(crate) fn at(&self, x: f32, y: f32) -> f32 {
+        let (x0, y0) = (x.floor(), y.floor());
+        let (fx, fy) = (x - x0, y - y0);
+        let (ix, iy) = (x0 as i64, y0 as i64);
+
+        let dot = |dx: i64, dy: i64| {
+            let (gx, gy) = self.gradient(ix + dx, iy + dy);
+            gx * (fx - dx as f32) + gy * (fy - dy as f32)
+        };
+        let fade = |t: f32| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+        let (u, v) = (fade(fx), fade(fy));
+        let top = lerp(dot(0, 0), dot(1, 0), u);
+        let bottom = lerp(dot(0, 1), dot(1, 1), u);
+        // The raw range is ±√½, stretched out to about ±1
+        lerp(top, bottom, v) * std::f32::consts::SQRT_2
+    }
+
+    /// Unit gradient at a lattice point, one of 8 evenly spread directions
+    fn gradient(&self, x: i64, y: i64) -> (f32, f32) {
+        let key = self.seed ^ (x as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15) ^ (y as u64).wrapping_mul(0xC2B2_AE3D_27D4_EB4F);
+        let hash = SplitMix64::new(key).next_u64();
+        let angle = (hash % 8) as f32 * std::f32::consts::FRAC_PI_4;
+        (angle.cos(), angle.sin())
+    }
+}
@@ -0,0 +1,29 @@
+use crate::Universe;
+
+/// Every `.npy` file starts with this, followed by the format's version (1.0)
+const MAGIC: &[u8] = b"\x93NUMPY\x01\x00";
+
+impl Universe {
+    /// The grid as a NumPy `.npy` file holding a (height, width) array of unsigned bytes, each cell its state
+    /// (see `Cell::state` and `Universe::conductor_state`). Conductors under 256-state rule tables don't fit in
+    /// a byte, so those take two. `numpy.load` reads it as is.
+    pub fn to_npy(&self) -> Vec<u8> {
+        let conductor = self.conductor_state();
+        let wide = conductor > u8::MAX as usize;
+        let descr = if wide { "<u2" } else { "|u1" };
+        let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': ({}, {}), }}", self.height, self.width);
+        // The data starts aligned to 64 bytes, after spaces and a newline that end the header
+        let length = MAGIC.len() + 2 + header.len() + 1;
+        header.push_str(&" ".repeat(length.next_multiple_of(64) - length));
+        header.push('\n');
+
+        let mut out = MAGIC.to_vec();
+        out.extend((header.len() as u16).to_le_bytes());
+        out.extend(header.bytes());
+        for cell in &self.cells {
+            let state = cell.state(conductor) as u16;
+            if wide { out.extend(state.to_le_bytes()); } else { out.push(state as u8); }
+        }
+        out
+    }
+}
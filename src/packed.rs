@@ -0,0 +1,169 @@
+use std::mem::swap;
+
+use crate::{Boundary, Cell, Coord, Rule, Universe};
+
+/// Universe storing one bit per cell (64 cells per word), ticked a whole word at a time. Being two-state,
+/// the decay states of Generations rules are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PackedUniverse {
+    /// Rows of `words_per_row` words each, the lowest bit of a word being its leftmost cell
+    words: Vec<u64>,
+    back_buffer: Vec<u64>,
+    height: usize,
+    width: usize,
+    words_per_row: usize,
+    /// Past the top and bottom edges
+    boundary: Boundary,
+    /// Past the left and right edges
+    col_boundary: Boundary,
+    rule: Rule,
+}
+
+impl PackedUniverse {
+    pub fn new(height: usize, width: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        let words = vec![0; words_per_row * height];
+        Self { words: words.clone(), back_buffer: words, height, width, words_per_row,
+               boundary: Boundary::default(), col_boundary: Boundary::default(), rule: Rule::default() }
+    }
+
+    pub fn get_width(&self) -> usize                 { self.width }
+    pub fn get_height(&self) -> usize                { self.height }
+    pub fn get_boundary(&self) -> Boundary           { self.boundary }
+    pub fn set_boundary(&mut self, b: Boundary)      { self.set_boundaries(b, b) }
+    pub fn get_boundaries(&self) -> (Boundary, Boundary) { (self.boundary, self.col_boundary) }
+    pub fn get_rule(&self) -> Rule                   { self.rule }
+    pub fn set_rule(&mut self, rule: Rule)           { self.rule = rule }
+    pub fn is_alive(&self, c: Coord) -> bool         { self.get(c).is_alive() }
+    pub fn toggle_pixel(&mut self, c: Coord)         { self.set(c, !self.get(c)) }
+    pub fn population(&self) -> usize                { self.words.iter().map(|w| w.count_ones() as usize).sum() }
+
+    /// See `Universe::set_boundaries`
+    pub fn set_boundaries(&mut self, vertical: Boundary, horizontal: Boundary) {
+        self.boundary = vertical;
+        self.col_boundary = horizontal;
+    }
+
+    pub fn get(&self, c: Coord) -> Cell {
+        let (i, bit) = self.locate(c);
+        if self.words[i] >> bit & 1 == 1 { Cell::Alive } else { Cell::Dead }
+    }
+
+    pub fn set(&mut self, c: Coord, val: Cell) {
+        let (i, bit) = self.locate(c);
+        if val.is_alive() { self.words[i] |= 1 << bit; } else { self.words[i] &= !(1 << bit); }
+    }
+
+    pub fn tick(&mut self) {
+        if self.width == 0 { return; }
+        let wpr = self.words_per_row;
+        let (mut west, mut east) = (vec![0; wpr], vec![0; wpr]);
+        let mut counters = [vec![0u64; wpr], vec![0u64; wpr], vec![0u64; wpr], vec![0u64; wpr]];
+        let mut back_buffer = std::mem::take(&mut self.back_buffer);
+
+        for y in 0..self.height {
+            for counter in counters.iter_mut() { counter.fill(0); }
+
+            for dy in [-1, 0, 1] {
+                let Some(row) = self.boundary.resolve(y as isize + dy, self.height) else { continue };
+                let words = &self.words[row * wpr..(row + 1) * wpr];
+                self.shift_row(words, &mut west, &mut east);
+
+                if dy != 0 { add_bitwise(&mut counters, words); }
+                add_bitwise(&mut counters, &west);
+                add_bitwise(&mut counters, &east);
+            }
+
+            let current = &self.words[y * wpr..(y + 1) * wpr];
+            let next = &mut back_buffer[y * wpr..(y + 1) * wpr];
+            for (w, next) in next.iter_mut().enumerate() {
+                let alive = current[w];
+                *next = (0..=8u8).fold(0, |acc, n| {
+                    let matches = (0..4).fold(!0, |m, b| m & if n >> b & 1 == 1 { counters[b][w] } else { !counters[b][w] });
+                    let survive = if self.rule.survives_on(n.into()) { alive } else { 0 };
+                    let birth = if self.rule.births_on(n.into()) { !alive } else { 0 };
+                    acc | matches & (survive | birth)
+                });
+            }
+            next[wpr - 1] &= self.last_word_mask();
+        }
+
+        swap(&mut self.words, &mut back_buffer);
+        self.back_buffer = back_buffer;
+    }
+
+    fn locate(&self, c: Coord) -> (usize, usize) {
+        (c.row * self.words_per_row + c.col / 64, c.col % 64)
+    }
+
+    fn last_word_mask(&self) -> u64 {
+        match self.width % 64 {
+            0 => !0,
+            r => (1 << r) - 1,
+        }
+    }
+
+    /// Bit of the cell at `col` on the given row, resolving out-of-range columns through the boundary
+    fn edge_bit(&self, words: &[u64], col: isize) -> u64 {
+        match self.col_boundary.resolve(col, self.width) {
+            Some(col) => words[col / 64] >> (col % 64) & 1,
+            None      => 0,
+        }
+    }
+
+    /// Fills `west`/`east` so that each bit holds the cell to its left/right
+    fn shift_row(&self, words: &[u64], west: &mut [u64], east: &mut [u64]) {
+        let last = words.len() - 1;
+        for w in 0..words.len() {
+            let carry_west = if w == 0 { self.edge_bit(words, -1) } else { words[w - 1] >> 63 };
+            let carry_east = if w == last { 0 } else { words[w + 1] << 63 };
+            west[w] = words[w] << 1 | carry_west;
+            east[w] = words[w] >> 1 | carry_east;
+        }
+        let last_bit = (self.width - 1) % 64;
+        east[last] |= self.edge_bit(words, self.width as isize) << last_bit;
+    }
+}
+
+/// Adds a bit vector into per-bit 4 bit counters, one full adder chain per word
+fn add_bitwise(counters: &mut [Vec<u64>; 4], x: &[u64]) {
+    for (w, &x) in x.iter().enumerate() {
+        let mut carry = x;
+        for counter in counters.iter_mut() {
+            let next_carry = counter[w] & carry;
+            counter[w] ^= carry;
+            carry = next_carry;
+        }
+    }
+}
+
+impl From<&Universe> for PackedUniverse {
+    fn from(universe: &Universe) -> Self {
+        let mut packed = Self::new(universe.get_height(), universe.get_width());
+        (packed.boundary, packed.col_boundary) = universe.get_boundaries();
+        packed.rule = universe.get_rule();
+        for c in universe.live_cells() { packed.set(c, Cell::Alive); }
+        packed
+    }
+}
+
+impl From<&PackedUniverse> for Universe {
+    fn from(packed: &PackedUniverse) -> Self {
+        let mut universe = Universe::new(packed.height, packed.width);
+        universe.set_boundaries(packed.boundary, packed.col_boundary);
+        universe.set_rule(packed.rule);
+        for row in 0..packed.height {
+            for col in 0..packed.width {
+                let c = Coord::new(row, col);
+                universe.set_pixel(c, packed.get(c));
+            }
+        }
+        universe
+    }
+}
+
+impl std::fmt::Display for PackedUniverse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", Universe::from(self))
+    }
+}
@@ -0,0 +1,39 @@
+use crate::Cell;
+
+/// RGBA colors cells get drawn in when the universe is exported
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Palette {
+    pub dead: [u8; 4],
+    pub alive: [u8; 4],
+    /// Fading out the closer cells are to dead
+    pub dying: [u8; 4],
+    pub conductor: [u8; 4],
+}
+
+impl Palette {
+    /// Live cells in black over white, decaying ones in grey and Wireworld's copper in orange
+    pub const PAPER: Palette = Palette {
+        dead: [255, 255, 255, 255],
+        alive: [0, 0, 0, 255],
+        dying: [128, 128, 128, 255],
+        conductor: [230, 150, 40, 255],
+    };
+
+    /// Color of `cell` under a rule with `states` states
+    pub fn color(&self, cell: Cell, states: u8) -> [u8; 4] {
+        match cell {
+            Cell::Dead      => self.dead,
+            Cell::Alive     => self.alive,
+            Cell::Dying(k)  => {
+                let [r, g, b, a] = self.dying;
+                let fade = 1.0 - k.saturating_sub(1) as f32 / (states as f32 - 1.0).max(1.0);
+                [r, g, b, (a as f32 * fade) as u8]
+            }
+            Cell::Conductor => self.conductor,
+        }
+    }
+}
+
+impl Default for Palette {
+    fn default() -> Self { Palette::PAPER }
+}
@@ -0,0 +1,137 @@
+use crate::{Boundary, Cell, Coord, Universe};
+
+/// Small grid of cells detached from any universe, ready to be blitted into one
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    cells: Vec<Cell>,
+    height: usize,
+    width: usize,
+}
+
+/// How a blitted pattern's cells combine with the ones already in the universe
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum BlitMode {
+    /// The pattern overwrites everything under it, dead cells included
+    #[default]
+    Copy,
+    Or,
+    Xor,
+    And,
+}
+
+impl BlitMode {
+    pub fn combine(&self, current: Cell, incoming: Cell) -> Cell {
+        let alive = |b: bool| if b { Cell::Alive } else { Cell::Dead };
+        match self {
+            BlitMode::Copy => incoming,
+            BlitMode::Or   => alive(current.is_alive() || incoming.is_alive()),
+            BlitMode::Xor  => alive(current.is_alive() != incoming.is_alive()),
+            BlitMode::And  => alive(current.is_alive() && incoming.is_alive()),
+        }
+    }
+}
+
+impl Pattern {
+    pub fn new(height: usize, width: usize) -> Self {
+        Self { cells: vec![Cell::Dead; height * width], height, width }
+    }
+
+    /// `cells` are row by row, and there must be exactly `height * width` of them
+    pub fn from_cells(height: usize, width: usize, cells: Vec<Cell>) -> Self {
+        assert_eq!(cells.len(), height * width, "pattern cells don't match its dimensions");
+        Self { cells, height, width }
+    }
+
+    pub fn get_height(&self) -> usize               { self.height }
+    pub fn get_width(&self) -> usize                { self.width }
+    pub fn get(&self, c: Coord) -> Cell             { self.cells[c.row * self.width + c.col] }
+    pub fn set(&mut self, c: Coord, val: Cell)      { self.cells[c.row * self.width + c.col] = val }
+    pub fn is_alive(&self, c: Coord) -> bool        { self.get(c).is_alive() }
+    pub fn population(&self) -> usize               { self.cells.iter().filter(|c| c.is_alive()).count() }
+
+    /// Every cell along with its coordinates, row by row
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Coord, Cell)> + '_ {
+        self.cells.iter().enumerate().map(|(i, &cell)| (Coord::new(i / self.width, i % self.width), cell))
+    }
+
+    /// Quarter turn clockwise
+    pub fn rotate_cw(&self) -> Pattern {
+        self.remap(self.width, self.height, |c| Coord::new(c.col, self.height - 1 - c.row))
+    }
+
+    /// Quarter turn counterclockwise
+    pub fn rotate_ccw(&self) -> Pattern {
+        self.remap(self.width, self.height, |c| Coord::new(self.width - 1 - c.col, c.row))
+    }
+
+    /// Mirrors left to right
+    pub fn flip_h(&self) -> Pattern {
+        self.remap(self.height, self.width, |c| Coord::new(c.row, self.width - 1 - c.col))
+    }
+
+    /// Mirrors top to bottom
+    pub fn flip_v(&self) -> Pattern {
+        self.remap(self.height, self.width, |c| Coord::new(self.height - 1 - c.row, c.col))
+    }
+
+    /// Top-left corner and size (height, width) of the smallest box holding every live cell, if there's any
+    pub fn bounding_box(&self) -> Option<(Coord, Coord)> { bounding_box(&self.cells, self.width) }
+
+    /// Cuts away the dead margins. An empty pattern trims down to 0×0.
+    pub fn trim(&self) -> Pattern {
+        let Some((top_left, size)) = self.bounding_box() else { return Pattern::new(0, 0) };
+        let mut trimmed = Pattern::new(size.row, size.col);
+        for row in 0..size.row {
+            for col in 0..size.col {
+                trimmed.set(Coord::new(row, col), self.get(Coord::new(top_left.row + row, top_left.col + col)));
+            }
+        }
+        trimmed
+    }
+
+    /// New `height`×`width` pattern whose cell at `to(c)` is this one's `c`
+    fn remap(&self, height: usize, width: usize, to: impl Fn(Coord) -> Coord) -> Pattern {
+        let mut out = Pattern::new(height, width);
+        for (c, cell) in self.iter_cells() { out.set(to(c), cell); }
+        out
+    }
+}
+
+/// See `Pattern::bounding_box`, over any row by row grid of cells
+pub(crate) fn bounding_box(cells: &[Cell], width: usize) -> Option<(Coord, Coord)> {
+    let (mut min, mut max) = (Coord::new(usize::MAX, usize::MAX), Coord::new(0, 0));
+    for (i, cell) in cells.iter().enumerate() {
+        if !cell.is_alive() { continue; }
+        let c = Coord::new(i / width, i % width);
+        min = Coord::new(min.row.min(c.row), min.col.min(c.col));
+        max = Coord::new(max.row.max(c.row), max.col.max(c.col));
+    }
+    (min.row <= max.row).then(|| (min, Coord::new(max.row - min.row + 1, max.col - min.col + 1)))
+}
+
+impl From<&Universe> for Pattern {
+    fn from(universe: &Universe) -> Self {
+        Self::from_cells(universe.get_height(), universe.get_width(), universe.cells.clone())
+    }
+}
+
+impl Universe {
+    /// Stamps `pattern` with its top-left corner at `at`. Parts hanging past the edges wrap around where the
+    /// boundary is toroidal, and are clipped otherwise.
+    pub fn blit(&mut self, pattern: &Pattern, at: Coord, mode: BlitMode) {
+        let (vertical, horizontal) = self.get_boundaries();
+        let (height, width) = (self.get_height(), self.get_width());
+        if height == 0 || width == 0 { return; }
+
+        let place = |pos: usize, len: usize, boundary: Boundary| match boundary {
+            Boundary::Toroidal => Some(pos % len),
+            _                  => (pos < len).then_some(pos),
+        };
+        for (offset, incoming) in pattern.iter_cells() {
+            let Some(row) = place(at.row + offset.row, height, vertical) else { continue };
+            let Some(col) = place(at.col + offset.col, width, horizontal) else { continue };
+            let c = Coord::new(row, col);
+            self[c] = mode.combine(self[c], incoming);
+        }
+    }
+}
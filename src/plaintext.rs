@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::{rle::{MAX_CELLS, MAX_SIDE}, Cell, Coord, Pattern};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ParsePlaintextError {
+    /// A character in a row that's neither `.` nor `O`, along with the line it's on (counting from 1)
+    InvalidCharacter(char, usize),
+    /// The rows go past `MAX_SIDE` to a side, or `MAX_CELLS` in all
+    TooLarge,
+}
+
+impl Pattern {
+    /// Reads a pattern in the plaintext `.cells` format: lines starting with `!` are comments, and every other
+    /// one is a row of `.` for dead cells and `O` (or `*`) for live ones. Rows shorter than the widest one are
+    /// padded with dead cells, up to the size RLE files are kept to.
+    pub fn from_plaintext(s: &str) -> Result<Pattern, ParsePlaintextError> {
+        let mut rows = Vec::new();
+        for (i, line) in s.trim_end().lines().enumerate() {
+            if line.starts_with('!') { continue; }
+            let row = line.trim_end().chars().map(|c| match c {
+                '.'       => Ok(false),
+                'O' | '*' => Ok(true),
+                _         => Err(ParsePlaintextError::InvalidCharacter(c, i + 1)),
+            }).collect::<Result<Vec<_>, _>>()?;
+            if row.len() > MAX_SIDE || rows.len() == MAX_SIDE { return Err(ParsePlaintextError::TooLarge); }
+            rows.push(row);
+        }
+
+        let width = rows.iter().map(Vec::len).max().unwrap_or(0);
+        if rows.len() * width > MAX_CELLS { return Err(ParsePlaintextError::TooLarge); }
+        let mut pattern = Pattern::new(rows.len(), width);
+        for (row, cells) in rows.iter().enumerate() {
+            for (col, _) in cells.iter().enumerate().filter(|(_, &alive)| alive) {
+                pattern.set(Coord::new(row, col), Cell::Alive);
+            }
+        }
+        Ok(pattern)
+    }
+
+    /// Writes the pattern in the plaintext `.cells` format, under a `!Name:` line if given a `name`. Only live
+    /// cells come out as `O`, and the dead cells ending each row are left out.
+    pub fn to_plaintext(&self, name: Option<&str>) -> String {
+        let mut out = name.map_or(String::new(), |name| format!("!Name: {name}\n"));
+        for row in 0..self.get_height() {
+            let line = (0..self.get_width()).map(|col| if self.is_alive(Coord::new(row, col)) { 'O' } else { '.' })
+                .collect::<String>();
+            out.push_str(line.trim_end_matches('.'));
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl fmt::Display for ParsePlaintextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParsePlaintextError::InvalidCharacter(c, line) => write!(f, "unexpected character {c:?} on line {line}"),
+            ParsePlaintextError::TooLarge                  => write!(f, "the pattern is too large"),
+        }
+    }
+}
+
+impl std::error::Error for ParsePlaintextError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_oversized_patterns() {
+        let wide = ".".repeat(MAX_SIDE) + "O";
+        assert_eq!(Pattern::from_plaintext(&wide), Err(ParsePlaintextError::TooLarge));
+        let tall = "O\n".repeat(MAX_SIDE + 1);
+        assert_eq!(Pattern::from_plaintext(&tall), Err(ParsePlaintextError::TooLarge));
+        // Neither side too long, but too many cells between them
+        let spread = format!("{}\n{}", ".".repeat(MAX_SIDE), "O\n".repeat(MAX_CELLS / MAX_SIDE));
+        assert_eq!(Pattern::from_plaintext(&spread), Err(ParsePlaintextError::TooLarge));
+
+        let fits = Pattern::from_plaintext(&"O\n".repeat(MAX_SIDE)).unwrap();
+        assert_eq!((fits.get_height(), fits.get_width()), (MAX_SIDE, 1));
+    }
+}
@@ -0,0 +1,99 @@
+//! Threads that stay around between generations, one per core, so that ticking in bands doesn't spawn and join
+//! a thread for each of them every tick.
+
+use std::{panic, sync::{mpsc, Arc, Condvar, Mutex, OnceLock}, thread};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub(crate) struct Pool {
+    jobs: mpsc::Sender<Job>,
+    threads: usize,
+}
+
+/// Started on the first tick that needs it
+static POOL: OnceLock<Pool> = OnceLock::new();
+
+impl Pool {
+    pub(crate) fn get() -> &'static Pool {
+        POOL.get_or_init(|| {
+            let threads = thread::available_parallelism().map_or(1, |n| n.get());
+            let (jobs, queue) = mpsc::channel::<Job>();
+            let queue = Arc::new(Mutex::new(queue));
+            for _ in 0..threads {
+                let queue = Arc::clone(&queue);
+                thread::spawn(move || loop {
+                    // Only held while waiting for the next job, not while running it
+                    let job = queue.lock().unwrap_or_else(|e| e.into_inner()).recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_)  => return,
+                    }
+                });
+            }
+            Pool { jobs, threads }
+        })
+    }
+
+    pub(crate) fn threads(&self) -> usize { self.threads }
+
+    /// Runs every job on the pool's threads, returning once they're all done. If any of them panicked, so does
+    /// this, after the rest are done.
+    pub(crate) fn run<'a>(&self, jobs: impl IntoIterator<Item = Box<dyn FnOnce() + Send + 'a>>) {
+        // All of them before any is sent, so that nothing left running can outlive `'a` by a panic here
+        let jobs: Vec<_> = jobs.into_iter().collect();
+        // Jobs still running, and whether any panicked
+        let state = Arc::new((Mutex::new((jobs.len(), false)), Condvar::new()));
+        for job in jobs {
+            // SAFETY: the job only gets to run before this returns, which waits for it to be done, so whatever it
+            // borrows for `'a` is still around while it does
+            let job = unsafe { std::mem::transmute::<Box<dyn FnOnce() + Send + 'a>, Job>(job) };
+            let state = Arc::clone(&state);
+            let job = Box::new(move || {
+                let panicked = panic::catch_unwind(panic::AssertUnwindSafe(job)).is_err();
+                let (running, done) = &*state;
+                let mut running = running.lock().unwrap_or_else(|e| e.into_inner());
+                *running = (running.0 - 1, running.1 || panicked);
+                done.notify_all();
+            });
+            // The threads never stop, but if they did the job would still get its turn here
+            if let Err(mpsc::SendError(job)) = self.jobs.send(job) { job(); }
+        }
+
+        let (running, done) = &*state;
+        let running = done.wait_while(running.lock().unwrap_or_else(|e| e.into_inner()), |(left, _)| *left > 0)
+            .unwrap_or_else(|e| e.into_inner());
+        if running.1 { panic!("ticking a band of rows panicked"); }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_every_job_before_returning() {
+        let mut bands = vec![0; 100];
+        for round in 1..=3 {
+            Pool::get().run(bands.chunks_mut(7).map(|band| {
+                Box::new(move || for n in band.iter_mut() { *n += round }) as Box<dyn FnOnce() + Send + '_>
+            }));
+        }
+        assert!(bands.iter().all(|&n| n == 6));
+    }
+
+    #[test]
+    fn panics_along_with_its_jobs() {
+        let ran = std::sync::atomic::AtomicUsize::new(0);
+        let result = panic::catch_unwind(panic::AssertUnwindSafe(|| {
+            Pool::get().run((0..4).map(|i| Box::new({
+                let ran = &ran;
+                move || {
+                    ran.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+                    assert_ne!(i, 2);
+                }
+            }) as Box<dyn FnOnce() + Send + '_>));
+        }));
+        assert!(result.is_err());
+        assert_eq!(ran.into_inner(), 4);
+    }
+}
@@ -0,0 +1,78 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+
+/// Live cells in each tile of a universe, counted the first time they're asked for after the tile changed. Counts
+/// are kept through a shared reference, so atomics hold them.
+#[derive(Debug)]
+pub(crate) struct TilePopulations(Vec<AtomicU32>);
+
+impl TilePopulations {
+    /// Not counted since the tile last changed
+    const STALE: u32 = u32::MAX;
+
+    /// For as many tiles of dead cells
+    pub(crate) fn new(tiles: usize) -> Self { Self((0..tiles).map(|_| AtomicU32::new(0)).collect()) }
+
+    pub(crate) fn forget(&mut self, tile: usize) { *self.0[tile].get_mut() = TilePopulations::STALE; }
+
+    /// Starts over with `tiles` tiles, none of them counted yet
+    pub(crate) fn forget_all(&mut self, tiles: usize) {
+        self.0.resize_with(tiles, || AtomicU32::new(TilePopulations::STALE));
+        for count in &mut self.0 { *count.get_mut() = TilePopulations::STALE; }
+    }
+
+    /// Of every tile, `count` counting the ones that changed
+    pub(crate) fn total(&self, count: impl Fn(usize) -> u32) -> u64 {
+        self.0.iter().enumerate().map(|(t, cached)| {
+            let population = match cached.load(Ordering::Relaxed) {
+                TilePopulations::STALE => {
+                    let population = count(t);
+                    cached.store(population, Ordering::Relaxed);
+                    population
+                }
+                population             => population,
+            };
+            population as u64
+        }).sum()
+    }
+}
+
+impl Clone for TilePopulations {
+    fn clone(&self) -> Self { Self(self.0.iter().map(|count| AtomicU32::new(count.load(Ordering::Relaxed))).collect()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cell, Coord, Universe};
+
+    fn counted(universe: &Universe) -> usize { universe.cells.iter().filter(|&&cell| cell == Cell::Alive).count() }
+
+    #[test]
+    fn keeps_up_with_ticks_and_edits() {
+        let mut universe = Universe::new(50, 70);
+        universe.randomize(0.4, 3);
+        assert_eq!(universe.population(), counted(&universe));
+        for generation in 0..30 {
+            universe.tick();
+            if generation % 7 == 0 { universe[Coord::new(generation, 2 * generation)] = Cell::Alive; }
+            assert_eq!(universe.population(), counted(&universe));
+        }
+        universe.set_dimensions(Coord::new(33, 90), crate::Anchor::Center);
+        assert_eq!(universe.population(), counted(&universe));
+        universe.clear();
+        assert_eq!(universe.population(), 0);
+    }
+
+    #[test]
+    fn the_log_only_keeps_the_latest() {
+        let mut universe = Universe::new(8, 8);
+        for col in 1..4 { universe[Coord::new(2, col)] = Cell::Alive; }
+        universe.record_population(true);
+        universe.set_past_depth(1);
+        universe.step_n(crate::POPULATION_LOG as u64 + 10);
+        let log = universe.population_history();
+        assert_eq!(log.len(), crate::POPULATION_LOG);
+        assert!(log.iter().all(|&population| population == 3));
+        assert!(universe.step_back());
+        assert_eq!(universe.population_history().len(), crate::POPULATION_LOG - 1);
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use crate::{Cell, Coord, Palette, Universe};
+
+impl Universe {
+    /// Universe with a cell for each pixel of the PNG at `path` (the only format `image` is built with), alive where
+    /// the pixel's luminance (0 to 1) is at least `threshold`. With `dither` the difference between every pixel and
+    /// the cell it became is spread over the pixels after it (Floyd–Steinberg), so shades come out as more or fewer
+    /// live cells rather than as flat patches.
+    pub fn from_image(path: impl AsRef<Path>, threshold: f32, dither: bool) -> ::image::ImageResult<Universe> {
+        Ok(Universe::from_luminance(::image::open(path)?.to_luma32f(), threshold, dither))
+    }
+
+    /// Like `from_image`, out of the bytes of an image file in any format `image` can guess
+    pub fn from_image_bytes(bytes: &[u8], threshold: f32, dither: bool) -> ::image::ImageResult<Universe> {
+        Ok(Universe::from_luminance(::image::load_from_memory(bytes)?.to_luma32f(), threshold, dither))
+    }
+
+    fn from_luminance(image: ::image::ImageBuffer<::image::Luma<f32>, Vec<f32>>, threshold: f32, dither: bool) -> Universe {
+        let (height, width) = (image.height() as usize, image.width() as usize);
+        let mut luminance = image.into_raw();
+        let mut universe = Universe::new(height, width);
+
+        for row in 0..height {
+            for col in 0..width {
+                let value = luminance[row * width + col];
+                let alive = value >= threshold;
+                if alive { universe.set_pixel(Coord::new(row, col), Cell::Alive); }
+                if !dither { continue; }
+
+                let error = value - if alive { 1.0 } else { 0.0 };
+                for (dy, dx, weight) in [(0, 1, 7.0), (1, -1, 3.0), (1, 0, 5.0), (1, 1, 1.0)] {
+                    let (row, col) = (row + dy, col as isize + dx);
+                    if row < height && (0..width as isize).contains(&col) {
+                        luminance[row * width + col as usize] += error * weight / 16.0;
+                    }
+                }
+            }
+        }
+        universe
+    }
+
+    /// Image of the universe with every cell a `cell_size`×`cell_size` square of its color in `palette`, rows
+    /// running down and columns across
+    pub fn to_image(&self, cell_size: u32, palette: &Palette) -> ::image::RgbaImage {
+        let (height, width) = (self.get_height() as u32, self.get_width() as u32);
+        let states = self.get_rule().states();
+        ::image::RgbaImage::from_fn(width * cell_size, height * cell_size, |x, y| {
+            let cell = self[Coord::new((y / cell_size) as usize, (x / cell_size) as usize)];
+            ::image::Rgba(palette.color(cell, states))
+        })
+    }
+}
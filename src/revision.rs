@@ -0,0 +1,112 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Hands out the ids that tell universes apart, copies included
+static UNIVERSES: AtomicU64 = AtomicU64::new(0);
+
+/// How far along its changes a universe was at some point, to be handed back to `Universe::changed_since`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Revision {
+    universe: u64,
+    changes: u64,
+}
+
+/// When each tile of a universe last changed, counting changes since the universe was made. A clone goes its
+/// own way from then on, so it counts as a universe of its own.
+#[derive(Debug)]
+pub(crate) struct Revisions {
+    universe: u64,
+    changes: u64,
+    tiles: Vec<u64>,
+}
+
+impl Revisions {
+    pub(crate) fn new(tiles: usize) -> Self {
+        Self { universe: UNIVERSES.fetch_add(1, Ordering::Relaxed), changes: 1, tiles: vec![1; tiles] }
+    }
+
+    pub(crate) fn current(&self) -> Revision { Revision { universe: self.universe, changes: self.changes } }
+
+    pub(crate) fn touch(&mut self, tile: usize) {
+        self.changes += 1;
+        self.tiles[tile] = self.changes;
+    }
+
+    /// Touches every tile for which `changed` says so, all in a single change
+    pub(crate) fn touch_where(&mut self, changed: impl Fn(usize) -> bool) {
+        self.changes += 1;
+        for (t, tile) in self.tiles.iter_mut().enumerate() {
+            if changed(t) { *tile = self.changes; }
+        }
+    }
+
+    /// Touches everything, starting over with `tiles` of them
+    pub(crate) fn reset(&mut self, tiles: usize) {
+        self.changes += 1;
+        self.tiles = vec![self.changes; tiles];
+    }
+
+    /// Whether `tile` changed after `since`, which it always did if that's a revision of another universe or none
+    pub(crate) fn changed(&self, tile: usize, since: Option<Revision>) -> bool {
+        match since {
+            Some(since) if since.universe == self.universe => self.tiles[tile] > since.changes,
+            _                                              => true,
+        }
+    }
+}
+
+impl Clone for Revisions {
+    fn clone(&self) -> Self {
+        Self { universe: UNIVERSES.fetch_add(1, Ordering::Relaxed), changes: self.changes, tiles: self.tiles.clone() }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Cell, Coord, Universe};
+
+    fn changed(universe: &Universe, since: crate::Revision) -> Vec<(Coord, Coord)> {
+        universe.changed_since(Some(since), Coord::new(0, 0), universe.get_height(), universe.get_width()).collect()
+    }
+
+    #[test]
+    fn only_edited_tiles_change() {
+        let mut universe = Universe::new(40, 40);
+        let since = universe.revision();
+        assert!(changed(&universe, since).is_empty());
+
+        universe[Coord::new(20, 35)] = Cell::Alive;
+        // Tiles are 16 cells to a side, and the last ones are cut short by the edges
+        assert_eq!(changed(&universe, since), [(Coord::new(16, 32), Coord::new(16, 8))]);
+        assert!(changed(&universe, universe.revision()).is_empty());
+    }
+
+    #[test]
+    fn regions_clip_the_tiles() {
+        let mut universe = Universe::new(40, 40);
+        let since = universe.revision();
+        universe[Coord::new(5, 5)] = Cell::Alive;
+        assert_eq!(universe.changed_since(Some(since), Coord::new(3, 4), 10, 10).collect::<Vec<_>>(),
+                   [(Coord::new(3, 4), Coord::new(10, 10))]);
+        assert_eq!(universe.changed_since(Some(since), Coord::new(20, 20), 10, 10).count(), 0);
+        assert_eq!(universe.changed_since(Some(since), Coord::new(50, 50), 10, 10).count(), 0);
+    }
+
+    #[test]
+    fn ticks_count_what_changed_and_what_did_the_generation_before() {
+        let mut universe = Universe::new(64, 64);
+        for col in 1..4 { universe[Coord::new(2, col)] = Cell::Alive; }
+        universe.tick();
+        let since = universe.revision();
+        universe.tick();
+        // The blinker keeps blinking in its tile, and nothing else changes
+        assert_eq!(changed(&universe, since), [(Coord::new(0, 0), Coord::new(16, 16))]);
+    }
+
+    #[test]
+    fn clones_go_their_own_way() {
+        let universe = Universe::new(16, 16);
+        let copy = universe.clone();
+        assert_eq!(changed(&copy, universe.revision()).len(), 1);
+        assert!(changed(&universe, universe.revision()).is_empty());
+    }
+}
@@ -0,0 +1,281 @@
+use std::fmt;
+
+use crate::{Cell, Coord, Pattern, Rule, RulePreset, Universe};
+
+/// Longest line `to_rle` writes, as the format asks
+const LINE_LENGTH: usize = 70;
+/// Longest side and most cells a pattern read from a file may have, enough for a 10000×10000 universe
+pub(crate) const MAX_SIDE: usize = 1 << 16;
+pub(crate) const MAX_CELLS: usize = 1 << 27;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRleError {
+    /// There's no `x = ..., y = ...` line before the cells
+    MissingHeader,
+    InvalidHeader(String),
+    /// The header's rule is neither one `Rule` parses nor the name of a preset
+    UnsupportedRule(String),
+    /// A character that isn't a run count, a state, `$` nor `!`
+    InvalidCharacter(char),
+    /// Multi-state files go up to state 255
+    InvalidState,
+    /// The header asks for a pattern bigger than `MAX_SIDE` or `MAX_CELLS`
+    TooLarge,
+    /// A run or cell goes past the header's width or height
+    OutOfBounds,
+}
+
+impl Pattern {
+    /// Reads a pattern in the Run Length Encoded format, along with the rule its header names if any. Lines
+    /// starting with `#` are comments, and are skipped. Two-state files take `b` as dead and any other lowercase
+    /// letter as alive, and files under a multi-state rule go by `.` for dead and `A` to `X`, maybe after one of
+    /// `p` to `y`, for the rest (see `Cell::from_state`, conductors going by `Rule::conductor_state`). Runs and
+    /// cells must fit in the header's width and height.
+    pub fn from_rle(s: &str) -> Result<(Pattern, Option<Rule>), ParseRleError> {
+        let mut lines = s.lines().map(str::trim).filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let header = lines.next().ok_or(ParseRleError::MissingHeader)?;
+        let (width, height, rule) = parse_header(header)?;
+        if width.max(height) > MAX_SIDE || width * height > MAX_CELLS { return Err(ParseRleError::TooLarge); }
+        let conductor = rule.unwrap_or_default().conductor_state();
+        let multi_state = rule.is_some_and(|rule| rule.states() > 2);
+
+        let mut pattern = Pattern::new(height, width);
+        let (mut row, mut col) = (0usize, 0usize);
+        let mut run = None::<usize>;
+        let mut prefix = None;
+        'body: for line in lines {
+            for c in line.chars() {
+                let count = run.unwrap_or(1);
+                let state = match c {
+                    _ if c.is_whitespace()                        => continue,
+                    '0'..='9' if prefix.is_none()                 => {
+                        let digit = c as usize - '0' as usize;
+                        let longer = run.unwrap_or(0).checked_mul(10).and_then(|run| run.checked_add(digit));
+                        run = Some(longer.ok_or(ParseRleError::OutOfBounds)?);
+                        continue;
+                    }
+                    '!' if prefix.is_none()                       => break 'body,
+                    '$' if prefix.is_none()                       => {
+                        (row, col, run) = (row.checked_add(count).ok_or(ParseRleError::OutOfBounds)?, 0, None);
+                        continue;
+                    }
+                    'p'..='y' if multi_state && prefix.is_none()  => {
+                        prefix = Some(c as usize - 'p' as usize + 1);
+                        continue;
+                    }
+                    'A'..='X' if multi_state                      => {
+                        prefix.take().unwrap_or(0) * 24 + (c as usize - 'A' as usize + 1)
+                    }
+                    _ if prefix.is_some()                         => return Err(ParseRleError::InvalidCharacter(c)),
+                    '.' | 'b'                                     => 0,
+                    _ if c.is_ascii_lowercase()                   => 1,
+                    _                                             => return Err(ParseRleError::InvalidCharacter(c)),
+                };
+                let state = u8::try_from(state).map_err(|_| ParseRleError::InvalidState)?;
+                let end = col.checked_add(count).filter(|&end| end <= width).ok_or(ParseRleError::OutOfBounds)?;
+                if state != 0 && row >= height { return Err(ParseRleError::OutOfBounds); }
+                if state != 0 {
+                    let cell = Cell::from_state(state as usize, conductor);
+                    for col in col..end { pattern.set(Coord::new(row, col), cell); }
+                }
+                (col, run) = (end, None);
+            }
+        }
+        Ok((pattern, rule))
+    }
+
+    /// Writes the pattern in the Run Length Encoded format, naming `rule` in the header if given. Patterns with
+    /// nothing but dead and live cells use `b` and `o`, and any others the multi-state letters `from_rle` reads.
+    /// Dead cells ending a row and empty rows at the bottom are left out.
+    pub fn to_rle(&self, rule: Option<Rule>) -> String {
+        let (height, width) = (self.get_height(), self.get_width());
+        let mut out = format!("x = {width}, y = {height}");
+        if let Some(rule) = rule { out.push_str(&format!(", rule = {rule}")); }
+        out.push('\n');
+
+        let conductor = rule.unwrap_or_default().conductor_state();
+        let states = self.iter_cells().map(|(_, cell)| cell.state(conductor)).collect::<Vec<_>>();
+        let multi_state = states.iter().any(|&state| state > 1);
+        let tag = |state: usize| match (multi_state, state) {
+            (false, 0) => "b".to_owned(),
+            (false, _) => "o".to_owned(),
+            (true, 0)  => ".".to_owned(),
+            (true, s)  => {
+                let letter = char::from(b'A' + ((s - 1) % 24) as u8);
+                match (s - 1) / 24 {
+                    0      => letter.to_string(),
+                    prefix => format!("{}{letter}", char::from(b'p' + prefix as u8 - 1)),
+                }
+            }
+        };
+        let token = |count: usize, tag: &str| if count > 1 { format!("{count}{tag}") } else { tag.to_owned() };
+
+        let mut tokens = Vec::new();
+        let mut row_ends = 0;
+        for row in states.chunks(width.max(1)) {
+            let mut runs = Vec::<(usize, usize)>::new();
+            for &state in row {
+                match runs.last_mut() {
+                    Some((run, count)) if *run == state => *count += 1,
+                    _                                   => runs.push((state, 1)),
+                }
+            }
+            if runs.last().is_some_and(|&(state, _)| state == 0) { runs.pop(); }
+            if !runs.is_empty() {
+                if row_ends > 0 { tokens.push(token(row_ends, "$")); }
+                tokens.extend(runs.iter().map(|&(state, count)| token(count, &tag(state))));
+                row_ends = 0;
+            }
+            row_ends += 1;
+        }
+        tokens.push("!".to_owned());
+
+        // Runs are never split across lines
+        let mut line = String::new();
+        for token in tokens {
+            if line.len() + token.len() > LINE_LENGTH {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            line.push_str(&token);
+        }
+        out.push_str(&line);
+        out.push('\n');
+        out
+    }
+}
+
+impl Universe {
+    /// The `height`×`width` region starting at `top_left` in the Run Length Encoded format, naming the rule
+    pub fn export_rle(&self, top_left: Coord, height: usize, width: usize) -> String {
+        Pattern::from(&self.extract(top_left, height, width)).to_rle(Some(self.rule))
+    }
+}
+
+/// Width, height and rule out of `x = 3, y = 3, rule = B3/S23`. The rule goes last, since it may have commas of
+/// its own, and Golly's bounded grid suffix (`:T40,40`) is left out of it.
+fn parse_header(header: &str) -> Result<(usize, usize, Option<Rule>), ParseRleError> {
+    let invalid = || ParseRleError::InvalidHeader(header.to_owned());
+    let (dimensions, rule) = match header.find("rule") {
+        Some(i) => (header[..i].trim_end().trim_end_matches(','), Some(&header[i + "rule".len()..])),
+        None    => (header, None),
+    };
+
+    let (mut width, mut height) = (None, None);
+    for field in dimensions.split(',') {
+        let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+        let value = value.trim().parse::<usize>().map_err(|_| invalid())?;
+        match key.trim() {
+            "x" => width = Some(value),
+            "y" => height = Some(value),
+            _   => return Err(invalid()),
+        }
+    }
+    let rule = match rule {
+        Some(rule) => {
+            let rule = rule.trim_start().strip_prefix('=').ok_or_else(invalid)?.trim();
+            let name = rule.split(':').next().unwrap_or_default();
+            let parsed = name.parse::<Rule>().ok().or_else(|| RulePreset::from_name(name).map(|p| p.rule()));
+            Some(parsed.ok_or_else(|| ParseRleError::UnsupportedRule(rule.to_owned()))?)
+        }
+        None       => None,
+    };
+    match (width, height) {
+        (Some(width), Some(height)) => Ok((width, height, rule)),
+        _                           => Err(invalid()),
+    }
+}
+
+impl fmt::Display for ParseRleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRleError::MissingHeader         => write!(f, "no x = ..., y = ... header found"),
+            ParseRleError::InvalidHeader(header) => write!(f, "couldn't make sense of the header {header:?}"),
+            ParseRleError::UnsupportedRule(rule) => write!(f, "the rule {rule} isn't supported"),
+            ParseRleError::InvalidCharacter(c)   => write!(f, "unexpected character {c:?}"),
+            ParseRleError::InvalidState          => write!(f, "states go up to 255"),
+            ParseRleError::TooLarge              => write!(f, "the pattern is too large"),
+            ParseRleError::OutOfBounds           => write!(f, "the cells don't fit in the header's x and y"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Result<Pattern, ParseRleError> { Pattern::from_rle(s).map(|(pattern, _)| pattern) }
+
+    #[test]
+    fn reads_a_glider() {
+        let glider = parse("x = 3, y = 3\nbo$2bo$3o!").unwrap();
+        assert_eq!((glider.get_height(), glider.get_width()), (3, 3));
+        assert_eq!(glider.population(), 5);
+    }
+
+    #[test]
+    fn round_trips_through_to_rle() {
+        let glider = parse("x = 3, y = 3, rule = B3/S23\nbo$2bo$3o!").unwrap();
+        assert_eq!(parse(&glider.to_rle(Some(Rule::CONWAY))).unwrap(), glider);
+    }
+
+    #[test]
+    fn rejects_overflowing_run_counts() {
+        assert_eq!(parse("x = 3, y = 3\n99999999999999999999999o!"), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse(&format!("x = 3, y = 3\n{}$o!", usize::MAX)), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse(&format!("x = 3, y = 3\n$$$$$${}$o!", usize::MAX - 2)), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse(&format!("x = 3, y = 3\nbo{}b!", usize::MAX)), Err(ParseRleError::OutOfBounds));
+    }
+
+    #[test]
+    fn rejects_runs_past_the_width() {
+        assert_eq!(parse("x = 3, y = 3\n4o!"), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse("x = 3, y = 3\nb3o!"), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse("x = 3, y = 3\n2000000000o!"), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse("x = 3, y = 3\n4b!"), Err(ParseRleError::OutOfBounds));
+    }
+
+    #[test]
+    fn rejects_cells_past_the_height() {
+        assert_eq!(parse("x = 3, y = 3\no$o$o$o!"), Err(ParseRleError::OutOfBounds));
+        assert_eq!(parse("x = 3, y = 3\n3$o!"), Err(ParseRleError::OutOfBounds));
+        // Going down past the last row is fine as long as nothing gets drawn there
+        assert_eq!(parse("x = 1, y = 1\no5$!").unwrap().population(), 1);
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        assert_eq!(parse("x = 200000, y = 200000\no!"), Err(ParseRleError::TooLarge));
+        assert_eq!(parse("x = 100000, y = 1\no!"), Err(ParseRleError::TooLarge));
+        assert_eq!(parse(&format!("x = {}, y = 0\n!", usize::MAX)), Err(ParseRleError::TooLarge));
+        assert_eq!(parse("x = 50000, y = 50000\no!"), Err(ParseRleError::TooLarge));
+    }
+
+    #[test]
+    fn uppercase_needs_a_multi_state_rule() {
+        assert_eq!(parse("x = 3, y = 1\nbAo!"), Err(ParseRleError::InvalidCharacter('A')));
+        assert_eq!(parse("x = 3, y = 1, rule = B3/S23\nAB!"), Err(ParseRleError::InvalidCharacter('A')));
+
+        let (pattern, rule) = Pattern::from_rle("x = 3, y = 1, rule = Wireworld\nA.C!").unwrap();
+        assert_eq!(rule, Some(Rule::WIREWORLD));
+        assert_eq!(pattern.get(Coord::new(0, 0)), Cell::Alive);
+        assert_eq!(pattern.get(Coord::new(0, 2)), Cell::Conductor);
+    }
+
+    #[test]
+    fn two_state_files_take_prefix_letters_as_alive() {
+        assert_eq!(parse("x = 2, y = 1\nxy!").unwrap().population(), 2);
+    }
+
+    #[test]
+    fn tells_conductors_from_decaying_cells() {
+        let rule: Rule = "B2/S345/C4".parse().unwrap();
+        let mut pattern = Pattern::new(1, 3);
+        pattern.set(Coord::new(0, 0), Cell::Dying(2));
+        pattern.set(Coord::new(0, 2), Cell::Conductor);
+        assert_eq!(Pattern::from_rle(&pattern.to_rle(Some(rule))).unwrap(), (pattern, Some(rule)));
+    }
+}
@@ -0,0 +1,705 @@
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+use crate::{Cell, Neighborhood};
+
+/// Life-like rule, in B/S notation: the neighbor counts on which dead cells are born and live ones survive
+///
+/// With more than 2 `states` it's a Generations rule: instead of dying outright, live cells go through
+/// `states - 2` decaying states (which can't be born into nor count as neighbors) before turning dead.
+/// The neighbor counts are over the rule's `neighborhood`, which is part of the rule as in `B2/S34H` or `B1/S1V`.
+///
+/// Isotropic non-totalistic rules, written in Hensel notation as in `B2-a/S12`, go further and tell apart the
+/// ways the 8 surrounding cells can be arranged. Only `Universe` does so: the other engines take such a rule
+/// by the counts on which every arrangement qualifies.
+///
+/// Stochastic rules give some conditions a chance of holding, e.g. births on 3 neighbors only half of the time.
+/// Chances need a source of randomness (see `Rule::next_rolled`) and don't show up in the notation.
+///
+/// Cyclic rules (see `Rule::cyclic`) have nothing to do with births and survivals, and only `Universe` runs them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule {
+    birth: Counts,
+    survival: Counts,
+    states: u8,
+    neighborhood: Neighborhood,
+    wireworld: bool,
+    /// Birth and survival configurations of non-totalistic rules
+    configurations: Option<(Configurations, Configurations)>,
+    /// Birth and survival chances of stochastic rules
+    chances: Option<(Chances, Chances)>,
+    /// Neighbors in the next state it takes for a cell to advance, in cyclic rules
+    cyclic: Option<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRuleError {
+    /// The rule must have one `/` separating birth and survival, and optionally another before the state count
+    MissingSeparator,
+    /// Generations rules need at least 2 states
+    InvalidStateCount,
+    /// A character that isn't a neighbor count (0-8) nor a `B`/`S` prefix
+    InvalidCharacter(char),
+    /// Larger than Life radii go from 1 to `Rule::MAX_RADIUS`
+    InvalidRadius,
+}
+
+/// Bitset over neighbor counts (bit `n` set means `n` neighbors qualifies), big enough for the widest
+/// Larger than Life neighborhood
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Counts([u64; 8]);
+
+impl Counts {
+    const EMPTY: Counts = Counts([0; 8]);
+
+    fn insert(&mut self, n: u16) {
+        if let Some(word) = self.0.get_mut(n as usize / 64) { *word |= 1 << (n % 64); }
+    }
+
+    fn remove(&mut self, n: u16) {
+        if let Some(word) = self.0.get_mut(n as usize / 64) { *word &= !(1 << (n % 64)); }
+    }
+
+    fn contains(&self, n: u16) -> bool { self.0.get(n as usize / 64).is_some_and(|word| word >> (n % 64) & 1 == 1) }
+
+    /// Runs of consecutive counts, as (first, last)
+    fn ranges(&self) -> Vec<(u16, u16)> {
+        let mut ranges: Vec<(u16, u16)> = Vec::new();
+        for n in (0..512).filter(|&n| self.contains(n)) {
+            match ranges.last_mut() {
+                Some((_, last)) if *last + 1 == n => *last = n,
+                _                                 => ranges.push((n, n)),
+            }
+        }
+        ranges
+    }
+}
+
+/// Bitset over the 256 configurations of live cells around a cell (see `Rule::next_configuration`)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Configurations([u64; 4]);
+
+impl Configurations {
+    const EMPTY: Configurations = Configurations([0; 4]);
+
+    fn insert(&mut self, configuration: u8)         { self.0[configuration as usize / 64] |= 1 << (configuration % 64) }
+    fn contains(&self, configuration: u8) -> bool   { self.0[configuration as usize / 64] >> (configuration % 64) & 1 == 1 }
+
+    /// Whether every configuration in `others` is in this one too
+    fn includes(&self, mut others: impl Iterator<Item = u8>) -> bool { others.all(|c| self.contains(c)) }
+}
+
+// Bits of a configuration, one per neighbor in `Neighborhood::Moore` order
+const NW: u8 = 1 << 0;
+const N: u8  = 1 << 1;
+const NE: u8 = 1 << 2;
+const W: u8  = 1 << 3;
+const E: u8  = 1 << 4;
+const SW: u8 = 1 << 5;
+const S: u8  = 1 << 6;
+const SE: u8 = 1 << 7;
+
+/// Hensel's letters for 1 to 4 live neighbors, each with one of the configurations it stands for; the rest
+/// are its rotations and reflections. 5 to 7 neighbors reuse the letters of 3 to 1, for their complements.
+const HENSEL: [&[(char, u8)]; 4] = [
+    &[('c', NE), ('e', N)],
+    &[('c', NE | SE), ('e', N | E), ('k', N | SE), ('a', N | NE), ('i', N | S), ('n', NE | SW)],
+    &[('c', NE | SE | SW), ('e', N | E | W), ('k', N | E | SW), ('a', N | W | NW), ('i', N | NE | NW),
+      ('n', N | E | SE), ('y', N | SE | SW), ('q', N | SE | NW), ('j', N | NE | SE), ('r', N | SE | S)],
+    &[('c', NW | NE | SW | SE), ('e', N | E | S | W), ('k', NW | N | W | SE), ('a', NW | N | NE | E),
+      ('i', NW | N | NE | SW), ('n', NW | E | S | W), ('y', NW | NE | E | S), ('q', NW | NE | E | SW),
+      ('j', NW | NE | W | E), ('r', NW | N | W | S), ('t', NW | N | NE | S), ('w', NW | N | E | SE),
+      ('z', NW | N | S | SE)],
+];
+
+/// Hensel letters valid for `count` live neighbors, with a configuration of each
+fn hensel_letters(count: u8) -> impl Iterator<Item = (char, u8)> {
+    let (letters, complement) = match count {
+        1..=4 => (HENSEL[count as usize - 1], false),
+        5..=7 => (HENSEL[7 - count as usize], true),
+        _     => (&[][..], false),
+    };
+    letters.iter().map(move |&(letter, c)| (letter, if complement { !c } else { c }))
+}
+
+/// Every rotation and reflection of `configuration`
+fn symmetries(configuration: u8) -> impl Iterator<Item = u8> {
+    let offsets = Neighborhood::Moore.offsets(0);
+    let bit = |offset: (isize, isize)| 1 << offsets.iter().position(|&o| o == offset).unwrap_or(0);
+    (0..8).map(move |symmetry| {
+        let transform = |(mut dy, mut dx): (isize, isize)| {
+            if symmetry >= 4 { dx = -dx; }
+            for _ in 0..symmetry % 4 { (dy, dx) = (dx, -dy); }
+            (dy, dx)
+        };
+        (0..8).filter(|i| configuration >> i & 1 == 1).map(|i| bit(transform(offsets[i]))).fold(0, |acc, b| acc | b)
+    })
+}
+
+/// Every configuration with `count` live neighbors
+fn with_count(count: u8) -> impl Iterator<Item = u8> { (0..=255).filter(move |c: &u8| c.count_ones() == count as u32) }
+
+/// Parses a birth or survival part in Hensel notation: neighbor counts, each optionally followed by the letters
+/// of the configurations it's restricted to, or by `-` and the letters of those it excludes
+fn parse_configurations(part: &str) -> Result<Configurations, ParseRuleError> {
+    let mut configurations = Configurations::EMPTY;
+    let mut chars = part.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let count = c.to_digit(10).filter(|&d| d <= 8).ok_or(ParseRuleError::InvalidCharacter(c))? as u8;
+        let exclude = chars.next_if_eq(&'-').is_some();
+        let mut letters = Configurations::EMPTY;
+        let mut any = false;
+
+        while let Some(letter) = chars.next_if(char::is_ascii_alphabetic) {
+            let Some((_, configuration)) = hensel_letters(count).find(|&(l, _)| l == letter.to_ascii_lowercase()) else {
+                return Err(ParseRuleError::InvalidCharacter(letter));
+            };
+            for c in symmetries(configuration) { letters.insert(c); }
+            any = true;
+        }
+        if exclude && !any { return Err(ParseRuleError::InvalidCharacter('-')); }
+
+        for c in with_count(count).filter(|&c| !any || letters.contains(c) != exclude) { configurations.insert(c); }
+    }
+    Ok(configurations)
+}
+
+/// Writes configurations back in Hensel notation, listing whichever of the letters in or out is shorter
+fn hensel(configurations: &Configurations) -> String {
+    let mut out = String::new();
+    for count in 0..=8 {
+        if !with_count(count).any(|c| configurations.contains(c)) { continue; }
+        out.push_str(&count.to_string());
+        if configurations.includes(with_count(count)) { continue; }
+
+        let (included, excluded): (Vec<_>, Vec<_>) = hensel_letters(count)
+            .partition(|&(_, c)| configurations.includes(symmetries(c)));
+        match included.len() <= excluded.len() {
+            true  => out.extend(included.iter().map(|&(letter, _)| letter)),
+            false => { out.push('-'); out.extend(excluded.iter().map(|&(letter, _)| letter)); }
+        }
+    }
+    out
+}
+
+/// Chance of a condition holding for each neighbor count up to 8, out of `Chances::CERTAIN`. Past 8 they're certain.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Chances([u32; 9]);
+
+impl Chances {
+    const CERTAIN: u32 = 1 << 16;
+
+    /// Whether the condition on `neighbors` holds, `roll` being uniformly random
+    fn holds(&self, neighbors: u16, roll: u16) -> bool {
+        self.0.get(neighbors as usize).is_none_or(|&chance| (roll as u32) < chance)
+    }
+}
+
+impl FromIterator<u16> for Counts {
+    fn from_iter<I: IntoIterator<Item = u16>>(iter: I) -> Self {
+        let mut counts = Counts::EMPTY;
+        for n in iter { counts.insert(n); }
+        counts
+    }
+}
+
+impl Rule {
+    /// Conway's Game of Life, B3/S23
+    pub const CONWAY: Rule = Rule {
+        birth: Counts([1 << 3, 0, 0, 0, 0, 0, 0, 0]),
+        survival: Counts([1 << 2 | 1 << 3, 0, 0, 0, 0, 0, 0, 0]),
+        states: 2,
+        neighborhood: Neighborhood::Moore,
+        wireworld: false,
+        configurations: None,
+        chances: None,
+        cyclic: None,
+    };
+
+    /// Wireworld, where live cells are electron heads: they become tails (`Dying(1)`) and then conductor
+    /// again, while conductor next to 1 or 2 heads turns into a head. Empty cells stay empty.
+    pub const WIREWORLD: Rule = Rule {
+        birth: Counts([1 << 1 | 1 << 2, 0, 0, 0, 0, 0, 0, 0]),
+        survival: Counts::EMPTY,
+        states: 3,
+        neighborhood: Neighborhood::Moore,
+        wireworld: true,
+        configurations: None,
+        chances: None,
+        cyclic: None,
+    };
+
+    /// Widest Larger than Life neighborhood supported
+    pub const MAX_RADIUS: u8 = 10;
+
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let counts = |counts: &[u8]| counts.iter().filter(|&&n| n <= 8).map(|&n| n as u16).collect();
+        Self { birth: counts(birth), survival: counts(survival), states: 2, neighborhood: Neighborhood::Moore,
+               wireworld: false, configurations: None, chances: None, cyclic: None }
+    }
+
+    /// Rule over the Moore neighborhood going by configurations, which only stays non-totalistic if some count
+    /// has configurations both in and out
+    fn from_configurations(birth: Configurations, survival: Configurations) -> Self {
+        let counts = |configurations: &Configurations| (0..=8).filter(|&n| configurations.includes(with_count(n))).collect::<Vec<_>>();
+        let rule = Rule::new(&counts(&birth), &counts(&survival));
+        let partial = |configurations: &Configurations| (0..=8).any(|n| {
+            with_count(n).any(|c| configurations.contains(c)) && !configurations.includes(with_count(n))
+        });
+        match partial(&birth) || partial(&survival) {
+            true  => Self { configurations: Some((birth, survival)), ..rule },
+            false => rule,
+        }
+    }
+
+    /// Larger than Life rule over the square of cells up to `radius` away, with births and survivals on ranges
+    /// of neighbor counts. Written down as `R5,C0,M1,S34..58,B34..45,NM` ("Bugs").
+    pub fn larger_than_life(radius: u8, include_center: bool, birth: RangeInclusive<u16>, survival: RangeInclusive<u16>) -> Self {
+        let radius = radius.clamp(1, Rule::MAX_RADIUS);
+        Self { birth: birth.collect(), survival: survival.collect(), states: 2,
+               neighborhood: Neighborhood::Box { radius, include_center }, wireworld: false, configurations: None,
+               chances: None, cyclic: None }
+    }
+
+    /// Cyclic automaton with `states` states (at least 2) going round in order, the last one followed by the
+    /// first: a cell advances to the next state once `threshold` of its neighbors are in it. Counts over the von
+    /// Neumann neighborhood, as in Griffeath's spirals (14 states, threshold 1), unless given another one.
+    /// States are numbered dead (0), alive (1) and then `Dying(1)` on.
+    pub fn cyclic(states: u8, threshold: u8) -> Self {
+        Self { states: states.max(2), neighborhood: Neighborhood::VonNeumann, cyclic: Some(threshold),
+               ..Rule::new(&[], &[]) }
+    }
+
+    /// Turns the rule into a Generations one with `states` states in total (at least 2)
+    pub fn with_states(self, states: u8) -> Self { Self { states: states.max(2), ..self } }
+
+    /// Same rule, counting neighbors over `neighborhood` instead. Counts past its size can never happen.
+    /// Configurations only make sense around the Moore neighborhood, so anywhere else the rule goes totalistic.
+    pub fn with_neighborhood(self, neighborhood: Neighborhood) -> Self {
+        let configurations = self.configurations.filter(|_| neighborhood == Neighborhood::Moore);
+        Self { neighborhood, configurations, ..self }
+    }
+
+    pub fn states(&self) -> u8                   { self.states }
+    /// Conductors come right after the rule's own states, so they're 3 under Wireworld like Golly's copper
+    pub fn conductor_state(&self) -> usize       { self.states as usize }
+    pub fn neighborhood(&self) -> Neighborhood   { self.neighborhood }
+    pub fn is_totalistic(&self) -> bool          { self.configurations.is_none() }
+    pub fn is_stochastic(&self) -> bool          { self.chances.is_some() }
+    pub fn is_cyclic(&self) -> bool              { self.cyclic.is_some() }
+    /// Neighbors in the next state a cell needs to advance, for cyclic rules
+    pub fn cyclic_threshold(&self) -> Option<u8> { self.cyclic }
+
+    /// State after `current` in a cyclic rule
+    pub fn successor(&self, current: Cell) -> Cell {
+        let conductor = self.conductor_state();
+        Cell::from_state((current.state(conductor) + 1) % self.states as usize, conductor)
+    }
+
+    /// State of a cell on the next generation of a cyclic rule, given how many of its neighbors are in the
+    /// state after its own. Cells stay as they are under any other rule.
+    pub fn next_cyclic(&self, current: Cell, successors: u16) -> Cell {
+        match self.cyclic {
+            Some(threshold) if successors >= threshold as u16 => self.successor(current),
+            _                                                 => current,
+        }
+    }
+
+    /// Makes births on `neighbors` (up to 8) happen with probability `chance`, 0 taking them out of the rule
+    pub fn with_birth_chance(self, neighbors: u16, chance: f32) -> Self { self.with_chance(neighbors, chance, true) }
+
+    /// Makes survivals on `neighbors` (up to 8) happen with probability `chance`, 0 taking them out of the rule
+    pub fn with_survival_chance(self, neighbors: u16, chance: f32) -> Self { self.with_chance(neighbors, chance, false) }
+
+    fn with_chance(mut self, neighbors: u16, chance: f32, birth: bool) -> Self {
+        if neighbors > 8 { return self; }
+        let chance = (chance.clamp(0.0, 1.0) * Chances::CERTAIN as f32) as u32;
+        let counts = if birth { &mut self.birth } else { &mut self.survival };
+        match chance {
+            0 => counts.remove(neighbors),
+            _ => counts.insert(neighbors),
+        }
+        let (births, survivals) = self.chances.get_or_insert((Chances([Chances::CERTAIN; 9]), Chances([Chances::CERTAIN; 9])));
+        (if birth { births } else { survivals }).0[neighbors as usize] = chance;
+        self
+    }
+
+    /// Probability of births on `neighbors`
+    pub fn birth_chance(&self, neighbors: u16) -> f32 { self.chance(neighbors, true) }
+
+    /// Probability of survivals on `neighbors`
+    pub fn survival_chance(&self, neighbors: u16) -> f32 { self.chance(neighbors, false) }
+
+    fn chance(&self, neighbors: u16, birth: bool) -> f32 {
+        let on = if birth { self.births_on(neighbors) } else { self.survives_on(neighbors) };
+        let chances = self.chances.map(|(births, survivals)| if birth { births } else { survivals });
+        match (on, chances.and_then(|c| c.0.get(neighbors as usize).copied())) {
+            (false, _)          => 0.0,
+            (true, None)        => 1.0,
+            (true, Some(c))     => c as f32 / Chances::CERTAIN as f32,
+        }
+    }
+
+    /// What empty space turns into by `generation`. Rules with B0 bring it to life on the first generation:
+    /// then it stays alive if they also have survival on every neighbor (as in B0/S8), and flips back and forth
+    /// every generation otherwise.
+    pub fn background(&self, generation: u64) -> Cell {
+        let strobing = !self.survives_on(self.neighborhood.size());
+        match self.births_on(0) && generation > 0 && (!strobing || !generation.is_multiple_of(2)) {
+            true  => Cell::Alive,
+            false => Cell::Dead,
+        }
+    }
+
+    /// Rule without B0 that takes cells from `generation` to the next one the way this rule does, only with
+    /// cells kept relative to the `background` (alive where they differ from it). With no B0 that's the rule
+    /// itself, as well as for Generations, non-totalistic or stochastic rules, which don't get translated.
+    pub fn phased(&self, generation: u64) -> Rule {
+        let simple = self.states == 2 && self.configurations.is_none() && self.chances.is_none() && self.cyclic.is_none();
+        if !self.births_on(0) || !simple { return *self; }
+        let size = self.neighborhood.size();
+        let (born, survives) = (|n| self.births_on(n), |n| self.survives_on(n));
+        let counts = |holds: &dyn Fn(u16) -> bool| (0..=size).filter(|&n| holds(n)).collect();
+
+        let (birth, survival) = match (self.background(generation), self.background(generation + 1)) {
+            // From the background as is to it inverted
+            (Cell::Dead, _) => (counts(&|n| !born(n)), counts(&|n| !survives(n))),
+            // From inverted to as is
+            (_, Cell::Dead) => (counts(&|n| survives(size - n)), counts(&|n| born(size - n))),
+            // From inverted to inverted
+            _               => (counts(&|n| !survives(size - n)), counts(&|n| !born(size - n))),
+        };
+        Rule { birth, survival, ..*self }
+    }
+
+    pub fn births_on(&self, neighbors: u16) -> bool   { self.birth.contains(neighbors) }
+    pub fn survives_on(&self, neighbors: u16) -> bool { self.survival.contains(neighbors) }
+
+    /// State of a cell on the next generation, given its current state and live neighbor count. Conditions
+    /// that only hold by chance are taken to hold.
+    pub fn next(&self, current: Cell, neighbors: u16) -> Cell { self.next_rolled(current, neighbors, 0) }
+
+    /// Same as `next`, with `roll` a uniformly random number deciding the conditions that hold by chance
+    pub fn next_rolled(&self, current: Cell, neighbors: u16, roll: u16) -> Cell {
+        let (born, survives) = self.rolled(neighbors, roll);
+        self.advance(current, self.births_on(neighbors) && born, self.survives_on(neighbors) && survives)
+    }
+
+    /// Same as `next_rolled`, but given which of the 8 surrounding cells are alive: one bit per neighbor, from
+    /// the lowest in the order of `Neighborhood::Moore` (up-left, up, up-right, left, right, down-left, down,
+    /// down-right). Totalistic rules only look at how many there are.
+    pub fn next_configuration(&self, current: Cell, configuration: u8, roll: u16) -> Cell {
+        let neighbors = configuration.count_ones() as u16;
+        match self.configurations {
+            Some((birth, survival)) => {
+                let (born, survives) = self.rolled(neighbors, roll);
+                self.advance(current, birth.contains(configuration) && born, survival.contains(configuration) && survives)
+            }
+            None => self.next_rolled(current, neighbors, roll),
+        }
+    }
+
+    /// Whether the chances of birth and survival on `neighbors` come good for `roll`
+    fn rolled(&self, neighbors: u16, roll: u16) -> (bool, bool) {
+        match self.chances {
+            Some((births, survivals)) => (births.holds(neighbors, roll), survivals.holds(neighbors, roll)),
+            None                      => (true, true),
+        }
+    }
+
+    fn advance(&self, current: Cell, born: bool, survives: bool) -> Cell {
+        match current {
+            Cell::Alive if survives                      => Cell::Alive,
+            Cell::Alive                                  => self.decay(0),
+            Cell::Dying(k)                               => self.decay(k),
+            Cell::Dead if !self.wireworld && born        => Cell::Alive,
+            Cell::Dead                                   => Cell::Dead,
+            Cell::Conductor if self.wireworld && born    => Cell::Alive,
+            Cell::Conductor                              => Cell::Conductor,
+        }
+    }
+
+    /// What comes after the `k`-th decaying state (0 being alive)
+    fn decay(&self, k: u8) -> Cell {
+        match k + 2 < self.states {
+            true                    => Cell::Dying(k + 1),
+            false if self.wireworld => Cell::Conductor,
+            false                   => Cell::Dead,
+        }
+    }
+}
+
+/// Transition function plugged in with `Universe::set_custom_rule`, from a cell and its live neighbors
+#[derive(Clone)]
+pub(crate) struct CustomRule(pub(crate) std::sync::Arc<dyn Fn(Cell, u8) -> Cell + Send + Sync>);
+
+impl fmt::Debug for CustomRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { f.write_str("CustomRule") }
+}
+
+/// Well-known life-like rules, to pick by name instead of B/S notation
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RulePreset {
+    Conway,
+    HighLife,
+    Seeds,
+    DayAndNight,
+    Maze,
+    LifeWithoutDeath,
+    /// Three states: on cells always go dying and then off, and off ones turn on next to exactly 2 on ones
+    BriansBrain,
+    Wireworld,
+    /// Griffeath's cyclic automaton, whose soups settle into spirals
+    Cyclic,
+}
+
+impl RulePreset {
+    pub const ALL: [RulePreset; 9] = [
+        RulePreset::Conway, RulePreset::HighLife, RulePreset::Seeds,
+        RulePreset::DayAndNight, RulePreset::Maze, RulePreset::LifeWithoutDeath,
+        RulePreset::BriansBrain, RulePreset::Wireworld, RulePreset::Cyclic,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RulePreset::Conway           => "Conway's Life",
+            RulePreset::HighLife         => "HighLife",
+            RulePreset::Seeds            => "Seeds",
+            RulePreset::DayAndNight      => "Day & Night",
+            RulePreset::Maze             => "Maze",
+            RulePreset::LifeWithoutDeath => "Life without Death",
+            RulePreset::BriansBrain      => "Brian's Brain",
+            RulePreset::Wireworld        => "Wireworld",
+            RulePreset::Cyclic           => "Cyclic",
+        }
+    }
+
+    pub fn rule(&self) -> Rule {
+        match self {
+            RulePreset::Conway           => Rule::CONWAY,
+            RulePreset::HighLife         => Rule::new(&[3, 6], &[2, 3]),
+            RulePreset::Seeds            => Rule::new(&[2], &[]),
+            RulePreset::DayAndNight      => Rule::new(&[3, 6, 7, 8], &[3, 4, 6, 7, 8]),
+            RulePreset::Maze             => Rule::new(&[3], &[1, 2, 3, 4, 5]),
+            RulePreset::LifeWithoutDeath => Rule::new(&[3], &[0, 1, 2, 3, 4, 5, 6, 7, 8]),
+            RulePreset::BriansBrain      => Rule::new(&[2], &[]).with_states(3),
+            RulePreset::Wireworld        => Rule::WIREWORLD,
+            RulePreset::Cyclic           => Rule::cyclic(14, 1),
+        }
+    }
+
+    /// Looks a preset up by name, ignoring case, spaces and punctuation (`"day and night"`, `"highlife"`...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        let simplify = |s: &str| s.replace('&', "and").chars().filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase()).collect::<String>();
+        let name = simplify(name);
+        let alias = |p: RulePreset| p == RulePreset::Conway && (name == "life" || name == "conway");
+        RulePreset::ALL.into_iter().find(|&p| simplify(p.name()) == name || alias(p))
+    }
+
+    /// The preset `rule` is, if any
+    pub fn of(rule: Rule) -> Option<Self> { RulePreset::ALL.into_iter().find(|p| p.rule() == rule) }
+}
+
+impl fmt::Display for RulePreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result { write!(f, "{}", self.name()) }
+}
+
+impl Default for Rule {
+    fn default() -> Self { Rule::CONWAY }
+}
+
+impl FromStr for Rule {
+    type Err = ParseRuleError;
+
+    /// Accepts `B3/S23` (in any case and order, e.g. `s23/b3`) as well as the older `23/3` (survival/birth) form.
+    /// Generations rules add the state count, as in `B2/S345/C4` or `345/2/4`, and a trailing `H` or `V` picks
+    /// the hexagonal or von Neumann neighborhood. Counts can be narrowed down with Hensel letters, as in `B2-a/S12`
+    /// or `B3/S2-i34q`. Larger than Life rules go as in `R5,C0,M1,S34..58,B34..45,NM`, cyclic ones as in
+    /// `Cyclic 14/1`, and `Wireworld` is just that.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("wireworld") { return Ok(Rule::WIREWORLD); }
+        if s.get(..6).is_some_and(|prefix| prefix.eq_ignore_ascii_case("cyclic")) { return parse_cyclic(&s[6..]); }
+        if s.starts_with(['R', 'r']) { return parse_larger_than_life(s); }
+        let (s, neighborhood) = match s.char_indices().last() {
+            Some((i, 'H' | 'h')) => (&s[..i], Neighborhood::Hex),
+            Some((i, 'V' | 'v')) => (&s[..i], Neighborhood::VonNeumann),
+            _                    => (s, Neighborhood::Moore),
+        };
+        let parts = s.split('/').collect::<Vec<_>>();
+        let (first, second, states) = match parts[..] {
+            [first, second]         => (first, second, None),
+            [first, second, states] => (first, second, Some(states)),
+            _                       => return Err(ParseRuleError::MissingSeparator),
+        };
+
+        let strip = |part: &str, prefix: char| {
+            part.strip_prefix(prefix).or(part.strip_prefix(prefix.to_ascii_lowercase())).map(str::to_owned)
+        };
+
+        let (birth, survival) = match (strip(first, 'B'), strip(second, 'S'), strip(first, 'S'), strip(second, 'B')) {
+            (Some(b), Some(s), _, _) | (_, _, Some(s), Some(b)) => (b, s),
+            _                                                   => (second.to_owned(), first.to_owned()),
+        };
+
+        let rule = Rule::from_configurations(parse_configurations(&birth)?, parse_configurations(&survival)?)
+            .with_neighborhood(neighborhood);
+        match states {
+            None         => Ok(rule),
+            Some(states) => {
+                let states = strip(states, 'C').unwrap_or(states.to_owned());
+                match states.parse::<u8>() {
+                    Ok(n) if n >= 2 => Ok(rule.with_states(n)),
+                    _               => Err(ParseRuleError::InvalidStateCount),
+                }
+            }
+        }
+    }
+}
+
+/// State count and threshold, as in ` 14/1`, followed by `M`, `H` or `R<radius>` for a neighborhood other than
+/// the von Neumann one
+fn parse_cyclic(s: &str) -> Result<Rule, ParseRuleError> {
+    let s = s.trim();
+    let (numbers, neighborhood) = s.split_at(s.find(|c: char| !c.is_ascii_digit() && c != '/').unwrap_or(s.len()));
+    let (states, threshold) = numbers.split_once('/').ok_or(ParseRuleError::MissingSeparator)?;
+    let states = states.parse::<u8>().ok().filter(|&n| n >= 2).ok_or(ParseRuleError::InvalidStateCount)?;
+    let threshold = threshold.parse::<u8>().map_err(|_| ParseRuleError::MissingSeparator)?;
+    let neighborhood = match neighborhood {
+        ""        => Neighborhood::VonNeumann,
+        "M" | "m" => Neighborhood::Moore,
+        "H" | "h" => Neighborhood::Hex,
+        other     => match other.strip_prefix(['R', 'r']).map(str::parse::<u8>) {
+            Some(Ok(radius @ 1..)) if radius <= Rule::MAX_RADIUS => Neighborhood::Box { radius, include_center: false },
+            Some(_)                                                => return Err(ParseRuleError::InvalidRadius),
+            None => return Err(ParseRuleError::InvalidCharacter(other.chars().next().unwrap_or_default())),
+        },
+    };
+    Ok(Rule::cyclic(states, threshold).with_neighborhood(neighborhood))
+}
+
+/// Comma separated fields: `R` radius, `C` states (0 meaning 2), `M` whether the cell counts itself, `S` and
+/// `B` each followed by ranges (`a..b`, `a-b` or just `a`, more of them after further commas), and `NM`
+fn parse_larger_than_life(s: &str) -> Result<Rule, ParseRuleError> {
+    let (mut radius, mut states, mut include_center) = (1, 2, false);
+    let (mut birth, mut survival) = (Counts::EMPTY, Counts::EMPTY);
+    let mut list = None;
+
+    let number = |digits: &str| -> Result<u16, ParseRuleError> {
+        match digits.chars().find(|c| !c.is_ascii_digit()) {
+            Some(c) => Err(ParseRuleError::InvalidCharacter(c)),
+            None    => digits.parse().map_err(|_| ParseRuleError::MissingSeparator),
+        }
+    };
+
+    for field in s.split(',').map(str::trim) {
+        let Some(first) = field.chars().next() else { return Err(ParseRuleError::MissingSeparator) };
+        let (key, value) = match first.is_ascii_digit() {
+            true  => (None, field),
+            false => (Some(first.to_ascii_uppercase()), &field[first.len_utf8()..]),
+        };
+
+        match key {
+            Some('R') => match number(value)? {
+                r @ 1.. if r <= Rule::MAX_RADIUS as u16 => radius = r as u8,
+                _                                       => return Err(ParseRuleError::InvalidRadius),
+            },
+            Some('C') => states = match number(value).map_err(|_| ParseRuleError::InvalidStateCount)? {
+                0 | 1          => 2,
+                n @ 2..=255    => n as u8,
+                _              => return Err(ParseRuleError::InvalidStateCount),
+            },
+            Some('M') => include_center = number(value)? == 1,
+            Some('N') if value.eq_ignore_ascii_case("M") => {}
+            Some('N') => return Err(ParseRuleError::InvalidCharacter(value.chars().next().unwrap_or('N'))),
+            Some('S') => list = Some(&mut survival),
+            Some('B') => list = Some(&mut birth),
+            Some(c)   => return Err(ParseRuleError::InvalidCharacter(c)),
+            None      => {}
+        }
+
+        let ranged = matches!(key, Some('S' | 'B') | None);
+        if !ranged || value.is_empty() { continue; }
+        let Some(counts) = list.as_deref_mut() else { return Err(ParseRuleError::InvalidCharacter(first)) };
+        let (low, high) = value.split_once("..").or(value.split_once('-')).unwrap_or((value, value));
+        for n in number(low)?..=number(high)? { counts.insert(n); }
+    }
+
+    Ok(Rule { birth, survival, states, neighborhood: Neighborhood::Box { radius, include_center }, wireworld: false,
+              configurations: None, chances: None, cyclic: None })
+}
+
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.wireworld { return write!(f, "Wireworld"); }
+        if let Some(threshold) = self.cyclic {
+            write!(f, "Cyclic {}/{threshold}", self.states)?;
+            return match self.neighborhood {
+                Neighborhood::Moore              => write!(f, "M"),
+                Neighborhood::VonNeumann         => Ok(()),
+                Neighborhood::Hex                => write!(f, "H"),
+                Neighborhood::Box { radius, .. } => write!(f, "R{radius}"),
+            };
+        }
+        if let Neighborhood::Box { radius, include_center } = self.neighborhood {
+            let ranges = |counts: &Counts| counts.ranges().iter()
+                .map(|&(low, high)| if low == high { low.to_string() } else { format!("{low}..{high}") })
+                .collect::<Vec<_>>()
+                .join(",");
+            let states = if self.states > 2 { self.states } else { 0 };
+            return write!(f, "R{radius},C{states},M{},S{},B{},NM", include_center as u8, ranges(&self.survival), ranges(&self.birth));
+        }
+
+        let digits = |counts: &Counts| (0..=8).filter(|&n| counts.contains(n)).map(|n| n.to_string()).collect::<String>();
+        match &self.configurations {
+            Some((birth, survival)) => write!(f, "B{}/S{}", hensel(birth), hensel(survival))?,
+            None                    => write!(f, "B{}/S{}", digits(&self.birth), digits(&self.survival))?,
+        }
+        if self.states > 2 { write!(f, "/C{}", self.states)?; }
+        match self.neighborhood {
+            Neighborhood::Moore      => Ok(()),
+            Neighborhood::VonNeumann => write!(f, "V"),
+            Neighborhood::Hex        => write!(f, "H"),
+            Neighborhood::Box { .. } => unreachable!("written in Larger than Life notation above"),
+        }
+    }
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleError::MissingSeparator     => write!(f, "rule must be of the form B<digits>/S<digits>[/C<states>][H|V]"),
+            ParseRuleError::InvalidStateCount    => write!(f, "the state count must be a number, 2 or more"),
+            ParseRuleError::InvalidCharacter(c)  => write!(f, "unexpected character '{c}' in rule"),
+            ParseRuleError::InvalidRadius        => write!(f, "the radius must go from 1 to {}", Rule::MAX_RADIUS),
+        }
+    }
+}
+
+impl std::error::Error for ParseRuleError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_round_trip_through_their_notation() {
+        for preset in RulePreset::ALL {
+            let rule = preset.rule();
+            assert_eq!(rule.to_string().parse::<Rule>(), Ok(rule), "{preset} written as {rule}");
+        }
+    }
+
+    #[test]
+    fn cyclic_rules_round_trip_over_any_neighborhood() {
+        for neighborhood in [Neighborhood::Moore, Neighborhood::VonNeumann, Neighborhood::Hex,
+                             Neighborhood::Box { radius: 3, include_center: false }] {
+            let rule = Rule::cyclic(5, 3).with_neighborhood(neighborhood);
+            assert_eq!(rule.to_string().parse::<Rule>(), Ok(rule), "written as {rule}");
+        }
+        assert_eq!("cyclic 14/1".parse::<Rule>(), Ok(Rule::cyclic(14, 1)));
+        assert_eq!("Cyclic 1/1".parse::<Rule>(), Err(ParseRuleError::InvalidStateCount));
+        assert_eq!("Cyclic 14".parse::<Rule>(), Err(ParseRuleError::MissingSeparator));
+    }
+}
@@ -0,0 +1,471 @@
+use std::{collections::{HashMap, HashSet}, fmt, str::FromStr};
+
+use crate::{Cell, Neighborhood};
+
+/// Rule loaded from a Golly `.rule` file, out of its `@TABLE` or `@TREE` section, over the Moore or von
+/// Neumann neighborhood and up to 256 states
+///
+/// State 0 is `Cell::Dead`, 1 is `Cell::Alive` and the ones after are `Cell::Dying`, from `Dying(1)` for 2 on.
+/// Golly's `WireWorld` table is the exception, its copper (state 3) being `Cell::Conductor`.
+#[derive(Clone, Debug)]
+pub struct RuleTable {
+    name: String,
+    states: usize,
+    neighborhood: Neighborhood,
+    lookup: Lookup,
+}
+
+#[derive(Clone, Debug)]
+enum Lookup {
+    /// Golly's scheme: for each position (the cell itself, then its neighbors in table order) and state there,
+    /// a bitset over the transitions allowing it. The first transition allowed everywhere wins.
+    Table { masks: Vec<u64>, words: usize, outputs: Vec<u8> },
+    /// Nodes of `states` children each, the last one being the root. Walking down through the states of the
+    /// neighbors in tree order and then of the cell lands on its next state.
+    Tree { nodes: Vec<u32> },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseRuleTableError {
+    /// The file has neither a `@TABLE` nor a `@TREE` section
+    MissingRule,
+    /// A field the rule can't do without, such as `n_states` or `num_nodes`
+    MissingField(&'static str),
+    /// Only the Moore and von Neumann neighborhoods are supported
+    UnsupportedNeighborhood(String),
+    UnsupportedSymmetries(String),
+    /// Rules go from 2 to 256 states
+    InvalidStateCount,
+    /// A line that couldn't be made sense of, counting from 1
+    InvalidLine(usize),
+}
+
+/// Neighbors of the table formats in the order `RuleTable::next` takes them in (that of `Neighborhood::Moore`)
+const TABLE_MOORE: [usize; 8] = [1, 2, 4, 7, 6, 5, 3, 0];
+const TABLE_VON_NEUMANN: [usize; 4] = [1, 4, 6, 3];
+const TREE_MOORE: [usize; 8] = [0, 2, 5, 7, 1, 3, 4, 6];
+const TREE_VON_NEUMANN: [usize; 4] = [1, 3, 4, 6];
+
+impl RuleTable {
+    pub fn get_name(&self) -> &str                 { &self.name }
+    pub fn states(&self) -> usize                  { self.states }
+    pub fn neighborhood(&self) -> Neighborhood     { self.neighborhood }
+
+    /// 3 for Golly's `WireWorld` table, like `Rule::WIREWORLD`'s, and otherwise right after the table's states,
+    /// which it has no say over
+    pub fn conductor_state(&self) -> usize {
+        if self.states == 4 && self.name.eq_ignore_ascii_case("wireworld") { 3 } else { self.states }
+    }
+
+    /// State of a cell on the next generation, given its current state and those of the 8 cells around it in
+    /// `Neighborhood::Moore` order (only the orthogonal ones matter under von Neumann). States the rule doesn't
+    /// have read as 0.
+    pub fn next(&self, current: Cell, neighbors: [Cell; 8]) -> Cell {
+        let conductor = self.conductor_state();
+        let state = |cell: Cell| Some(cell.state(conductor)).filter(|&s| s < self.states).unwrap_or(0);
+        let order: &[usize] = match (&self.lookup, self.neighborhood) {
+            (Lookup::Table { .. }, Neighborhood::Moore) => &TABLE_MOORE,
+            (Lookup::Table { .. }, _)                   => &TABLE_VON_NEUMANN,
+            (Lookup::Tree { .. }, Neighborhood::Moore)  => &TREE_MOORE,
+            (Lookup::Tree { .. }, _)                    => &TREE_VON_NEUMANN,
+        };
+        let center = state(current);
+
+        match &self.lookup {
+            Lookup::Table { masks, words, outputs } => {
+                // The cell itself and up to 8 neighbors, without allocating for every cell
+                let mut offsets = [0; 9];
+                let positions = std::iter::once(center).chain(order.iter().map(|&i| state(neighbors[i])));
+                for (p, s) in positions.enumerate() { offsets[p] = (p * self.states + s) * words; }
+                let offsets = &offsets[..order.len() + 1];
+                for word in 0..*words {
+                    let allowed = offsets.iter().fold(u64::MAX, |acc, &offset| acc & masks[offset + word]);
+                    if allowed != 0 { return Cell::from_state(outputs[word * 64 + allowed.trailing_zeros() as usize] as usize, conductor); }
+                }
+                current
+            }
+            Lookup::Tree { nodes } => {
+                let inputs = order.iter().map(|&i| state(neighbors[i])).chain(std::iter::once(center));
+                let root = nodes.len() / self.states - 1;
+                Cell::from_state(inputs.fold(root, |node, s| nodes[node * self.states + s] as usize), conductor)
+            }
+        }
+    }
+
+    /// Whether empty space stays empty, so that untouched regions can be skipped
+    pub(crate) fn is_quiescent(&self) -> bool { self.next(Cell::Dead, [Cell::Dead; 8]) == Cell::Dead }
+}
+
+/// An entry of a transition: a state, or a variable standing for a set of them
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Entry<'a> {
+    State(u8),
+    Variable(&'a str),
+}
+
+/// Parses `key:value` (or `key=value`) header fields
+fn field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let rest = line.strip_prefix(key)?.trim_start();
+    rest.strip_prefix([':', '=']).map(str::trim)
+}
+
+fn parse_table(lines: &[(usize, &str)]) -> Result<(usize, Neighborhood, Lookup), ParseRuleTableError> {
+    let (mut states, mut neighborhood, mut symmetries) = (None, None, None);
+    let mut variables: HashMap<&str, Vec<u8>> = HashMap::new();
+    let mut transitions: Vec<(Vec<Vec<u8>>, u8)> = Vec::new();
+
+    for &(number, line) in lines {
+        let invalid = ParseRuleTableError::InvalidLine(number);
+        if let Some(value) = field(line, "n_states") {
+            states = Some(match value.parse::<usize>() {
+                Ok(n @ 2..=256) => n,
+                _               => return Err(ParseRuleTableError::InvalidStateCount),
+            });
+        } else if let Some(value) = field(line, "neighborhood") {
+            neighborhood = Some(match value {
+                "Moore"      => Neighborhood::Moore,
+                "vonNeumann" => Neighborhood::VonNeumann,
+                _            => return Err(ParseRuleTableError::UnsupportedNeighborhood(value.to_owned())),
+            });
+        } else if let Some(value) = field(line, "symmetries") {
+            symmetries = Some(value);
+        } else if let Some(definition) = line.strip_prefix("var ") {
+            let states = states.ok_or(ParseRuleTableError::MissingField("n_states"))?;
+            let (name, set) = definition.split_once('=').ok_or(invalid.clone())?;
+            let set = set.trim().strip_prefix('{').and_then(|s| s.strip_suffix('}')).ok_or(invalid.clone())?;
+            let mut values = Vec::new();
+            for value in set.split(',').map(str::trim) {
+                match value.parse::<u8>() {
+                    Ok(s) if (s as usize) < states => values.push(s),
+                    Ok(_)                          => return Err(invalid),
+                    Err(_)                         => values.extend(variables.get(value).ok_or(invalid.clone())?),
+                }
+            }
+            variables.insert(name.trim(), values);
+        } else {
+            let states = states.ok_or(ParseRuleTableError::MissingField("n_states"))?;
+            let neighborhood = neighborhood.ok_or(ParseRuleTableError::MissingField("neighborhood"))?;
+            let symmetries = symmetries.ok_or(ParseRuleTableError::MissingField("symmetries"))?;
+            let group = symmetry_group(neighborhood, symmetries)?;
+
+            let tokens = match line.contains(',') {
+                true  => line.split(',').map(str::trim).collect::<Vec<_>>(),
+                false => line.char_indices().filter(|(_, c)| !c.is_whitespace()).map(|(i, c)| &line[i..i + c.len_utf8()]).collect(),
+            };
+            if tokens.len() != neighborhood.size() as usize + 2 { return Err(invalid); }
+            let entries = tokens.iter().map(|&token| match token.parse::<u8>() {
+                Ok(s) if (s as usize) < states          => Ok(Entry::State(s)),
+                Err(_) if variables.contains_key(token) => Ok(Entry::Variable(token)),
+                _                                       => Err(invalid.clone()),
+            }).collect::<Result<Vec<_>, _>>()?;
+            expand(&entries, &variables, &group, &mut transitions).ok_or(invalid)?;
+        }
+    }
+
+    let states = states.ok_or(ParseRuleTableError::MissingField("n_states"))?;
+    let neighborhood = neighborhood.ok_or(ParseRuleTableError::MissingField("neighborhood"))?;
+    let positions = neighborhood.size() as usize + 1;
+    let words = transitions.len().div_ceil(64).max(1);
+    let mut masks = vec![0; positions * states * words];
+    for (t, (inputs, _)) in transitions.iter().enumerate() {
+        for (p, allowed) in inputs.iter().enumerate() {
+            for &s in allowed { masks[(p * states + s as usize) * words + t / 64] |= 1 << (t % 64); }
+        }
+    }
+    let outputs = transitions.into_iter().map(|(_, output)| output).collect();
+    Ok((states, neighborhood, Lookup::Table { masks, words, outputs }))
+}
+
+/// Permutations of the neighbors (in table order) that `symmetries` makes equivalent
+fn symmetry_group(neighborhood: Neighborhood, symmetries: &str) -> Result<Vec<Vec<usize>>, ParseRuleTableError> {
+    let n = neighborhood.size() as usize;
+    let unsupported = || ParseRuleTableError::UnsupportedSymmetries(symmetries.to_owned());
+    // Rotations go a neighbor at a time, which is 45° for Moore
+    let (rotations, reflect): (Vec<usize>, bool) = match (symmetries, n) {
+        ("none", _)              => (vec![0], false),
+        ("reflect", _)           => (vec![0], true),
+        ("rotate4", _)           => ((0..n).step_by(n / 4).collect(), false),
+        ("rotate4reflect", _)    => ((0..n).step_by(n / 4).collect(), true),
+        ("rotate8", 8)           => ((0..n).collect(), false),
+        ("rotate8reflect", 8)    => ((0..n).collect(), true),
+        ("permute", _)           => return Ok(permutations(n)),
+        _                        => return Err(unsupported()),
+    };
+
+    let mut group = Vec::new();
+    for k in rotations {
+        group.push((0..n).map(|i| (i + k) % n).collect::<Vec<_>>());
+        if reflect { group.push((0..n).map(|i| (n - i + k) % n).collect()); }
+    }
+    Ok(group)
+}
+
+fn permutations(n: usize) -> Vec<Vec<usize>> {
+    if n == 0 { return vec![Vec::new()]; }
+    let mut all = Vec::new();
+    for smaller in permutations(n - 1) {
+        for at in 0..n {
+            let mut p = smaller.clone();
+            p.insert(at, n - 1);
+            all.push(p);
+        }
+    }
+    all
+}
+
+/// Appends the transitions a table line stands for: one per value of the variables it uses more than once
+/// (which are bound, taking the same value everywhere), each in all its symmetric variants. None if the output
+/// is a variable that isn't bound.
+fn expand(entries: &[Entry], variables: &HashMap<&str, Vec<u8>>, group: &[Vec<usize>],
+          transitions: &mut Vec<(Vec<Vec<u8>>, u8)>) -> Option<()> {
+    let (inputs, output) = entries.split_at(entries.len() - 1);
+    let mut bound: Vec<&str> = Vec::new();
+    for entry in entries {
+        if let Entry::Variable(name) = *entry {
+            if entries.iter().filter(|&&e| e == Entry::Variable(name)).count() > 1 && !bound.contains(&name) { bound.push(name); }
+        }
+    }
+    if matches!(output[0], Entry::Variable(name) if !bound.contains(&name)) { return None; }
+
+    let combinations = bound.iter().map(|name| variables[name].len()).product::<usize>();
+    for mut combination in 0..combinations {
+        let mut values = HashMap::new();
+        for name in &bound {
+            let set = &variables[name];
+            values.insert(*name, set[combination % set.len()]);
+            combination /= set.len();
+        }
+        let resolve = |entry: &Entry| match *entry {
+            Entry::State(s)                                 => vec![s],
+            Entry::Variable(name) if values.contains_key(name) => vec![values[name]],
+            Entry::Variable(name)                           => variables[name].clone(),
+        };
+        let resolved = inputs.iter().map(resolve).collect::<Vec<_>>();
+        let output = resolve(&output[0])[0];
+
+        let mut seen = HashSet::new();
+        for permutation in group {
+            let mut variant = vec![resolved[0].clone()];
+            variant.extend(permutation.iter().map(|&i| resolved[1 + i].clone()));
+            if seen.insert(variant.clone()) { transitions.push((variant, output)); }
+        }
+    }
+    Some(())
+}
+
+fn parse_tree(lines: &[(usize, &str)]) -> Result<(usize, Neighborhood, Lookup), ParseRuleTableError> {
+    let (mut states, mut neighbors, mut node_count) = (None, None, None);
+    let mut levels: Vec<usize> = Vec::new();
+    let mut nodes = Vec::new();
+
+    for &(number, line) in lines {
+        let invalid = ParseRuleTableError::InvalidLine(number);
+        if let Some(value) = field(line, "num_states") {
+            states = Some(match value.parse::<usize>() {
+                Ok(n @ 2..=256) => n,
+                _               => return Err(ParseRuleTableError::InvalidStateCount),
+            });
+        } else if let Some(value) = field(line, "num_neighbors") {
+            neighbors = Some(match value {
+                "8" => Neighborhood::Moore,
+                "4" => Neighborhood::VonNeumann,
+                _   => return Err(ParseRuleTableError::UnsupportedNeighborhood(value.to_owned())),
+            });
+        } else if let Some(value) = field(line, "num_nodes") {
+            node_count = Some(value.parse::<usize>().map_err(|_| invalid)?);
+        } else {
+            let states = states.ok_or(ParseRuleTableError::MissingField("num_states"))?;
+            let numbers = line.split_whitespace().map(str::parse::<u32>).collect::<Result<Vec<_>, _>>().map_err(|_| invalid.clone())?;
+            let [level, children @ ..] = &numbers[..] else { return Err(invalid) };
+            let level = *level as usize;
+            let valid = children.len() == states && level >= 1 && children.iter().all(|&c| match level {
+                1 => (c as usize) < states,
+                _ => levels.get(c as usize) == Some(&(level - 1)),
+            });
+            if !valid { return Err(invalid); }
+            levels.push(level);
+            nodes.extend_from_slice(children);
+        }
+    }
+
+    let states = states.ok_or(ParseRuleTableError::MissingField("num_states"))?;
+    let neighborhood = neighbors.ok_or(ParseRuleTableError::MissingField("num_neighbors"))?;
+    let node_count = node_count.ok_or(ParseRuleTableError::MissingField("num_nodes"))?;
+    let last = lines.last().map_or(0, |&(number, _)| number);
+    if levels.len() != node_count || levels.last() != Some(&(neighborhood.size() as usize + 1)) {
+        return Err(ParseRuleTableError::InvalidLine(last));
+    }
+    Ok((states, neighborhood, Lookup::Tree { nodes }))
+}
+
+impl FromStr for RuleTable {
+    type Err = ParseRuleTableError;
+
+    /// Reads the `@RULE` name and the `@TABLE` or `@TREE` section, ignoring the rest (`@COLORS`, `@ICONS`...)
+    /// and anything after a `#`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut name = String::new();
+        let mut section = "";
+        let (mut table, mut tree) = (Vec::new(), Vec::new());
+
+        for (i, line) in s.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() { continue; }
+            if let Some(header) = line.strip_prefix('@') {
+                let (key, rest) = header.split_once(char::is_whitespace).unwrap_or((header, ""));
+                section = key;
+                if key == "RULE" { name = rest.trim().to_owned(); }
+                continue;
+            }
+            match section {
+                "TABLE" => table.push((i + 1, line)),
+                "TREE"  => tree.push((i + 1, line)),
+                _       => {}
+            }
+        }
+
+        let (states, neighborhood, lookup) = match (table.is_empty(), tree.is_empty()) {
+            (false, _)    => parse_table(&table)?,
+            (true, false) => parse_tree(&tree)?,
+            (true, true)  => return Err(ParseRuleTableError::MissingRule),
+        };
+        Ok(Self { name, states, neighborhood, lookup })
+    }
+}
+
+impl fmt::Display for ParseRuleTableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleTableError::MissingRule                 => write!(f, "no @TABLE or @TREE section found"),
+            ParseRuleTableError::MissingField(field)         => write!(f, "missing the {field} field"),
+            ParseRuleTableError::UnsupportedNeighborhood(n)  => write!(f, "the {n} neighborhood isn't supported"),
+            ParseRuleTableError::UnsupportedSymmetries(s)    => write!(f, "the {s} symmetries aren't supported"),
+            ParseRuleTableError::InvalidStateCount           => write!(f, "the state count must go from 2 to 256"),
+            ParseRuleTableError::InvalidLine(line)           => write!(f, "couldn't make sense of line {line}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseRuleTableError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Rule, Universe};
+
+    /// One of each way to have `live` of `n` neighbors alive, up to rotation, in table order
+    fn necklaces(n: usize, live: u32) -> Vec<String> {
+        let rotate = |mask: u32, k: usize| (mask >> k | mask << (n - k)) & ((1 << n) - 1);
+        (0..1u32 << n).filter(|&mask| mask.count_ones() == live && (1..n).all(|k| rotate(mask, k) >= mask))
+            .map(|mask| (0..n).map(|i| (mask >> i & 1).to_string()).collect::<Vec<_>>().join(","))
+            .collect()
+    }
+
+    /// A table for the totalistic rule given by its birth and survival counts, listing every case under
+    /// `symmetries` just once and leaving the rest of the live cells to die
+    fn totalistic_table(neighborhood: &str, symmetries: &str, birth: &[u32], survival: &[u32]) -> String {
+        let n = if neighborhood == "Moore" { 8 } else { 4 };
+        let mut table = format!("@RULE Totalistic\n@TABLE\nn_states:2\nneighborhood:{neighborhood}\nsymmetries:{symmetries}\n");
+        table.push_str("var a={0,1}\nvar b={0,1}\nvar c={0,1}\nvar d={0,1}\nvar e={0,1}\nvar f={0,1}\nvar g={0,1}\nvar h={0,1}\n");
+        for (center, counts) in [(0, birth), (1, survival)] {
+            for line in counts.iter().flat_map(|&live| necklaces(n, live)) { table.push_str(&format!("{center},{line},1\n")); }
+        }
+        table.push_str(&format!("1,{},0\n", ["a", "b", "c", "d", "e", "f", "g", "h"][..n].join(",")));
+        table
+    }
+
+    /// Life as a tree: down through the neighbors counting the live ones, and then the cell itself
+    fn life_tree() -> String {
+        let mut lines = Vec::new();
+        // Line number of the node at the level below for each count of live neighbors so far
+        let mut below = Vec::new();
+        for level in 1..=9 {
+            let counts = 0..=9 - level;
+            below = counts.map(|count| {
+                let children = match level {
+                    1 => [(count == 3) as usize, (count == 2 || count == 3) as usize],
+                    _ => [below[count], below[count + 1]],
+                };
+                lines.push(format!("{level} {} {}", children[0], children[1]));
+                lines.len() - 1
+            }).collect();
+        }
+        format!("@RULE Life\n@TREE\nnum_states=2\nnum_neighbors=8\nnum_nodes={}\n{}\n", lines.len(), lines.join("\n"))
+    }
+
+    /// Whether the table ticks a soup just like `rule` does
+    fn ticks_like(table: &str, rule: Rule) -> bool {
+        let mut by_rule = Universe::new(40, 50);
+        by_rule.set_rule(rule);
+        by_rule.randomize(0.4, 7);
+        let mut by_table = by_rule.clone();
+        by_table.set_rule_table(Some(table.parse().unwrap()));
+        for _ in 0..30 {
+            by_rule.tick();
+            by_table.tick();
+            if by_rule.live_cells().ne(by_table.live_cells()) { return false; }
+        }
+        true
+    }
+
+    #[test]
+    fn tables_and_trees_tick_like_life() {
+        let table = totalistic_table("Moore", "rotate8", &[3], &[2, 3]);
+        assert_eq!(table.parse::<RuleTable>().unwrap().neighborhood(), Neighborhood::Moore);
+        assert!(ticks_like(&table, Rule::CONWAY));
+        assert!(ticks_like(&life_tree(), Rule::CONWAY));
+        // Without the symmetries, the cases listed only once are missing most of their rotations
+        assert!(!ticks_like(&table.replace("rotate8", "none"), Rule::CONWAY));
+    }
+
+    #[test]
+    fn von_neumann_tables() {
+        let table = totalistic_table("vonNeumann", "rotate4", &[2], &[1, 2]);
+        let parsed = table.parse::<RuleTable>().unwrap();
+        assert_eq!((parsed.states(), parsed.neighborhood()), (2, Neighborhood::VonNeumann));
+        let rule = "B2/S12".parse::<Rule>().unwrap().with_neighborhood(Neighborhood::VonNeumann);
+        assert!(ticks_like(&table, rule));
+    }
+
+    /// Golly's `WireWorld.rule`, with copper as state 3
+    const WIREWORLD: &str = "@RULE WireWorld
+@TABLE
+n_states:4
+neighborhood:Moore
+symmetries:permute
+var a={0,1,2,3}
+var b={0,1,2,3}
+var c={0,1,2,3}
+var d={0,1,2,3}
+var e={0,1,2,3}
+var f={0,1,2,3}
+var g={0,1,2,3}
+var h={0,1,2,3}
+var i={0,2,3}
+var j={0,2,3}
+var k={0,2,3}
+var l={0,2,3}
+var m={0,2,3}
+var n={0,2,3}
+var o={0,2,3}
+1,a,b,c,d,e,f,g,h,2
+2,a,b,c,d,e,f,g,h,3
+3,1,i,j,k,l,m,n,o,1
+3,1,1,i,j,k,l,m,n,1
+";
+
+    #[test]
+    fn keeps_wireworld_copper_as_conductor() {
+        let table = WIREWORLD.parse::<RuleTable>().unwrap();
+        let mut head = [Cell::Conductor; 8];
+        head[0] = Cell::Alive;
+        assert_eq!(table.next(Cell::Conductor, head), Cell::Alive);
+        assert_eq!(table.next(Cell::Alive, head), Cell::Dying(1));
+        assert_eq!(table.next(Cell::Dying(1), head), Cell::Conductor);
+        assert_eq!(table.next(Cell::Conductor, [Cell::Conductor; 8]), Cell::Conductor);
+
+        // Under any other name, state 3 is just the one after `Dying(1)`
+        let table = WIREWORLD.replace("WireWorld", "Sparks").parse::<RuleTable>().unwrap();
+        assert_eq!(table.next(Cell::Dying(1), head), Cell::Dying(2));
+    }
+}
@@ -0,0 +1,208 @@
+use std::{collections::BTreeMap, fmt, str::FromStr};
+
+use crate::{Boundary, ParseRleError, Pattern, Symmetry, Universe};
+
+/// First line of every session file
+const HEADER: &str = "#Session";
+
+/// Everything it takes to pick a run back up where it was left: the universe's cells, rule (stochastic chances
+/// included), generation, boundaries and symmetry, how fast it was going and whatever else the frontend wants
+/// kept. Rule tables, block, custom and elementary rules, turmites and zones aren't kept.
+#[derive(Clone, Debug)]
+pub struct Session {
+    pub universe: Universe,
+    /// Seconds between generations
+    pub speed: f32,
+    pub paused: bool,
+    /// Frontend settings, by name. Values can't span several lines.
+    pub settings: BTreeMap<String, String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseSessionError {
+    /// The first line must be `#Session`
+    MissingHeader,
+    /// A `key = value` line that couldn't be made sense of, counting from 1
+    InvalidLine(usize),
+    /// The cells, in the Run Length Encoded format, after the settings
+    Cells(ParseRleError),
+}
+
+impl Session {
+    pub fn new(universe: Universe, speed: f32, paused: bool) -> Self {
+        Self { universe, speed, paused, settings: BTreeMap::new() }
+    }
+
+    pub fn get_setting(&self, key: &str) -> Option<&str>          { self.settings.get(key).map(String::as_str) }
+    pub fn set_setting(&mut self, key: &str, value: impl ToString) { self.settings.insert(key.to_owned(), value.to_string()); }
+}
+
+impl fmt::Display for Session {
+    /// `#Session`, then a `key = value` line for each setting (the frontend's ones after a `set` of their own),
+    /// and the cells as RLE
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let universe = &self.universe;
+        let (vertical, horizontal) = universe.get_boundaries();
+        writeln!(f, "{HEADER}")?;
+        writeln!(f, "generation = {}", universe.get_generation())?;
+        writeln!(f, "boundaries = {vertical:?}, {horizontal:?}")?;
+        writeln!(f, "symmetry = {:?}", universe.get_symmetry())?;
+        writeln!(f, "seed = {}", universe.get_seed())?;
+        writeln!(f, "speed = {}", self.speed)?;
+        writeln!(f, "paused = {}", self.paused)?;
+        let rule = universe.get_rule();
+        if rule.is_stochastic() {
+            let chances = |chance: &dyn Fn(u16) -> f32| (0..=8).filter(|&n| chance(n) > 0.0)
+                .map(|n| format!("{n}:{}", chance(n)))
+                .collect::<Vec<_>>()
+                .join(", ");
+            writeln!(f, "birth_chances = {}", chances(&|n| rule.birth_chance(n)))?;
+            writeln!(f, "survival_chances = {}", chances(&|n| rule.survival_chance(n)))?;
+        }
+        for (key, value) in &self.settings { writeln!(f, "set {key} = {value}")?; }
+        write!(f, "{}", Pattern::from(universe).to_rle(Some(universe.get_rule())))
+    }
+}
+
+impl FromStr for Session {
+    type Err = ParseSessionError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s.lines().enumerate();
+        if lines.next().map(|(_, line)| line.trim()) != Some(HEADER) { return Err(ParseSessionError::MissingHeader); }
+
+        let mut session = Session::new(Universe::new(0, 0), 0.0, true);
+        let (mut generation, mut boundaries, mut symmetry, mut seed) = (0, (Boundary::Toroidal, Boundary::Toroidal), Symmetry::None, 0);
+        let (mut birth_chances, mut survival_chances) = (Vec::new(), Vec::new());
+        let mut cells = String::new();
+        for (i, line) in lines.by_ref() {
+            let invalid = ParseSessionError::InvalidLine(i + 1);
+            // The cells start with their own x = ..., y = ... header
+            if line.trim_start().starts_with('x') {
+                cells = format!("{line}\n");
+                break;
+            }
+            if line.trim().is_empty() { continue; }
+            let (key, value) = line.split_once('=').ok_or(invalid.clone())?;
+            let (key, value) = (key.trim(), value.trim());
+            if let Some(setting) = key.strip_prefix("set ") {
+                session.set_setting(setting.trim(), value);
+                continue;
+            }
+            match key {
+                "generation"       => generation = value.parse().map_err(|_| invalid)?,
+                "boundaries"       => {
+                    let (vertical, horizontal) = value.split_once(',').ok_or(invalid.clone())?;
+                    boundaries = (parse_boundary(vertical).ok_or(invalid.clone())?, parse_boundary(horizontal).ok_or(invalid)?);
+                }
+                "symmetry"         => symmetry = parse_symmetry(value).ok_or(invalid)?,
+                "seed"             => seed = value.parse().map_err(|_| invalid)?,
+                "speed"            => session.speed = value.parse().map_err(|_| invalid)?,
+                "paused"           => session.paused = value.parse().map_err(|_| invalid)?,
+                "birth_chances"    => birth_chances = parse_chances(value).ok_or(invalid)?,
+                "survival_chances" => survival_chances = parse_chances(value).ok_or(invalid)?,
+                _                  => return Err(invalid),
+            }
+        }
+        for (_, line) in lines { cells.push_str(line); cells.push('\n'); }
+
+        let (pattern, rule) = Pattern::from_rle(&cells).map_err(ParseSessionError::Cells)?;
+        let universe = &mut session.universe;
+        *universe = Universe::new(pattern.get_height(), pattern.get_width());
+        if let Some(rule) = rule {
+            let rule = birth_chances.into_iter().fold(rule, |rule, (n, chance)| rule.with_birth_chance(n, chance));
+            universe.set_rule(survival_chances.into_iter().fold(rule, |rule, (n, chance)| rule.with_survival_chance(n, chance)));
+        }
+        for (c, cell) in pattern.iter_cells() { universe[c] = cell; }
+        universe.set_generation(generation);
+        universe.set_boundaries(boundaries.0, boundaries.1);
+        universe.set_symmetry(symmetry);
+        universe.set_seed(seed);
+        Ok(session)
+    }
+}
+
+/// Neighbor counts along with their chances, as in `3:0.5, 6:1`
+fn parse_chances(s: &str) -> Option<Vec<(u16, f32)>> {
+    s.split(',').filter(|chance| !chance.trim().is_empty()).map(|chance| {
+        let (neighbors, chance) = chance.split_once(':')?;
+        Some((neighbors.trim().parse().ok()?, chance.trim().parse().ok()?))
+    }).collect()
+}
+
+fn parse_boundary(s: &str) -> Option<Boundary> {
+    match s.trim() {
+        "Toroidal" => Some(Boundary::Toroidal),
+        "Dead"     => Some(Boundary::Dead),
+        "Mirror"   => Some(Boundary::Mirror),
+        _          => None,
+    }
+}
+
+fn parse_symmetry(s: &str) -> Option<Symmetry> {
+    match s.trim() {
+        "None"       => Some(Symmetry::None),
+        "Horizontal" => Some(Symmetry::Horizontal),
+        "Vertical"   => Some(Symmetry::Vertical),
+        "Rotational" => Some(Symmetry::Rotational),
+        "FourFold"   => Some(Symmetry::FourFold),
+        "EightFold"  => Some(Symmetry::EightFold),
+        _            => None,
+    }
+}
+
+impl fmt::Display for ParseSessionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseSessionError::MissingHeader     => write!(f, "the file doesn't start with {HEADER}"),
+            ParseSessionError::InvalidLine(line) => write!(f, "couldn't make sense of line {line}"),
+            ParseSessionError::Cells(e)          => write!(f, "couldn't read the cells: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseSessionError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Cell, Coord, Rule, RulePreset};
+
+    fn round_trip(universe: Universe) {
+        let mut session = Session::new(universe, 0.25, false);
+        session.set_setting("view", "-12.5, 40, 2.5");
+        let loaded = session.to_string().parse::<Session>().unwrap();
+
+        let (saved, universe) = (&session.universe, &loaded.universe);
+        assert_eq!(universe, saved);
+        assert_eq!(universe.get_rule(), saved.get_rule());
+        assert_eq!(universe.get_generation(), saved.get_generation());
+        assert_eq!(universe.get_boundaries(), saved.get_boundaries());
+        assert_eq!(universe.get_symmetry(), saved.get_symmetry());
+        assert_eq!(universe.get_seed(), saved.get_seed());
+        assert_eq!((loaded.speed, loaded.paused), (session.speed, session.paused));
+        assert_eq!(loaded.settings, session.settings);
+    }
+
+    #[test]
+    fn every_preset_loads_back() {
+        for preset in RulePreset::ALL {
+            let mut universe = Universe::new(6, 7);
+            universe.set_rule(preset.rule());
+            universe[Coord::new(1, 2)] = Cell::Alive;
+            universe[Coord::new(4, 5)] = Cell::from_state(preset.rule().states() as usize - 1, preset.rule().conductor_state());
+            universe.set_generation(42);
+            universe.set_boundaries(Boundary::Dead, Boundary::Toroidal);
+            universe.set_symmetry(Symmetry::Rotational);
+            universe.set_seed(7);
+            round_trip(universe);
+        }
+    }
+
+    #[test]
+    fn stochastic_chances_load_back() {
+        let mut universe = Universe::new(3, 3);
+        universe.set_rule(Rule::CONWAY.with_birth_chance(3, 0.5).with_birth_chance(6, 0.125).with_survival_chance(2, 0.75));
+        round_trip(universe);
+    }
+}
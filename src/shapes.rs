@@ -0,0 +1,60 @@
+use crate::Coord;
+
+/// The cells along the straight line from `from` to `to`, both ends included, by Bresenham's algorithm
+pub fn line_cells(from: Coord, to: Coord) -> Vec<Coord> {
+    let (mut y, mut x) = (from.row as isize, from.col as isize);
+    let (dy, dx) = (-(to.row.abs_diff(from.row) as isize), to.col.abs_diff(from.col) as isize);
+    let (sy, sx) = (if from.row < to.row { 1 } else { -1 }, if from.col < to.col { 1 } else { -1 });
+    let mut error = dx + dy;
+    let mut cells = Vec::with_capacity(dx.max(-dy) as usize + 1);
+    loop {
+        cells.push(Coord::new(y as usize, x as usize));
+        if (y, x) == (to.row as isize, to.col as isize) { return cells; }
+        let twice = 2 * error;
+        if twice >= dy { error += dy; x += sx; }
+        if twice <= dx { error += dx; y += sy; }
+    }
+}
+
+/// The cells of the rectangle with its opposite corners at `a` and `b`, only its edges unless `filled`
+pub fn rectangle_cells(a: Coord, b: Coord, filled: bool) -> Vec<Coord> {
+    let (top, bottom) = (a.row.min(b.row), a.row.max(b.row));
+    let (left, right) = (a.col.min(b.col), a.col.max(b.col));
+    (top..=bottom).flat_map(|row| (left..=right).map(move |col| Coord::new(row, col)))
+        .filter(|c| filled || c.row == top || c.row == bottom || c.col == left || c.col == right)
+        .collect()
+}
+
+/// The cells of the circle of `radius` around `center`, by the midpoint algorithm, only its outline unless
+/// `filled`. Those that would fall past the top or left edges are left out.
+pub fn circle_cells(center: Coord, radius: usize, filled: bool) -> Vec<Coord> {
+    let r = radius as isize;
+    let mut outline = Vec::new();
+    let (mut x, mut y, mut error) = (r, 0, 1 - r);
+    while y <= x {
+        for (dy, dx) in [(y, x), (x, y)] {
+            outline.extend([(dy, dx), (dy, -dx), (-dy, dx), (-dy, -dx)]);
+        }
+        y += 1;
+        if error < 0 {
+            error += 2 * y + 1;
+        } else {
+            x -= 1;
+            error += 2 * (y - x) + 1;
+        }
+    }
+
+    let mut offsets = match filled {
+        // Every row spans as far as its outline reaches
+        true  => (-r..=r).flat_map(|dy| {
+            let reach = outline.iter().filter(|&&(y, _)| y == dy).map(|&(_, x)| x).max().unwrap_or(0);
+            (-reach..=reach).map(move |dx| (dy, dx))
+        }).collect(),
+        false => outline,
+    };
+    offsets.sort_unstable();
+    offsets.dedup();
+    offsets.into_iter()
+        .filter_map(|(dy, dx)| Some(Coord::new(center.row.checked_add_signed(dy)?, center.col.checked_add_signed(dx)?)))
+        .collect()
+}
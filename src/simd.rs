@@ -0,0 +1,73 @@
+//! Row-at-a-time neighbor sums over 0/1 alive flags. Vectorized with SSE2 on x86_64, scalar everywhere else
+//! (and for whatever doesn't fill a whole vector).
+
+/// `out[i] = a[i] + b[i] + c[i]`
+pub(crate) fn add3(a: &[u8], b: &[u8], c: &[u8], out: &mut [u8]) {
+    let n = out.len();
+    let (a, b, c) = (&a[..n], &b[..n], &c[..n]);
+
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: SSE2 is part of the x86_64 baseline
+    let done = unsafe { sse2::add3(a, b, c, out) };
+    #[cfg(not(target_arch = "x86_64"))]
+    let done = 0;
+
+    for i in done..n { out[i] = a[i] + b[i] + c[i]; }
+}
+
+/// `out[i] = v[i] + v[i + 1] + v[i + 2] - center[i + 1]`: from the vertical sums of a padded row, each cell's
+/// Moore neighbor count
+pub(crate) fn window_sums(v: &[u8], center: &[u8], out: &mut [u8]) {
+    let n = out.len();
+    let (v, center) = (&v[..n + 2], &center[..n + 2]);
+
+    #[cfg(target_arch = "x86_64")]
+    // SAFETY: SSE2 is part of the x86_64 baseline
+    let done = unsafe { sse2::window_sums(v, center, out) };
+    #[cfg(not(target_arch = "x86_64"))]
+    let done = 0;
+
+    for i in done..n { out[i] = v[i] + v[i + 1] + v[i + 2] - center[i + 1]; }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod sse2 {
+    use std::arch::x86_64::{__m128i, _mm_add_epi8, _mm_loadu_si128, _mm_storeu_si128, _mm_sub_epi8};
+
+    const LANES: usize = 16;
+
+    /// Loads 16 bytes starting at `xs[i]`
+    fn load(xs: &[u8], i: usize) -> __m128i {
+        assert!(i + LANES <= xs.len());
+        // SAFETY: just checked that the 16 bytes are in bounds, and loadu has no alignment requirement
+        unsafe { _mm_loadu_si128(xs.as_ptr().add(i) as *const __m128i) }
+    }
+
+    fn store(xs: &mut [u8], i: usize, v: __m128i) {
+        assert!(i + LANES <= xs.len());
+        // SAFETY: as in `load`
+        unsafe { _mm_storeu_si128(xs.as_mut_ptr().add(i) as *mut __m128i, v) }
+    }
+
+    /// Returns how many leading elements were handled
+    #[target_feature(enable = "sse2")]
+    pub(super) fn add3(a: &[u8], b: &[u8], c: &[u8], out: &mut [u8]) -> usize {
+        let n = out.len() / LANES * LANES;
+        for i in (0..n).step_by(LANES) {
+            let sum = _mm_add_epi8(_mm_add_epi8(load(a, i), load(b, i)), load(c, i));
+            store(out, i, sum);
+        }
+        n
+    }
+
+    /// Returns how many leading elements were handled
+    #[target_feature(enable = "sse2")]
+    pub(super) fn window_sums(v: &[u8], center: &[u8], out: &mut [u8]) -> usize {
+        let n = out.len() / LANES * LANES;
+        for i in (0..n).step_by(LANES) {
+            let sum = _mm_add_epi8(_mm_add_epi8(load(v, i), load(v, i + 1)), load(v, i + 2));
+            store(out, i, _mm_sub_epi8(sum, load(center, i + 1)));
+        }
+        n
+    }
+}
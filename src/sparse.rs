@@ -0,0 +1,93 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{Cell, Coord, PlaneCoord, Rule, Universe};
+
+/// Unbounded universe storing only its live cells, so patterns never wrap around.
+/// Rules with B0 or decay states are not supported.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseUniverse {
+    live: HashSet<PlaneCoord>,
+    rule: Rule,
+}
+
+impl SparseUniverse {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn get_rule(&self) -> Rule                      { self.rule }
+    pub fn set_rule(&mut self, rule: Rule)              { self.rule = rule }
+    pub fn population(&self) -> usize                   { self.live.len() }
+    pub fn is_alive(&self, c: PlaneCoord) -> bool       { self.live.contains(&c) }
+    pub fn live_cells(&self) -> impl Iterator<Item = PlaneCoord> + '_ { self.live.iter().copied() }
+
+    pub fn set_pixel(&mut self, c: PlaneCoord, val: Cell) {
+        if val.is_alive() { self.live.insert(c); } else { self.live.remove(&c); }
+    }
+
+    pub fn toggle_pixel(&mut self, c: PlaneCoord) {
+        if !self.live.remove(&c) { self.live.insert(c); }
+    }
+
+    pub fn tick(&mut self) {
+        let mut neighbors: HashMap<PlaneCoord, u8> = HashMap::with_capacity(self.live.len() * 8);
+        for c in &self.live {
+            for dy in [-1, 0, 1] {
+                for dx in [-1, 0, 1] {
+                    if dx == 0 && dy == 0 { continue; }
+                    *neighbors.entry(PlaneCoord::new(c.row + dy, c.col + dx)).or_default() += 1;
+                }
+            }
+        }
+
+        let isolated_survivors = self.live.iter().copied()
+            .filter(|c| self.rule.survives_on(0) && !neighbors.contains_key(c))
+            .collect::<Vec<_>>();
+        self.live = neighbors.into_iter()
+            .filter(|&(c, n)| {
+                let current = if self.live.contains(&c) { Cell::Alive } else { Cell::Dead };
+                self.rule.next(current, n.into()).is_alive()
+            })
+            .map(|(c, _)| c)
+            .chain(isolated_survivors)
+            .collect();
+    }
+
+    /// Smallest (top-left, bottom-right) rectangle enclosing every live cell, inclusive
+    pub fn bounding_box(&self) -> Option<(PlaneCoord, PlaneCoord)> {
+        let first = *self.live.iter().next()?;
+        Some(self.live.iter().fold((first, first), |(min, max), c| (
+            PlaneCoord::new(min.row.min(c.row), min.col.min(c.col)),
+            PlaneCoord::new(max.row.max(c.row), max.col.max(c.col)),
+        )))
+    }
+
+    /// Copies a window of the plane into a dense universe
+    pub fn to_universe(&self, top_left: PlaneCoord, height: usize, width: usize) -> Universe {
+        let mut universe = Universe::new(height, width);
+        universe.set_rule(self.rule);
+        for c in &self.live {
+            let (row, col) = (c.row - top_left.row, c.col - top_left.col);
+            if (0..height as i64).contains(&row) && (0..width as i64).contains(&col) {
+                universe.set_pixel(Coord::new(row as usize, col as usize), Cell::Alive);
+            }
+        }
+        universe
+    }
+}
+
+impl From<&Universe> for SparseUniverse {
+    /// Places the universe with its top-left corner at the origin of the plane
+    fn from(universe: &Universe) -> Self {
+        let live = universe.live_cells().map(PlaneCoord::from).collect();
+        Self { live, rule: universe.get_rule() }
+    }
+}
+
+impl From<&SparseUniverse> for Universe {
+    /// Crops the plane down to the bounding box of its live cells
+    fn from(sparse: &SparseUniverse) -> Self {
+        match sparse.bounding_box() {
+            Some((min, max)) => sparse.to_universe(min, (max.row - min.row + 1) as usize, (max.col - min.col + 1) as usize),
+            None             => sparse.to_universe(PlaneCoord::new(0, 0), 0, 0),
+        }
+    }
+}
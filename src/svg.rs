@@ -0,0 +1,58 @@
+use std::fmt::Write;
+
+use crate::{Cell, Coord, Palette, Pattern, Universe};
+
+impl Pattern {
+    /// The pattern as an SVG image, every cell that isn't dead a `cell_size` wide `<rect>` in its `palette` color
+    /// over a background of dead ones, with lines of the `grid` color between cells if given. Decaying cells fade
+    /// out as if the rule had a state past the most decayed of them.
+    pub fn to_svg(&self, cell_size: f32, palette: &Palette, grid: Option<[u8; 4]>) -> String {
+        let states = self.iter_cells().filter_map(|(_, cell)| match cell {
+            Cell::Dying(k) => Some(k.saturating_add(2)),
+            _              => None,
+        }).max().unwrap_or(2);
+        svg(self, cell_size, palette, grid, states)
+    }
+}
+
+impl Universe {
+    /// The `height`×`width` region starting at `top_left` as an SVG image (see `Pattern::to_svg`), decaying cells
+    /// fading out by the rule's states
+    pub fn export_svg(&self, top_left: Coord, height: usize, width: usize, cell_size: f32, palette: &Palette,
+                      grid: Option<[u8; 4]>) -> String {
+        svg(&Pattern::from(&self.extract(top_left, height, width)), cell_size, palette, grid, self.rule.states())
+    }
+}
+
+fn svg(pattern: &Pattern, cell_size: f32, palette: &Palette, grid: Option<[u8; 4]>, states: u8) -> String {
+    let (height, width) = (pattern.get_height() as f32 * cell_size, pattern.get_width() as f32 * cell_size);
+    let mut out = format!("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+                           viewBox=\"0 0 {width} {height}\">\n");
+    if palette.dead[3] > 0 {
+        let _ = writeln!(out, "<rect width=\"{width}\" height=\"{height}\" {}/>", paint("fill", palette.dead));
+    }
+    for (c, cell) in pattern.iter_cells().filter(|(_, cell)| *cell != Cell::Dead) {
+        let (x, y) = (c.col as f32 * cell_size, c.row as f32 * cell_size);
+        let _ = writeln!(out, "<rect x=\"{x}\" y=\"{y}\" width=\"{cell_size}\" height=\"{cell_size}\" {}/>",
+                         paint("fill", palette.color(cell, states)));
+    }
+    // A single path for all the lines, inside the borders
+    if let Some(color) = grid {
+        let mut path = String::new();
+        for row in 1..pattern.get_height() { let _ = write!(path, "M0 {}H{width}", row as f32 * cell_size); }
+        for col in 1..pattern.get_width() { let _ = write!(path, "M{} 0V{height}", col as f32 * cell_size); }
+        if !path.is_empty() {
+            let _ = writeln!(out, "<path d=\"{path}\" {} stroke-width=\"{}\"/>", paint("stroke", color), cell_size / 10.0);
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// `attribute` (`fill` or `stroke`) set to `color`, with its opacity if it's translucent
+fn paint(attribute: &str, [r, g, b, a]: [u8; 4]) -> String {
+    match a {
+        255 => format!("{attribute}=\"#{r:02x}{g:02x}{b:02x}\""),
+        _   => format!("{attribute}=\"#{r:02x}{g:02x}{b:02x}\" {attribute}-opacity=\"{:.3}\"", a as f32 / 255.0),
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{Coord, Universe};
+
+/// Axes edits get mirrored across, see `Universe::set_symmetry`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Symmetry {
+    #[default]
+    None,
+    /// Mirrored across the horizontal axis (top to bottom)
+    Horizontal,
+    /// Mirrored across the vertical axis (left to right)
+    Vertical,
+    /// Half a turn around the center
+    Rotational,
+    /// Both axes
+    FourFold,
+    /// Both axes and both diagonals. On a non-square universe the diagonal images that fall outside are dropped.
+    EightFold,
+}
+
+impl Universe {
+    /// Everything an edit at `c` should also touch under the current symmetry, `c` itself first and without
+    /// repeats
+    pub fn symmetric_images(&self, c: Coord) -> Vec<Coord> {
+        let (h, w) = (self.get_height(), self.get_width());
+        let flip_rows = |c: Coord| Coord::new(h - 1 - c.row, c.col);
+        let flip_cols = |c: Coord| Coord::new(c.row, w - 1 - c.col);
+        // Transposes around the center, working on doubled coordinates so that half cells stay exact
+        let transpose = |c: Coord| {
+            let row = (h as isize - 1) + (2 * c.col as isize - (w as isize - 1));
+            let col = (w as isize - 1) + (2 * c.row as isize - (h as isize - 1));
+            let fits = |x: isize, len: usize| x % 2 == 0 && (0..len as isize).contains(&(x / 2));
+            (fits(row, h) && fits(col, w)).then(|| Coord::new(row as usize / 2, col as usize / 2))
+        };
+
+        let mut images = vec![c];
+        match self.get_symmetry() {
+            Symmetry::None       => {}
+            Symmetry::Horizontal => images.push(flip_rows(c)),
+            Symmetry::Vertical   => images.push(flip_cols(c)),
+            Symmetry::Rotational => images.push(flip_rows(flip_cols(c))),
+            Symmetry::FourFold   => images.extend([flip_rows(c), flip_cols(c), flip_rows(flip_cols(c))]),
+            Symmetry::EightFold  => {
+                images.extend([flip_rows(c), flip_cols(c), flip_rows(flip_cols(c))]);
+                let diagonal = images.iter().filter_map(|&c| transpose(c)).collect::<Vec<_>>();
+                images.extend(diagonal);
+            }
+        }
+
+        let mut unique = Vec::with_capacity(images.len());
+        for image in images {
+            if !unique.contains(&image) { unique.push(image); }
+        }
+        unique
+    }
+}
@@ -0,0 +1,108 @@
+use crate::{Cell, Coord, Universe};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Heading {
+    /// Towards row 0
+    #[default]
+    North,
+    East,
+    South,
+    West,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Turn {
+    None,
+    Right,
+    UTurn,
+    Left,
+}
+
+impl Heading {
+    fn turn(self, turn: Turn) -> Heading {
+        let quarters = match turn { Turn::None => 0, Turn::Right => 1, Turn::UTurn => 2, Turn::Left => 3 };
+        [Heading::North, Heading::East, Heading::South, Heading::West][(self as usize + quarters) % 4]
+    }
+
+    /// (row, column) step
+    fn delta(self) -> (isize, isize) {
+        match self {
+            Heading::North => (-1, 0),
+            Heading::East  => (0, 1),
+            Heading::South => (1, 0),
+            Heading::West  => (0, -1),
+        }
+    }
+}
+
+/// What a turmite does on a cell: write it, turn, and switch to another of its states
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub write: Cell,
+    pub turn: Turn,
+    pub next_state: u8,
+}
+
+/// Agent walking over the grid. Each step it looks up the move for its state and whether the cell under it is
+/// alive, applies it, and then walks one cell forward.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Turmite {
+    pub position: Coord,
+    pub heading: Heading,
+    pub state: u8,
+    /// Indexed by state, then by the cell being dead (0) or alive (1)
+    table: Vec<[Move; 2]>,
+}
+
+impl Turmite {
+    /// `table` needs a pair of moves (on a dead cell, on a live one) for every state the turmite can get to
+    pub fn new(position: Coord, heading: Heading, table: Vec<[Move; 2]>) -> Self {
+        Self { position, heading, state: 0, table }
+    }
+
+    /// Langton's ant: turns right on dead cells and left on live ones, flipping each as it leaves
+    pub fn langtons_ant(position: Coord) -> Self {
+        let flip = |write, turn| Move { write, turn, next_state: 0 };
+        Self::new(position, Heading::North, vec![[flip(Cell::Alive, Turn::Right), flip(Cell::Dead, Turn::Left)]])
+    }
+
+    pub fn get_table(&self) -> &[[Move; 2]] { &self.table }
+}
+
+impl Universe {
+    pub fn turmites(&self) -> &[Turmite] { &self.turmites }
+
+    /// Adds an agent, returning its index. Turmites step along with every `tick`, after the cells do.
+    pub fn add_turmite(&mut self, turmite: Turmite) -> usize {
+        self.turmites.push(turmite);
+        self.turmites.len() - 1
+    }
+
+    pub fn remove_turmite(&mut self, index: usize) -> Option<Turmite> {
+        (index < self.turmites.len()).then(|| self.turmites.remove(index))
+    }
+
+    pub fn clear_turmites(&mut self) { self.turmites.clear() }
+
+    /// Moves every turmite once, leaving the rest of the cells alone (so they can run instead of the rule).
+    /// Turmites walking into a dead boundary stay where they are, and ones in a state missing from their table
+    /// stop altogether.
+    pub fn tick_turmites(&mut self) {
+        let mut turmites = std::mem::take(&mut self.turmites);
+        for turmite in turmites.iter_mut() {
+            let here = turmite.position;
+            let Some(moves) = turmite.table.get(turmite.state as usize) else { continue };
+            let Move { write, turn, next_state } = moves[self.is_alive(here) as usize];
+
+            self[here] = write;
+            turmite.state = next_state;
+            turmite.heading = turmite.heading.turn(turn);
+            let (dy, dx) = turmite.heading.delta();
+            let (vertical, horizontal) = self.get_boundaries();
+            let row = vertical.resolve(here.row as isize + dy, self.get_height());
+            let col = horizontal.resolve(here.col as isize + dx, self.get_width());
+            if let (Some(row), Some(col)) = (row, col) { turmite.position = Coord::new(row, col); }
+        }
+        self.turmites = turmites;
+    }
+}
@@ -0,0 +1,109 @@
+use crate::{rng::SplitMix64, Boundary, Cell, Coord, Universe};
+
+/// Rule for 3D life, over the 26 cells around each one. Bays' naming goes by the survival range and then the
+/// birth range, so `LIFE_4555` survives on 4 or 5 neighbors and is born on 5.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Rule3D {
+    birth: u32,
+    survival: u32,
+}
+
+impl Rule3D {
+    pub const LIFE_4555: Rule3D = Rule3D { birth: 1 << 5, survival: 1 << 4 | 1 << 5 };
+    pub const LIFE_5766: Rule3D = Rule3D { birth: 1 << 6, survival: 1 << 5 | 1 << 6 | 1 << 7 };
+
+    pub fn new(birth: &[u8], survival: &[u8]) -> Self {
+        let mask = |counts: &[u8]| counts.iter().filter(|&&n| n <= 26).fold(0, |m, &n| m | 1 << n);
+        Self { birth: mask(birth), survival: mask(survival) }
+    }
+
+    pub fn births_on(&self, neighbors: u8) -> bool   { self.birth    >> neighbors & 1 == 1 }
+    pub fn survives_on(&self, neighbors: u8) -> bool { self.survival >> neighbors & 1 == 1 }
+}
+
+impl Default for Rule3D {
+    fn default() -> Self { Rule3D::LIFE_4555 }
+}
+
+/// Stack of same-sized layers, each cell counting the 26 around it across its own layer and the two next to it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Universe3D {
+    /// Layer by layer, each one row by row
+    cells: Vec<Cell>,
+    depth: usize,
+    height: usize,
+    width: usize,
+    boundary: Boundary,
+    rule: Rule3D,
+    generation: u64,
+}
+
+impl Universe3D {
+    pub fn new(depth: usize, height: usize, width: usize) -> Self {
+        Self { cells: vec![Cell::Dead; depth * height * width], depth, height, width,
+               boundary: Boundary::default(), rule: Rule3D::default(), generation: 0 }
+    }
+
+    pub fn get_depth(&self) -> usize               { self.depth }
+    pub fn get_height(&self) -> usize              { self.height }
+    pub fn get_width(&self) -> usize               { self.width }
+    pub fn get_boundary(&self) -> Boundary         { self.boundary }
+    pub fn set_boundary(&mut self, b: Boundary)    { self.boundary = b }
+    pub fn get_rule(&self) -> Rule3D               { self.rule }
+    pub fn set_rule(&mut self, rule: Rule3D)       { self.rule = rule }
+    pub fn get_generation(&self) -> u64            { self.generation }
+    pub fn population(&self) -> usize              { self.cells.iter().filter(|c| c.is_alive()).count() }
+
+    pub fn is_alive(&self, layer: usize, c: Coord) -> bool      { self.cells[self.idx(layer, c)].is_alive() }
+    pub fn set_pixel(&mut self, layer: usize, c: Coord, val: Cell) { let i = self.idx(layer, c); self.cells[i] = val; }
+    pub fn toggle_pixel(&mut self, layer: usize, c: Coord)      { let i = self.idx(layer, c); self.cells[i] = !self.cells[i]; }
+
+    /// Copy of one layer as a flat universe, e.g. to draw it
+    pub fn slice(&self, layer: usize) -> Universe {
+        let mut universe = Universe::new(self.height, self.width);
+        universe.set_boundary(self.boundary);
+        let layer_len = self.height * self.width;
+        universe.cells.copy_from_slice(&self.cells[layer * layer_len..(layer + 1) * layer_len]);
+        universe
+    }
+
+    /// See `Universe::randomize`
+    pub fn randomize(&mut self, density: f32, seed: u64) {
+        let mut rng = SplitMix64::new(seed);
+        for cell in self.cells.iter_mut() {
+            *cell = if rng.next_f32() < density { Cell::Alive } else { Cell::Dead };
+        }
+    }
+
+    /// The 3×3×3 neighbor sums are separable, so they're added up one axis at a time
+    pub fn tick(&mut self) {
+        let (d, h, w) = (self.depth, self.height, self.width);
+        if d * h * w == 0 { return; }
+
+        let alive = self.cells.iter().map(|c| c.is_alive() as u8).collect::<Vec<_>>();
+        let along_x = self.sum_along(&alive, 1, w);
+        let along_y = self.sum_along(&along_x, w, h);
+        let around = self.sum_along(&along_y, w * h, d);
+
+        for ((cell, &sum), &me) in self.cells.iter_mut().zip(&around).zip(&alive) {
+            let neighbors = sum - me;
+            *cell = match me == 1 {
+                true if self.rule.survives_on(neighbors) => Cell::Alive,
+                false if self.rule.births_on(neighbors)  => Cell::Alive,
+                _                                        => Cell::Dead,
+            };
+        }
+        self.generation += 1;
+    }
+
+    /// Each value plus the ones right before and after it along one axis, `stride` apart and `len` long
+    fn sum_along(&self, values: &[u8], stride: usize, len: usize) -> Vec<u8> {
+        values.iter().enumerate().map(|(i, &v)| {
+            let pos = (i / stride % len) as isize;
+            let at = |p: isize| self.boundary.resolve(p, len).map_or(0, |p| values[i - pos as usize * stride + p * stride]);
+            at(pos - 1) + v + at(pos + 1)
+        }).collect()
+    }
+
+    fn idx(&self, layer: usize, c: Coord) -> usize { (layer * self.height + c.row) * self.width + c.col }
+}
@@ -0,0 +1,117 @@
+use std::{io::{self, Write}, path::Path, process::{Child, Command, Stdio}};
+
+use crate::{Cell, Coord, Palette, Universe};
+
+/// Streams generations of a universe as an uncompressed YUV4MPEG2 (`.y4m`) video, to any writer or to an `ffmpeg`
+/// child process encoding it into whatever format its output path asks for
+pub struct VideoExporter {
+    out: Box<dyn Write>,
+    /// The `ffmpeg` process `out` feeds, if any
+    child: Option<Child>,
+    width: usize,
+    height: usize,
+    palette: Palette,
+    /// Generations between frames
+    every: u64,
+    transposed: bool,
+    frames: u64,
+    last_generation: Option<u64>,
+}
+
+impl VideoExporter {
+    /// Writes a `width`×`height` pixels video playing at `fps` frames a second to `out`, with every universe
+    /// scaled to fit in the middle and its cells in their `palette` colors (blended over dead cells by their
+    /// alpha). Both sizes are rounded up to even, as most codecs want.
+    pub fn new(out: impl Write + 'static, width: usize, height: usize, fps: u32, palette: Palette) -> io::Result<Self> {
+        let (width, height) = (width.max(2).next_multiple_of(2), height.max(2).next_multiple_of(2));
+        let mut out: Box<dyn Write> = Box::new(io::BufWriter::new(out));
+        writeln!(out, "YUV4MPEG2 W{width} H{height} F{}:1 Ip A1:1 C444", fps.max(1))?;
+        Ok(Self { out, child: None, width, height, palette, every: 1, transposed: false, frames: 0, last_generation: None })
+    }
+
+    /// Like `new`, but encoded by `ffmpeg` (which must be on the `PATH`) into `path`, such as an `.mp4` or a
+    /// `.webm`, overwriting it
+    pub fn ffmpeg(path: impl AsRef<Path>, width: usize, height: usize, fps: u32, palette: Palette) -> io::Result<Self> {
+        let mut child = Command::new("ffmpeg")
+            .args(["-loglevel", "error", "-y", "-f", "yuv4mpegpipe", "-i", "-", "-pix_fmt", "yuv420p"])
+            .arg(path.as_ref())
+            .stdin(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or_else(|| io::Error::other("ffmpeg's stdin wasn't piped"))?;
+        let mut exporter = Self::new(stdin, width, height, fps, palette)?;
+        exporter.child = Some(child);
+        Ok(exporter)
+    }
+
+    pub fn get_every(&self) -> u64              { self.every }
+    /// Only captures the generations that are a multiple of `every`
+    pub fn set_every(&mut self, every: u64)     { self.every = every.max(1) }
+    pub fn is_transposed(&self) -> bool         { self.transposed }
+    /// Swaps rows and columns, for frontends drawing rows across instead of down
+    pub fn set_transposed(&mut self, t: bool)   { self.transposed = t }
+    pub fn frame_count(&self) -> u64            { self.frames }
+
+    /// Writes a frame of the universe, unless its generation isn't one to capture or was just captured
+    pub fn capture(&mut self, universe: &Universe) -> io::Result<()> {
+        let generation = universe.get_generation();
+        if !generation.is_multiple_of(self.every) || self.last_generation == Some(generation) { return Ok(()); }
+        self.last_generation = Some(generation);
+
+        let (rows, cols) = match self.transposed {
+            false => (universe.get_height(), universe.get_width()),
+            true  => (universe.get_width(), universe.get_height()),
+        };
+        // Pixels a cell takes, and the margins around the universe once scaled
+        let scale = (self.width as f32 / cols.max(1) as f32).min(self.height as f32 / rows.max(1) as f32);
+        let (left, top) = ((self.width as f32 - cols as f32 * scale) / 2.0, (self.height as f32 - rows as f32 * scale) / 2.0);
+        let cell_at = |pixel: usize, margin: f32, cells: usize| {
+            let cell = ((pixel as f32 + 0.5 - margin) / scale).floor();
+            (cell >= 0.0 && (cell as usize) < cells).then_some(cell as usize)
+        };
+        let cells_y = (0..self.height).map(|y| cell_at(y, top, rows)).collect::<Vec<_>>();
+        let cells_x = (0..self.width).map(|x| cell_at(x, left, cols)).collect::<Vec<_>>();
+
+        let states = universe.get_rule().states();
+        let dead = yuv(self.palette.dead, self.palette.dead);
+        let color = |cell: Cell| yuv(self.palette.color(cell, states), self.palette.dead);
+        let mut planes = vec![0; self.width * self.height * 3];
+        let plane = self.width * self.height;
+        for (y, row) in cells_y.iter().enumerate() {
+            for (x, col) in cells_x.iter().enumerate() {
+                let pixel = match (*row, *col) {
+                    (Some(row), Some(col)) if self.transposed => color(universe[Coord::new(col, row)]),
+                    (Some(row), Some(col))                    => color(universe[Coord::new(row, col)]),
+                    _                                         => dead,
+                };
+                let i = y * self.width + x;
+                (planes[i], planes[plane + i], planes[2 * plane + i]) = (pixel[0], pixel[1], pixel[2]);
+            }
+        }
+        self.out.write_all(b"FRAME\n")?;
+        self.out.write_all(&planes)?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Flushes the frames left and, if encoding through `ffmpeg`, waits for it to be done
+    pub fn finish(mut self) -> io::Result<()> {
+        self.out.flush()?;
+        // Closing its input is what lets ffmpeg know there's nothing left
+        drop(self.out);
+        match self.child.take().map(|mut child| child.wait()).transpose()? {
+            Some(status) if !status.success() => Err(io::Error::other(format!("ffmpeg exited with {status}"))),
+            _                                 => Ok(()),
+        }
+    }
+}
+
+/// Studio swing BT.601 Y, Cb and Cr of `color`, blended over `background` by its alpha
+fn yuv(color: [u8; 4], background: [u8; 4]) -> [u8; 3] {
+    let alpha = color[3] as f32 / 255.0;
+    let [r, g, b] = [0, 1, 2].map(|c| background[c] as f32 * (1.0 - alpha) + color[c] as f32 * alpha);
+    [
+        16.0 + (65.738 * r + 129.057 * g + 25.064 * b) / 256.0,
+        128.0 + (-37.945 * r - 74.494 * g + 112.439 * b) / 256.0,
+        128.0 + (112.439 * r - 94.154 * g - 18.285 * b) / 256.0,
+    ].map(|v| v.round().clamp(0.0, 255.0) as u8)
+}
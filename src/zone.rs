@@ -0,0 +1,109 @@
+use crate::{Cell, Coord, Neighborhood, Rule, Universe};
+
+/// Rectangle of the universe running by a rule of its own
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Zone {
+    pub top_left: Coord,
+    /// (height, width)
+    pub size: Coord,
+    pub rule: Rule,
+}
+
+/// What cells next to the edge of a zone make of the cells across it
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum ZoneBorder {
+    /// They count as neighbors like any other, so patterns can cross over
+    #[default]
+    Open,
+    /// They count as dead, walling every zone (and the rest of the universe) off from the others
+    Closed,
+}
+
+impl Zone {
+    pub fn new(top_left: Coord, size: Coord, rule: Rule) -> Self { Self { top_left, size, rule } }
+
+    pub fn contains(&self, c: Coord) -> bool {
+        (self.top_left.row..self.top_left.row + self.size.row).contains(&c.row)
+            && (self.top_left.col..self.top_left.col + self.size.col).contains(&c.col)
+    }
+}
+
+impl Universe {
+    pub fn zones(&self) -> &[Zone]                   { &self.zones }
+    pub fn get_zone_border(&self) -> ZoneBorder      { self.zone_border }
+    pub fn set_zone_border(&mut self, b: ZoneBorder) { self.zone_border = b; self.mark_all_dirty(); }
+
+    /// Adds a zone, returning its index. Where zones overlap the last one added wins, and cells outside every
+    /// zone keep going by the universe's rule. Zones only count while that's a `Rule`: they're left out under
+    /// rule tables, block, custom and elementary rules.
+    pub fn add_zone(&mut self, zone: Zone) -> usize {
+        self.zones.push(zone);
+        self.mark_all_dirty();
+        self.zones.len() - 1
+    }
+
+    pub fn remove_zone(&mut self, index: usize) -> Option<Zone> {
+        self.mark_all_dirty();
+        (index < self.zones.len()).then(|| self.zones.remove(index))
+    }
+
+    pub fn clear_zones(&mut self) {
+        self.zones.clear();
+        self.mark_all_dirty();
+    }
+
+    /// Rule the cell goes by, that of the last zone holding it if any
+    pub fn rule_at(&self, c: Coord) -> Rule {
+        self.zones.iter().rev().find(|zone| zone.contains(c)).map_or(self.rule, |zone| zone.rule)
+    }
+
+    /// After the whole universe has been ticked into `next` by its own rule, ticks again the cells that go by
+    /// another one, or that may not see some of their neighbors across a closed border
+    pub(crate) fn tick_zones(&self, next: &mut [Cell]) {
+        // Owner of every cell, 0 being the universe itself and zone `z` being `z + 1`
+        let mut owners = vec![0; self.cells.len()];
+        for (z, zone) in self.zones.iter().enumerate() {
+            for row in zone.top_left.row..(zone.top_left.row + zone.size.row).min(self.height) {
+                for col in zone.top_left.col..(zone.top_left.col + zone.size.col).min(self.width) {
+                    owners[row * self.width + col] = z + 1;
+                }
+            }
+        }
+
+        let closed = self.zone_border == ZoneBorder::Closed;
+        let rules = std::iter::once(self.rule).chain(self.zones.iter().map(|zone| zone.rule)).collect::<Vec<_>>();
+        // Hex neighborhoods differ between even and odd rows
+        let offsets = rules.iter().map(|rule| [0, 1].map(|row| rule.neighborhood().all_offsets(row))).collect::<Vec<_>>();
+        let mut neighbors = Vec::new();
+
+        for (i, &owner) in owners.iter().enumerate() {
+            let (rule, c) = (rules[owner], self.idx_to_coords(i));
+            neighbors.clear();
+            neighbors.extend(offsets[owner][c.row % 2].iter().map(|&(dy, dx)| {
+                let row = self.boundary.resolve(c.row as isize + dy, self.height);
+                let col = self.col_boundary.resolve(c.col as isize + dx, self.width);
+                row.zip(col).map(|(row, col)| row * self.width + col)
+            }));
+            // The universe's own cells came out right already, unless they're walled off from some neighbor
+            if owner == 0 && (!closed || neighbors.iter().flatten().all(|&j| owners[j] == 0)) { continue; }
+
+            let current = self.cells[i];
+            let state = |j: &Option<usize>| match *j {
+                Some(j) if !closed || owners[j] == owner => self.cells[j],
+                _                                        => Cell::Dead,
+            };
+            next[i] = if rule.is_cyclic() {
+                let successor = rule.successor(current);
+                rule.next_cyclic(current, neighbors.iter().filter(|j| state(j) == successor).count() as u16)
+            } else if !rule.is_totalistic() && rule.neighborhood() == Neighborhood::Moore {
+                let configuration = neighbors.iter().enumerate()
+                    .filter(|(_, j)| state(j).is_alive())
+                    .fold(0, |bits, (bit, _)| bits | 1 << bit);
+                rule.next_configuration(current, configuration, self.roll_by(&rule, i))
+            } else {
+                let alive = neighbors.iter().filter(|j| state(j).is_alive()).count() as u16;
+                rule.next_rolled(current, alive, self.roll_by(&rule, i))
+            };
+        }
+    }
+}